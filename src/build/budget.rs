@@ -0,0 +1,198 @@
+//! Token and spend limits for the build loop.
+//!
+//! Limits come from config and are checked after each iteration. Spend is the
+//! cost recorded from the pricing table, so a build stops cleanly between
+//! iterations once a ceiling is crossed rather than killing Claude mid-task.
+//! Timed-out and failed iterations add what they used before stopping, so
+//! retries count toward the ceilings too.
+
+use crate::config::Config;
+use crate::pricing::format_cost;
+
+use super::tokens::{IterationTokens, TokenUsage};
+
+/// Spending limits for a build. `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Budget {
+    /// Ceiling on total tokens (all kinds) across the build.
+    pub max_total_tokens: Option<u64>,
+    /// Ceiling on estimated spend across the build.
+    pub max_cost_usd: Option<f64>,
+    /// Tokens that stop the build once a single iteration reaches them.
+    ///
+    /// Checked once the iteration has finished, like the other limits; it does
+    /// not interrupt the iteration that crosses it.
+    pub max_iteration_tokens: Option<u64>,
+}
+
+impl Budget {
+    /// Read limits from config.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_total_tokens: config.max_total_tokens,
+            max_cost_usd: config.max_cost_usd,
+            max_iteration_tokens: config.max_iteration_tokens,
+        }
+    }
+
+    /// Write these limits into a config.
+    pub fn apply_to(&self, config: &mut Config) {
+        config.max_total_tokens = self.max_total_tokens;
+        config.max_cost_usd = self.max_cost_usd;
+        config.max_iteration_tokens = self.max_iteration_tokens;
+    }
+
+    /// Limits left after `spent` has been used (e.g., by an eval's planning phase).
+    ///
+    /// The per-iteration cap is unchanged.
+    pub fn remaining_after(&self, spent: &TokenUsage) -> Self {
+        Self {
+            max_total_tokens: self
+                .max_total_tokens
+                .map(|max| max.saturating_sub(spent.total())),
+            max_cost_usd: self.max_cost_usd.map(|max| (max - spent.cost_usd).max(0.0)),
+            max_iteration_tokens: self.max_iteration_tokens,
+        }
+    }
+
+    /// Check spend against the limits.
+    ///
+    /// # Returns
+    ///
+    /// A description of the first limit crossed, or None if within budget.
+    pub fn exceeded(
+        &self,
+        total: &TokenUsage,
+        last_iteration: Option<&IterationTokens>,
+    ) -> Option<String> {
+        if let (Some(max), Some(iter)) = (self.max_iteration_tokens, last_iteration) {
+            if iter.total() >= max {
+                return Some(format!(
+                    "iteration {} used {} tokens (max_iteration_tokens = {})",
+                    iter.iteration,
+                    iter.total(),
+                    max
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_total_tokens {
+            if total.total() >= max {
+                return Some(format!(
+                    "{} total tokens used (max_total_tokens = {})",
+                    total.total(),
+                    max
+                ));
+            }
+        }
+
+        if let Some(max) = self.max_cost_usd {
+            if total.cost_usd >= max {
+                return Some(format!(
+                    "spend {} (max_cost_usd = {})",
+                    format_cost(total.cost_usd),
+                    format_cost(max)
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u64, output: u64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            output_tokens: output,
+            ..Default::default()
+        }
+    }
+
+    fn spent(cost_usd: f64) -> TokenUsage {
+        TokenUsage {
+            cost_usd,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unlimited_budget_never_exceeded() {
+        let budget = Budget::default();
+        assert!(budget
+            .exceeded(&usage(u64::MAX / 4, u64::MAX / 4), None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_total_tokens_ceiling() {
+        let budget = Budget {
+            max_total_tokens: Some(1000),
+            ..Default::default()
+        };
+        assert!(budget.exceeded(&usage(400, 500), None).is_none());
+        let reason = budget.exceeded(&usage(500, 500), None).expect("at ceiling");
+        assert!(reason.contains("max_total_tokens"));
+    }
+
+    #[test]
+    fn test_cost_ceiling() {
+        let budget = Budget {
+            max_cost_usd: Some(1.0),
+            ..Default::default()
+        };
+        let reason = budget.exceeded(&spent(1.5), None).expect("over");
+        assert!(reason.contains("$1.50"), "reason: {}", reason);
+        assert!(budget.exceeded(&spent(0.75), None).is_none());
+    }
+
+    #[test]
+    fn test_iteration_ceiling() {
+        let budget = Budget {
+            max_iteration_tokens: Some(100),
+            ..Default::default()
+        };
+        let iter = IterationTokens {
+            iteration: 3,
+            input_tokens: 80,
+            output_tokens: 30,
+            ..Default::default()
+        };
+        let reason = budget
+            .exceeded(&usage(0, 0), Some(&iter))
+            .expect("iteration over cap");
+        assert!(reason.starts_with("iteration 3 used 110 tokens"));
+
+        // Like the totals, reaching the ceiling is enough
+        let at_ceiling = IterationTokens {
+            output_tokens: 20,
+            ..iter.clone()
+        };
+        assert!(budget.exceeded(&usage(0, 0), Some(&at_ceiling)).is_some());
+        let below = IterationTokens {
+            output_tokens: 19,
+            ..iter
+        };
+        assert!(budget.exceeded(&usage(0, 0), Some(&below)).is_none());
+    }
+
+    #[test]
+    fn test_remaining_after() {
+        let budget = Budget {
+            max_total_tokens: Some(1000),
+            max_cost_usd: Some(1.0),
+            max_iteration_tokens: Some(500),
+        };
+        let remaining = budget.remaining_after(&TokenUsage {
+            output_tokens: 10_000,
+            cost_usd: 0.15,
+            ..Default::default()
+        });
+        assert_eq!(remaining.max_total_tokens, Some(0));
+        assert!((remaining.max_cost_usd.unwrap() - 0.85).abs() < 1e-9);
+        assert_eq!(remaining.max_iteration_tokens, Some(500));
+    }
+}
@@ -5,16 +5,25 @@
 
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 
 use crate::config::Config;
 use crate::error::RslphError;
+use crate::pricing::format_cost;
 use crate::progress::ProgressFile;
 use crate::prompts::PromptMode;
 
+use super::events::{BuildEvent, EventSink};
+use super::hooks::run_on_done;
 use super::iteration::run_single_iteration;
+use super::journal::BuildJournal;
+use super::notify::{notify, notify_iteration, NotifyEvent};
+use super::pause::{format_paused, wait_while_paused};
+use super::stall::{StallPolicy, StallTracker};
 use super::state::{BuildContext, BuildState, DoneReason, IterationResult};
-use super::tokens::TokenUsage;
+use super::tokens::{format_tokens, TokenUsage};
+use super::transcript::start_archive;
 
 /// Callback type for reporting build iteration progress.
 /// Parameters: (current_iteration, max_iterations)
@@ -30,11 +39,13 @@ pub type ProgressCallback = Arc<dyn Fn(u32, u32) + Send + Sync>;
 /// * `progress_path` - Path to the progress.md file
 /// * `once` - If true, run only one iteration
 /// * `dry_run` - If true, preview what would be done without executing
+/// * `resume` - If true, continue the most recent run from its build journal
 /// * `mode` - The prompt mode to use for this build
 /// * `no_dsp` - If true, append --dangerously-skip-permissions to Claude
 /// * `config` - Application configuration
 /// * `cancel_token` - Token for graceful cancellation
 /// * `progress_callback` - Optional callback for iteration progress updates
+/// * `events` - Optional machine-readable event stream (`--events`)
 ///
 /// # Returns
 ///
@@ -45,17 +56,20 @@ pub async fn run_build_command(
     progress_path: PathBuf,
     once: bool,
     dry_run: bool,
+    resume: bool,
     mode: PromptMode,
     no_dsp: bool,
     config: &Config,
     cancel_token: CancellationToken,
     progress_callback: Option<ProgressCallback>,
+    events: Option<EventSink>,
 ) -> color_eyre::Result<TokenUsage> {
     // Load initial progress file
     let progress = ProgressFile::load(&progress_path)?;
 
-    // Determine if TUI should be used
-    let use_tui = config.tui_enabled && !dry_run;
+    // Determine if TUI should be used; events on stdout need the terminal
+    let use_tui =
+        config.tui_enabled && !dry_run && !events.as_ref().is_some_and(EventSink::is_stdout);
 
     if !use_tui {
         println!("Build started: {}", progress_path.display());
@@ -77,6 +91,7 @@ pub async fn run_build_command(
         dry_run,
         no_dsp,
     );
+    ctx.events = events.clone();
 
     // Dry-run mode: preview and exit
     if dry_run {
@@ -90,16 +105,21 @@ pub async fn run_build_command(
         return run_build_with_tui(
             progress_path,
             ctx.progress.clone(),
+            resume,
             mode,
             no_dsp,
             config,
             cancel_token,
+            events,
         )
         .await;
     }
 
     // Main iteration loop with state machine
-    let mut state = BuildState::Starting;
+    let mut state = start_journal(&mut ctx, resume)?;
+    state = stop_if_over_budget(&ctx, state);
+    ctx.record_state(&state);
+    emit_build_started(&ctx);
 
     loop {
         state = match state {
@@ -128,7 +148,13 @@ pub async fn run_build_command(
                     Ok(IterationResult::Timeout) => {
                         // Handle timeout with retry
                         ctx.timeout_retry_count += 1;
-                        if ctx.timeout_retry_count >= ctx.config.timeout_retries {
+                        if let Some(limit) = ctx.budget_exceeded() {
+                            // The timed-out run's partial spend counts too
+                            ctx.log(&format!("[BUILD] Budget exhausted: {}", limit));
+                            BuildState::Done {
+                                reason: DoneReason::BudgetExhausted,
+                            }
+                        } else if ctx.timeout_retry_count >= ctx.config.timeout_retries {
                             ctx.log(&format!(
                                 "[BUILD] Iteration {} timed out {} times, failing",
                                 iteration, ctx.timeout_retry_count
@@ -175,12 +201,27 @@ pub async fn run_build_command(
                     ctx.progress.completed_tasks(),
                     ctx.progress.total_tasks()
                 ));
+                if let Some(iter) = ctx.iteration_tokens.last() {
+                    ctx.log(&format!(
+                        "[BUILD] Cost: {} this iteration, {} total",
+                        format_cost(iter.cost_usd),
+                        format_cost(ctx.total_tokens.cost_usd)
+                    ));
+                }
 
                 // Log to progress file
                 log_iteration(&mut ctx, iteration, tasks_completed)?;
+                notify_iteration(&ctx, iteration).await;
 
                 // Check termination conditions in priority order
-                if ctx.once_mode {
+                if let Some(limit) = ctx.budget_exceeded() {
+                    ctx.log(&format!("[BUILD] Budget exhausted: {}", limit));
+                    BuildState::Done {
+                        reason: DoneReason::BudgetExhausted,
+                    }
+                } else if let Some(reason) = handle_stall(&mut ctx, iteration, tasks_completed)? {
+                    BuildState::Done { reason }
+                } else if ctx.once_mode {
                     BuildState::Done {
                         reason: DoneReason::SingleIterationComplete,
                     }
@@ -215,10 +256,17 @@ pub async fn run_build_command(
 
             BuildState::Done { reason } => {
                 print_completion_message(&reason, &ctx);
+                emit_done(&ctx, &reason);
+                run_on_done(&ctx, &reason).await;
+                notify(&ctx, NotifyEvent::Done(reason)).await;
                 return Ok(ctx.total_tokens.clone());
             }
 
             BuildState::Failed { error } => {
+                ctx.emit(BuildEvent::Failed {
+                    error: error.clone(),
+                });
+                notify(&ctx, NotifyEvent::Failed(error.clone())).await;
                 return Err(color_eyre::eyre::eyre!("Build failed: {}", error));
             }
         };
@@ -229,6 +277,8 @@ pub async fn run_build_command(
                 reason: DoneReason::UserCancelled,
             };
         }
+
+        ctx.record_state(&state);
     }
 }
 
@@ -241,7 +291,7 @@ pub async fn run_build_command(
 /// - Build prompt source and validation
 /// - Recent attempts summary
 fn run_dry_run(ctx: &BuildContext) -> color_eyre::Result<TokenUsage> {
-    use crate::prompts::get_build_prompt;
+    use crate::prompts::{load_build_message, load_build_prompt};
 
     println!("\n=== DRY RUN MODE ===\n");
 
@@ -261,13 +311,32 @@ fn run_dry_run(ctx: &BuildContext) -> color_eyre::Result<TokenUsage> {
     let total = ctx.progress.total_tasks();
     let completed = ctx.progress.completed_tasks();
     let remaining = total - completed;
-    println!(
-        "Tasks: {}/{} complete ({} remaining)",
-        completed, total, remaining
-    );
+    let blocked = ctx.progress.blocked_tasks();
+    if blocked > 0 {
+        println!(
+            "Tasks: {}/{} complete ({} remaining, {} blocked)",
+            completed, total, remaining, blocked
+        );
+    } else {
+        println!(
+            "Tasks: {}/{} complete ({} remaining)",
+            completed, total, remaining
+        );
+    }
 
     if remaining == 0 && total > 0 {
         println!("  -> All tasks complete, build would exit immediately");
+    } else if ctx.progress.is_finished_with_blocked() {
+        println!("  -> Only blocked tasks remain, build would exit immediately");
+    }
+    for phase in &ctx.progress.tasks {
+        for task in phase.tasks.iter().filter(|t| t.is_blocked()) {
+            println!(
+                "  Blocked: {} ({})",
+                task.description,
+                task.blocked.as_deref().unwrap_or_default()
+            );
+        }
     }
     println!();
 
@@ -286,6 +355,7 @@ fn run_dry_run(ctx: &BuildContext) -> color_eyre::Result<TokenUsage> {
     println!("  Max iterations: {}", ctx.max_iterations);
     println!("  Once mode: {}", ctx.once_mode);
     println!("  Recent attempts depth: {}", ctx.config.recent_threads);
+    println!("  Agent backend: {}", ctx.config.agent_backend);
     println!();
 
     // Prompt info
@@ -297,10 +367,16 @@ fn run_dry_run(ctx: &BuildContext) -> color_eyre::Result<TokenUsage> {
     println!("Build prompt: {}", prompt_source);
 
     // Validate prompt is loadable
-    match get_build_prompt(&ctx.config) {
+    match load_build_prompt(&ctx.config, ctx.mode) {
         Ok(prompt) => println!("  Prompt length: {} chars", prompt.len()),
         Err(e) => println!("  WARNING: Failed to load prompt: {}", e),
     }
+    if let Some(ref path) = ctx.config.build_message_prompt {
+        println!("Build message: custom ({})", path.display());
+        if let Err(e) = load_build_message(&ctx.config) {
+            println!("  WARNING: Failed to load prompt: {}", e);
+        }
+    }
     println!();
 
     // Recent attempts summary
@@ -324,20 +400,28 @@ fn run_dry_run(ctx: &BuildContext) -> color_eyre::Result<TokenUsage> {
 ///
 /// Initializes the TUI and runs the build loop concurrently with visual feedback.
 /// The build loop runs in the background and sends events to the TUI via channels.
+#[allow(clippy::too_many_arguments)]
 async fn run_build_with_tui(
     progress_path: PathBuf,
     progress: ProgressFile,
+    resume: bool,
     mode: PromptMode,
     no_dsp: bool,
     config: &Config,
     cancel_token: CancellationToken,
+    events: Option<EventSink>,
 ) -> color_eyre::Result<TokenUsage> {
     use crate::tui::{run_tui, App, SubprocessEvent};
 
     // Initialize app state from progress
-    let mut app = App::new(config.max_iterations, "Claude", progress.name.clone());
+    let mut app = App::new(
+        config.max_iterations,
+        config.agent_backend.label(),
+        progress.name.clone(),
+    );
     app.current_task = progress.completed_tasks() as u32;
     app.total_tasks = progress.total_tasks() as u32;
+    app.blocked_tasks = progress.blocked_tasks() as u32;
     app.log_path = Some(progress_path.clone());
     app.current_iteration = 0;
     app.viewing_iteration = 0;
@@ -345,9 +429,12 @@ async fn run_build_with_tui(
     // Get recent message count from config
     let recent_count = config.tui_recent_messages;
 
+    // Pause flag shared between the TUI (writer) and the build loop (reader)
+    let (pause_tx, pause_rx) = watch::channel(false);
+
     // Start TUI and get subprocess event sender
     // Pass a clone of cancel_token so TUI can cancel the build on quit
-    let subprocess_tx = run_tui(app, recent_count, cancel_token.clone()).await?;
+    let subprocess_tx = run_tui(app, recent_count, cancel_token.clone(), pause_tx, None).await?;
 
     // Create build context with TUI sender for log routing
     let mut ctx = BuildContext::with_tui(
@@ -361,13 +448,39 @@ async fn run_build_with_tui(
         no_dsp,
         Some(subprocess_tx.clone()),
     );
+    ctx.pause_rx = Some(pause_rx);
+    ctx.events = events;
 
     // Create a channel for build loop to send updates to TUI
     // The subprocess_tx is an UnboundedSender<SubprocessEvent>
     let tui_tx = subprocess_tx.clone();
 
+    let mut state = start_journal(&mut ctx, resume)?;
+    state = stop_if_over_budget(&ctx, state);
+    ctx.record_state(&state);
+    emit_build_started(&ctx);
+
+    // Sync TUI with a resumed run: restored token totals and current iteration
+    if resume {
+        let _ = tui_tx.send(SubprocessEvent::TokenUsage {
+            input_tokens: ctx.total_tokens.input_tokens,
+            output_tokens: ctx.total_tokens.output_tokens,
+            cache_creation_input_tokens: ctx.total_tokens.cache_creation_input_tokens,
+            cache_read_input_tokens: ctx.total_tokens.cache_read_input_tokens,
+            cost_usd: ctx.total_tokens.cost_usd,
+        });
+        if let BuildState::Running { iteration } | BuildState::IterationComplete { iteration, .. } =
+            state
+        {
+            let _ = tui_tx.send(SubprocessEvent::IterationStart { iteration });
+            let _ = tui_tx.send(SubprocessEvent::Log(format!(
+                "--- Iteration {} (resumed) ---",
+                iteration
+            )));
+        }
+    }
+
     // Run build loop, forwarding events to TUI
-    let mut state = BuildState::Starting;
     let result = loop {
         state = match state {
             BuildState::Starting => {
@@ -382,46 +495,64 @@ async fn run_build_with_tui(
             }
 
             BuildState::Running { iteration } => {
-                match run_single_iteration(&mut ctx).await {
-                    Ok(IterationResult::Continue { tasks_completed }) => {
-                        // Reset timeout retry count on success
-                        ctx.timeout_retry_count = 0;
-                        BuildState::IterationComplete {
-                            iteration,
-                            tasks_completed,
-                        }
+                // Hold here while paused so a human can edit files between iterations
+                hold_while_paused(&mut ctx, iteration).await;
+
+                if cancel_token.is_cancelled() {
+                    BuildState::Done {
+                        reason: DoneReason::UserCancelled,
                     }
-                    Ok(IterationResult::Done(reason)) => BuildState::Done { reason },
-                    Ok(IterationResult::Timeout) => {
-                        // Handle timeout with retry
-                        ctx.timeout_retry_count += 1;
-                        if ctx.timeout_retry_count >= ctx.config.timeout_retries {
-                            let _ = tui_tx.send(SubprocessEvent::Log(format!(
-                                "Iteration {} timed out {} times, failing",
-                                iteration, ctx.timeout_retry_count
-                            )));
-                            BuildState::Failed {
-                                error: format!(
-                                    "Iteration timed out {} times (max retries: {})",
-                                    ctx.timeout_retry_count, ctx.config.timeout_retries
-                                ),
+                } else {
+                    match run_single_iteration(&mut ctx).await {
+                        Ok(IterationResult::Continue { tasks_completed }) => {
+                            // Reset timeout retry count on success
+                            ctx.timeout_retry_count = 0;
+                            BuildState::IterationComplete {
+                                iteration,
+                                tasks_completed,
+                            }
+                        }
+                        Ok(IterationResult::Done(reason)) => BuildState::Done { reason },
+                        Ok(IterationResult::Timeout) => {
+                            // Handle timeout with retry
+                            ctx.timeout_retry_count += 1;
+                            if let Some(limit) = ctx.budget_exceeded() {
+                                // The timed-out run's partial spend counts too
+                                let _ = tui_tx.send(SubprocessEvent::Log(format!(
+                                    "Budget exhausted: {}",
+                                    limit
+                                )));
+                                BuildState::Done {
+                                    reason: DoneReason::BudgetExhausted,
+                                }
+                            } else if ctx.timeout_retry_count >= ctx.config.timeout_retries {
+                                let _ = tui_tx.send(SubprocessEvent::Log(format!(
+                                    "Iteration {} timed out {} times, failing",
+                                    iteration, ctx.timeout_retry_count
+                                )));
+                                BuildState::Failed {
+                                    error: format!(
+                                        "Iteration timed out {} times (max retries: {})",
+                                        ctx.timeout_retry_count, ctx.config.timeout_retries
+                                    ),
+                                }
+                            } else {
+                                let _ = tui_tx.send(SubprocessEvent::Log(format!(
+                                    "Iteration {} timed out, retry {}/{}",
+                                    iteration, ctx.timeout_retry_count, ctx.config.timeout_retries
+                                )));
+                                // Reset iteration start time for retry
+                                ctx.iteration_start = Some(std::time::Instant::now());
+                                BuildState::Running { iteration }
                             }
-                        } else {
-                            let _ = tui_tx.send(SubprocessEvent::Log(format!(
-                                "Iteration {} timed out, retry {}/{}",
-                                iteration, ctx.timeout_retry_count, ctx.config.timeout_retries
-                            )));
-                            // Reset iteration start time for retry
-                            ctx.iteration_start = Some(std::time::Instant::now());
-                            BuildState::Running { iteration }
                         }
+                        Err(RslphError::Cancelled) => BuildState::Done {
+                            reason: DoneReason::UserCancelled,
+                        },
+                        Err(e) => BuildState::Failed {
+                            error: e.to_string(),
+                        },
                     }
-                    Err(RslphError::Cancelled) => BuildState::Done {
-                        reason: DoneReason::UserCancelled,
-                    },
-                    Err(e) => BuildState::Failed {
-                        error: e.to_string(),
-                    },
                 }
             }
 
@@ -435,6 +566,7 @@ async fn run_build_with_tui(
                 // Send iteration complete to TUI
                 let _ = tui_tx.send(SubprocessEvent::IterationDone {
                     tasks_done: tasks_completed,
+                    tasks_blocked: ctx.progress.blocked_tasks() as u32,
                 });
 
                 let _ = tui_tx.send(SubprocessEvent::Log(format!(
@@ -446,9 +578,18 @@ async fn run_build_with_tui(
 
                 // Log to progress file
                 log_iteration(&mut ctx, iteration, tasks_completed)?;
+                notify_iteration(&ctx, iteration).await;
 
                 // Check termination conditions
-                if iteration >= ctx.max_iterations {
+                if let Some(limit) = ctx.budget_exceeded() {
+                    let _ =
+                        tui_tx.send(SubprocessEvent::Log(format!("Budget exhausted: {}", limit)));
+                    BuildState::Done {
+                        reason: DoneReason::BudgetExhausted,
+                    }
+                } else if let Some(reason) = handle_stall(&mut ctx, iteration, tasks_completed)? {
+                    BuildState::Done { reason }
+                } else if iteration >= ctx.max_iterations {
                     let _ = tui_tx.send(SubprocessEvent::Log(format!(
                         "Max iterations ({}) reached",
                         ctx.max_iterations
@@ -480,11 +621,18 @@ async fn run_build_with_tui(
 
             BuildState::Done { reason } => {
                 let _ = tui_tx.send(SubprocessEvent::Log(format!("Build complete: {}", reason)));
+                emit_done(&ctx, &reason);
+                run_on_done(&ctx, &reason).await;
+                notify(&ctx, NotifyEvent::Done(reason)).await;
                 break Ok(ctx.total_tokens.clone());
             }
 
             BuildState::Failed { error } => {
                 let _ = tui_tx.send(SubprocessEvent::Log(format!("Build failed: {}", error)));
+                ctx.emit(BuildEvent::Failed {
+                    error: error.clone(),
+                });
+                notify(&ctx, NotifyEvent::Failed(error.clone())).await;
                 break Err(color_eyre::eyre::eyre!("Build failed: {}", error));
             }
         };
@@ -496,6 +644,8 @@ async fn run_build_with_tui(
             };
         }
 
+        ctx.record_state(&state);
+
         // Small yield to let TUI render
         tokio::task::yield_now().await;
     };
@@ -503,8 +653,244 @@ async fn run_build_with_tui(
     result
 }
 
+/// Open the build journal and decide the initial state.
+///
+/// A fresh run gets a new journal and starts at `Starting`. With `--resume`,
+/// the most recent journal for the progress file is loaded, its counters,
+/// token totals, task attempts and stall tracking are restored, and an interrupted iteration is reconciled
+/// against the progress file: kept if its task changes were written, re-run
+/// (with a note in Recent Attempts) if not.
+fn start_journal(ctx: &mut BuildContext, resume: bool) -> color_eyre::Result<BuildState> {
+    if !resume {
+        match BuildJournal::create(&ctx.progress_path) {
+            Ok(journal) => {
+                ctx.log(&format!(
+                    "[JOURNAL] Run {} ({})",
+                    journal.run_id,
+                    journal.dir().display()
+                ));
+                start_archive(ctx, journal.dir());
+                ctx.journal = Some(journal);
+            }
+            Err(e) => ctx.log(&format!("[JOURNAL] Warning: journal disabled: {}", e)),
+        }
+        return Ok(BuildState::Starting);
+    }
+
+    let journal = BuildJournal::latest(&ctx.progress_path)?.ok_or_else(|| {
+        color_eyre::eyre::eyre!(
+            "No build journal found for {}; nothing to resume",
+            ctx.progress_path.display()
+        )
+    })?;
+
+    ctx.current_iteration = journal.current_iteration;
+    ctx.timeout_retry_count = journal.timeout_retry_count;
+    ctx.iteration_tokens = journal.iteration_tokens.clone();
+    ctx.total_tokens = journal.total_tokens.clone();
+    ctx.task_attempts = journal.task_attempts.clone();
+    // The threshold comes from the current config, not the interrupted run
+    ctx.stall = StallTracker {
+        threshold: ctx.stall.threshold,
+        ..journal.stall.clone()
+    };
+
+    let state = journal.resume_state(ctx.progress.completed_tasks());
+    ctx.log(&format!(
+        "[JOURNAL] Resuming run {} (last state: {:?})",
+        journal.run_id, journal.state
+    ));
+
+    match (&journal.in_flight, &state) {
+        (Some(in_flight), BuildState::Running { iteration }) => {
+            ctx.log(&format!(
+                "[JOURNAL] Iteration {} was interrupted without recording progress, re-running it",
+                iteration
+            ));
+            ctx.progress.add_attempt(
+                in_flight.iteration,
+                "Run iteration",
+                "Interrupted before completion (build exited mid-iteration)",
+                Some("Re-running iteration after resume"),
+            );
+            ctx.progress
+                .trim_attempts(ctx.config.recent_threads as usize);
+            ctx.progress.write(&ctx.progress_path)?;
+        }
+        (Some(_), BuildState::IterationComplete {
+            iteration,
+            tasks_completed,
+        }) => {
+            ctx.log(&format!(
+                "[JOURNAL] Iteration {} was interrupted after recording {} task(s), accepting it",
+                iteration, tasks_completed
+            ));
+        }
+        (None, BuildState::Running { .. }) => {
+            // Fresh iteration after a clean stop
+            ctx.timeout_retry_count = 0;
+        }
+        _ => {}
+    }
+
+    if let BuildState::Running { iteration } | BuildState::IterationComplete { iteration, .. } =
+        state
+    {
+        ctx.current_iteration = iteration;
+        ctx.iteration_start = Some(std::time::Instant::now());
+    }
+
+    start_archive(ctx, journal.dir());
+    ctx.journal = Some(journal);
+    Ok(state)
+}
+
+/// Stop before the first iteration if the budget is already spent.
+///
+/// This happens when resuming a run that stopped at its limits, or when an
+/// eval trial's planning phase used up the trial budget.
+fn stop_if_over_budget(ctx: &BuildContext, state: BuildState) -> BuildState {
+    if matches!(state, BuildState::Done { .. }) {
+        return state;
+    }
+
+    match ctx.budget_exceeded() {
+        Some(limit) => {
+            ctx.log(&format!("[BUILD] Budget exhausted: {}", limit));
+            BuildState::Done {
+                reason: DoneReason::BudgetExhausted,
+            }
+        }
+        None => state,
+    }
+}
+
+/// Apply the stall policy after an iteration.
+///
+/// # Returns
+///
+/// * `Ok(Some(DoneReason::Stalled))` - The build should stop
+/// * `Ok(None)` - Keep going (possibly with a task blocked or escalation on)
+pub fn handle_stall(
+    ctx: &mut BuildContext,
+    iteration: u32,
+    tasks_completed: u32,
+) -> Result<Option<DoneReason>, RslphError> {
+    ctx.stall.record(tasks_completed);
+    if tasks_completed > 0 && ctx.stall.escalated {
+        ctx.stall.escalated = false;
+        ctx.log("[STALL] Progress resumed, escalation cleared");
+    }
+
+    let Some(stall) = ctx.stall.check(&ctx.progress) else {
+        return Ok(None);
+    };
+    ctx.log(&format!("[STALL] Build stalled: {}", stall));
+
+    match ctx.config.stall_policy {
+        StallPolicy::Stop => return Ok(Some(DoneReason::Stalled)),
+        StallPolicy::Skip => {
+            let Some((phase, task)) = ctx
+                .progress
+                .next_task()
+                .map(|(phase, task)| (phase.to_string(), task.description.clone()))
+            else {
+                return Ok(Some(DoneReason::Stalled));
+            };
+            ctx.progress.block_task(&phase, &task, &stall);
+            ctx.progress.add_attempt(
+                iteration,
+                &format!("Stalled on task: {}", task),
+                &stall,
+                Some("Task marked blocked; moving on to the next task"),
+            );
+            ctx.log(&format!("[STALL] Marked task blocked: {}", task));
+        }
+        StallPolicy::Escalate => {
+            if ctx.stall.escalated {
+                ctx.log("[STALL] Still stalled after escalating");
+                return Ok(Some(DoneReason::Stalled));
+            }
+            ctx.stall.escalated = true;
+            let model = ctx
+                .config
+                .stall_model
+                .as_deref()
+                .unwrap_or("the default model");
+            ctx.progress.add_attempt(
+                iteration,
+                "Escalating after stall",
+                &stall,
+                Some(&format!("Retrying with {} and a change of approach", model)),
+            );
+            ctx.log(&format!(
+                "[STALL] Escalating: next iterations use {}",
+                model
+            ));
+        }
+    }
+
+    ctx.stall.reset(iteration);
+    if let Some(attempt) = ctx.progress.recent_attempts.last().cloned() {
+        ctx.emit(BuildEvent::AttemptRecorded { attempt });
+    }
+    ctx.progress
+        .trim_attempts(ctx.config.recent_threads as usize);
+    ctx.progress.write(&ctx.progress_path)?;
+    Ok(None)
+}
+
+/// Report the start of the build loop to the `--events` stream.
+pub fn emit_build_started(ctx: &BuildContext) {
+    ctx.emit(BuildEvent::BuildStarted {
+        progress_path: ctx.progress_path.display().to_string(),
+        tasks_completed: ctx.progress.completed_tasks(),
+        tasks_total: ctx.progress.total_tasks(),
+        max_iterations: ctx.max_iterations,
+    });
+}
+
+/// Report how the build stopped to the `--events` stream.
+pub fn emit_done(ctx: &BuildContext, reason: &DoneReason) {
+    ctx.emit(BuildEvent::Done {
+        reason: reason.clone(),
+        tasks_completed: ctx.progress.completed_tasks(),
+        tasks_total: ctx.progress.total_tasks(),
+        tokens: ctx.total_tokens.clone(),
+    });
+}
+
+/// Wait before starting an iteration while the TUI has the build paused.
+///
+/// The iteration start time is reset after a pause so logged durations
+/// exclude the time a human spent working by hand.
+async fn hold_while_paused(ctx: &mut BuildContext, iteration: u32) {
+    let paused = ctx
+        .pause_rx
+        .as_mut()
+        .is_some_and(|rx| *rx.borrow_and_update());
+    if !paused {
+        return;
+    }
+
+    ctx.log(&format!("[BUILD] Paused before iteration {}", iteration));
+
+    let Some(pause_rx) = ctx.pause_rx.as_mut() else {
+        return;
+    };
+    let waited = wait_while_paused(pause_rx, &ctx.cancel_token).await;
+
+    if !ctx.cancel_token.is_cancelled() {
+        ctx.log(&format!(
+            "[BUILD] Resumed after {} paused",
+            format_paused(waited)
+        ));
+        ctx.iteration_start = Some(std::time::Instant::now());
+    }
+}
+
 /// Print completion message based on done reason.
-fn print_completion_message(reason: &DoneReason, ctx: &BuildContext) {
+pub fn print_completion_message(reason: &DoneReason, ctx: &BuildContext) {
     println!("\n=== BUILD COMPLETE ===");
     println!("Reason: {}", reason);
     println!(
@@ -512,11 +898,18 @@ fn print_completion_message(reason: &DoneReason, ctx: &BuildContext) {
         ctx.progress.completed_tasks(),
         ctx.progress.total_tasks()
     );
+    print_cost_summary(ctx);
 
     match reason {
         DoneReason::AllTasksComplete | DoneReason::RalphDoneMarker => {
             println!("All tasks completed successfully!");
         }
+        DoneReason::CompleteWithBlocked => {
+            println!(
+                "All other tasks completed; {} task(s) blocked.",
+                ctx.progress.blocked_tasks()
+            );
+        }
         DoneReason::MaxIterationsReached => {
             let remaining = ctx.progress.total_tasks() - ctx.progress.completed_tasks();
             println!(
@@ -530,11 +923,45 @@ fn print_completion_message(reason: &DoneReason, ctx: &BuildContext) {
         DoneReason::SingleIterationComplete => {
             println!("Single iteration completed (--once mode).");
         }
+        DoneReason::BudgetExhausted => {
+            let remaining = ctx.progress.total_tasks() - ctx.progress.completed_tasks();
+            println!(
+                "Stopped at budget limit: {} tokens used ({}). {} task(s) remaining.",
+                format_tokens(ctx.total_tokens.total()),
+                format_cost(ctx.total_tokens.cost_usd),
+                remaining
+            );
+        }
+        DoneReason::Stalled => {
+            let remaining = ctx.progress.total_tasks() - ctx.progress.completed_tasks();
+            println!(
+                "Stopped: no progress being made. {} task(s) remaining ({} blocked).",
+                remaining,
+                ctx.progress.blocked_tasks()
+            );
+        }
+    }
+}
+
+/// Print total cost and the cost of each iteration.
+fn print_cost_summary(ctx: &BuildContext) {
+    if ctx.iteration_tokens.is_empty() {
+        return;
+    }
+
+    println!("Cost: {}", format_cost(ctx.total_tokens.cost_usd));
+    for iter in &ctx.iteration_tokens {
+        println!(
+            "  Iteration {}: {} ({} tokens)",
+            iter.iteration,
+            format_cost(iter.cost_usd),
+            format_tokens(iter.total())
+        );
     }
 }
 
 /// Log iteration to progress file.
-fn log_iteration(
+pub fn log_iteration(
     ctx: &mut BuildContext,
     iteration: u32,
     tasks_completed: u32,
@@ -555,10 +982,37 @@ fn log_iteration(
     } else {
         format!("{} task(s) completed", tasks_completed)
     };
+    let model = ctx
+        .iteration_tokens
+        .last()
+        .filter(|t| t.iteration == iteration)
+        .and_then(|t| t.model.as_deref());
+    let notes = match model {
+        Some(model) => format!("{} (model: {})", notes, model),
+        None => notes,
+    };
 
     ctx.progress
         .log_iteration(iteration, &started, &duration, tasks_completed, &notes);
 
+    for attempt in &ctx.progress.recent_attempts {
+        if attempt.iteration == iteration {
+            ctx.emit(BuildEvent::AttemptRecorded {
+                attempt: attempt.clone(),
+            });
+        }
+    }
+    ctx.emit(BuildEvent::IterationFinished {
+        iteration,
+        tasks_completed,
+        duration_secs: ctx
+            .iteration_start
+            .map(|s| s.elapsed().as_secs_f64())
+            .unwrap_or_default(),
+        progress_completed: ctx.progress.completed_tasks(),
+        progress_total: ctx.progress.total_tasks(),
+    });
+
     ctx.progress.write(&ctx.progress_path)?;
 
     Ok(())
@@ -583,12 +1037,15 @@ mod tests {
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: false,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             testing_strategy: "Unit tests".to_string(),
             completed_this_iteration: vec![],
             recent_attempts: vec![],
             iteration_log: vec![],
+            ..Default::default()
         };
 
         let path = dir.path().join("progress.md");
@@ -622,11 +1079,13 @@ mod tests {
             progress_path,
             true, // once
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -652,11 +1111,13 @@ mod tests {
             progress_path,
             false,
             true, // dry_run
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -706,11 +1167,13 @@ mod tests {
             progress_path,
             true, // once mode to limit iterations
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -760,11 +1223,13 @@ mod tests {
             progress_path,
             false,
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -781,11 +1246,13 @@ mod tests {
             PathBuf::from("/nonexistent/progress.md"),
             false,
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -808,12 +1275,15 @@ mod tests {
                     Task {
                         description: "Task 1".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -830,11 +1300,13 @@ mod tests {
             progress_path.clone(),
             false,
             true, // dry_run
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -863,7 +1335,9 @@ mod tests {
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: false,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -877,11 +1351,13 @@ mod tests {
             progress_path,
             true, // once mode
             true, // dry_run
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -904,12 +1380,15 @@ mod tests {
                     Task {
                         description: "Completed task".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                     Task {
                         description: "Pending task".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             recent_attempts: vec![Attempt {
                 iteration: 1,
@@ -957,16 +1436,20 @@ mod tests {
                     Task {
                         description: "Task 1".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 3".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             testing_strategy: "Test with cargo test.".to_string(),
             ..Default::default()
@@ -991,11 +1474,13 @@ mod tests {
             progress_path.clone(),
             true,  // once mode
             false, // not dry-run
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -1016,7 +1501,9 @@ mod tests {
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: false,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1059,7 +1546,9 @@ mod tests {
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: true,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1093,11 +1582,13 @@ mod tests {
             progress_path,
             false,
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -1129,12 +1620,15 @@ mod tests {
                     Task {
                         description: "Task 1".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -1166,11 +1660,13 @@ mod tests {
             progress_path,
             false,
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -1202,12 +1698,15 @@ mod tests {
                     Task {
                         description: "Task 1".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             testing_strategy: "Test with cargo test.".to_string(),
             ..Default::default()
@@ -1230,11 +1729,13 @@ mod tests {
             progress_path.clone(),
             false,
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
@@ -1261,20 +1762,25 @@ mod tests {
                     Task {
                         description: "Task 1 - already done".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2 - already done".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 3 - next to execute".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 4 - waiting".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             testing_strategy: "Test with cargo test.".to_string(),
             completed_this_iteration: vec![],
@@ -1308,6 +1814,7 @@ mod tests {
                     notes: "Task 2".to_string(),
                 },
             ],
+            ..Default::default()
         };
 
         let progress_path = dir.path().join("progress.md");
@@ -1330,15 +1837,76 @@ mod tests {
             progress_path,
             true, // once mode to limit execution
             false,
+            false, // resume
             PromptMode::Basic,
             false, // no_dsp
             &config,
             token,
             None,
+            None,
         )
         .await;
 
         // Echo produces invalid output, so validation should reject it
         assert!(result.is_err(), "Resume should reject invalid output");
     }
+
+    #[tokio::test]
+    async fn test_hold_while_paused_waits_for_resume() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress_path = create_test_progress_file(&dir);
+        let progress = ProgressFile::load(&progress_path).expect("load progress");
+
+        let token = CancellationToken::new();
+        let mut ctx = BuildContext::new(
+            progress_path,
+            progress,
+            Config::default(),
+            PromptMode::Basic,
+            token,
+            false, // once_mode
+            false, // dry_run
+            false, // no_dsp
+        );
+
+        let (pause_tx, pause_rx) = watch::channel(true);
+        ctx.pause_rx = Some(pause_rx);
+        let stale_start = std::time::Instant::now();
+        ctx.iteration_start = Some(stale_start);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = pause_tx.send(false);
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        });
+
+        hold_while_paused(&mut ctx, 2).await;
+
+        // Iteration timer restarts after the pause
+        let start = ctx.iteration_start.expect("start should be set");
+        assert!(start.duration_since(stale_start) >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_hold_while_paused_without_tui_is_noop() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress_path = create_test_progress_file(&dir);
+        let progress = ProgressFile::load(&progress_path).expect("load progress");
+
+        let mut ctx = BuildContext::new(
+            progress_path,
+            progress,
+            Config::default(),
+            PromptMode::Basic,
+            CancellationToken::new(),
+            false, // once_mode
+            false, // dry_run
+            false, // no_dsp
+        );
+        let start = std::time::Instant::now();
+        ctx.iteration_start = Some(start);
+
+        hold_while_paused(&mut ctx, 1).await;
+        assert_eq!(ctx.iteration_start, Some(start));
+    }
 }
@@ -0,0 +1,116 @@
+//! Model selection for build iterations.
+//!
+//! Iterations run on `build_model`. Every `escalate_after` iterations that end
+//! without finishing the task they worked on move that task one rung up the
+//! `build_model_escalation` ladder, so a cheap default model can hand hard
+//! tasks to a stronger one.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Counts the iterations spent on each task so far.
+///
+/// A completed task is never worked on again, so every iteration after the
+/// first on the same task means the previous ones failed to finish it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskAttempts {
+    started: BTreeMap<String, u32>,
+}
+
+impl TaskAttempts {
+    /// Note that an iteration is starting on `task`.
+    ///
+    /// Returns how many earlier iterations worked on the same task without
+    /// finishing it (always 0 when the task is unknown).
+    pub fn start(&mut self, task: Option<&str>) -> u32 {
+        let Some(task) = task else {
+            return 0;
+        };
+        let started = self.started.entry(task.to_string()).or_insert(0);
+        *started += 1;
+        *started - 1
+    }
+}
+
+/// Model for a build iteration on a task with `failures` unfinished iterations.
+///
+/// Returns None when no model is configured (the Claude CLI default is used).
+pub fn build_model(config: &Config, failures: u32) -> Option<String> {
+    let ladder = &config.build_model_escalation;
+    let rung = match config.escalate_after {
+        0 => 0,
+        after => (failures / after) as usize,
+    };
+
+    if rung == 0 || ladder.is_empty() {
+        config.build_model.clone()
+    } else {
+        Some(ladder[rung.min(ladder.len()) - 1].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ladder_config() -> Config {
+        Config {
+            build_model: Some("claude-haiku-4-5".to_string()),
+            build_model_escalation: vec![
+                "claude-sonnet-4-5".to_string(),
+                "claude-opus-4-5".to_string(),
+            ],
+            escalate_after: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_task_attempts_count_earlier_iterations_per_task() {
+        let mut attempts = TaskAttempts::default();
+        assert_eq!(attempts.start(Some("Task 1")), 0);
+        assert_eq!(attempts.start(Some("Task 1")), 1);
+        assert_eq!(attempts.start(Some("Task 1")), 2);
+        assert_eq!(attempts.start(Some("Task 2")), 0);
+        assert_eq!(attempts.start(Some("Task 1")), 3);
+        assert_eq!(attempts.start(None), 0);
+        assert_eq!(attempts.start(None), 0);
+    }
+
+    #[test]
+    fn test_build_model_climbs_the_ladder() {
+        let config = ladder_config();
+        let models: Vec<_> = (0..7)
+            .map(|failures| build_model(&config, failures).expect("model"))
+            .collect();
+        assert_eq!(
+            models,
+            vec![
+                "claude-haiku-4-5",
+                "claude-haiku-4-5",
+                "claude-sonnet-4-5",
+                "claude-sonnet-4-5",
+                "claude-opus-4-5",
+                "claude-opus-4-5",
+                "claude-opus-4-5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_model_without_ladder() {
+        assert_eq!(build_model(&Config::default(), 5), None);
+
+        let config = Config {
+            escalate_after: 0,
+            ..ladder_config()
+        };
+        assert_eq!(
+            build_model(&config, 10).as_deref(),
+            Some("claude-haiku-4-5")
+        );
+    }
+}
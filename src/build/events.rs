@@ -0,0 +1,255 @@
+//! Machine-readable build event stream (`--events`).
+//!
+//! Each event is written as one JSON object per line. Every object carries
+//! `schema_version` (bumped on incompatible changes), an RFC 3339 `timestamp`
+//! and a snake_case `type`, followed by the event's fields. New event types
+//! and fields may be added without a version bump, so consumers should ignore
+//! what they don't recognize.
+//!
+//! With `--events -` the events own stdout: the TUI is disabled and the rest
+//! of rslph's stdout output is sent to stderr, so the stream stays parseable.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+
+use super::state::DoneReason;
+use super::tokens::{IterationTokens, TokenUsage};
+use crate::progress::Attempt;
+
+/// Keep stdout for the event stream and point the process's stdout at stderr.
+#[cfg(unix)]
+fn take_stdout() -> io::Result<File> {
+    use std::os::fd::{AsRawFd, FromRawFd};
+
+    io::stdout().flush()?;
+    let stdout = io::stdout().as_raw_fd();
+    let events = nix::unistd::dup(stdout)?;
+    nix::unistd::dup2(io::stderr().as_raw_fd(), stdout)?;
+    // SAFETY: `events` is a fresh descriptor owned by nothing else
+    Ok(unsafe { File::from_raw_fd(events) })
+}
+
+/// Without descriptor redirection, events share stdout with other output.
+#[cfg(not(unix))]
+fn take_stdout() -> io::Result<io::Stdout> {
+    Ok(io::stdout())
+}
+
+/// Version of the event schema written to the stream.
+pub const EVENTS_SCHEMA_VERSION: u32 = 1;
+
+/// A build event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BuildEvent {
+    /// The build loop started.
+    BuildStarted {
+        progress_path: String,
+        tasks_completed: usize,
+        tasks_total: usize,
+        max_iterations: u32,
+    },
+    /// An iteration is about to spawn Claude.
+    IterationStarted {
+        iteration: u32,
+        /// Task the iteration targets, when known.
+        task: Option<String>,
+    },
+    /// Claude used a tool.
+    ToolUse {
+        iteration: u32,
+        tool: String,
+        summary: String,
+    },
+    /// Token usage and cost of an iteration.
+    TokenUsage {
+        #[serde(flatten)]
+        tokens: IterationTokens,
+    },
+    /// An attempt was recorded in Recent Attempts.
+    AttemptRecorded {
+        #[serde(flatten)]
+        attempt: Attempt,
+    },
+    /// Iteration changes were committed.
+    Commit {
+        iteration: u32,
+        hash: String,
+        vcs: String,
+    },
+    /// An iteration finished and was logged.
+    IterationFinished {
+        iteration: u32,
+        tasks_completed: u32,
+        duration_secs: f64,
+        progress_completed: usize,
+        progress_total: usize,
+    },
+    /// The build stopped.
+    Done {
+        reason: DoneReason,
+        tasks_completed: usize,
+        tasks_total: usize,
+        tokens: TokenUsage,
+    },
+    /// The build failed with an error.
+    Failed { error: String },
+}
+
+/// An event with the common envelope fields.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    schema_version: u32,
+    timestamp: String,
+    #[serde(flatten)]
+    event: &'a BuildEvent,
+}
+
+/// Destination for build events, shared by everything in a build.
+#[derive(Clone)]
+pub struct EventSink {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    /// Events go to stdout (`--events -`)
+    stdout: bool,
+}
+
+impl std::fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSink").finish_non_exhaustive()
+    }
+}
+
+impl EventSink {
+    /// Open the event stream: `-` for stdout, otherwise a file (truncated).
+    ///
+    /// For stdout, the process's stdout is redirected to stderr on unix and
+    /// only the event stream keeps the original descriptor.
+    pub fn open(target: &Path) -> io::Result<Self> {
+        if target == Path::new("-") {
+            return Ok(Self {
+                stdout: true,
+                ..Self::from_writer(take_stdout()?)
+            });
+        }
+        Ok(Self::from_writer(BufWriter::new(File::create(target)?)))
+    }
+
+    /// Write events to any writer.
+    pub fn from_writer(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            stdout: false,
+        }
+    }
+
+    /// Check whether events are written to stdout.
+    pub fn is_stdout(&self) -> bool {
+        self.stdout
+    }
+
+    /// Write one event as a JSON line.
+    ///
+    /// Errors are ignored: a broken event consumer must not stop the build.
+    pub fn emit(&self, event: BuildEvent) {
+        let envelope = Envelope {
+            schema_version: EVENTS_SCHEMA_VERSION,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            event: &event,
+        };
+        let Ok(line) = serde_json::to_string(&envelope) else {
+            return;
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line);
+            let _ = writer.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer whose contents can be read back after the sink is done.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn emitted(events: Vec<BuildEvent>) -> Vec<serde_json::Value> {
+        let buffer = Shared::default();
+        let sink = EventSink::from_writer(buffer.clone());
+        for event in events {
+            sink.emit(event);
+        }
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).expect("utf8");
+        text.lines()
+            .map(|l| serde_json::from_str(l).expect("valid json line"))
+            .collect()
+    }
+
+    #[test]
+    fn test_events_have_envelope_and_type() {
+        let lines = emitted(vec![
+            BuildEvent::IterationStarted {
+                iteration: 1,
+                task: Some("Task 1".to_string()),
+            },
+            BuildEvent::Done {
+                reason: DoneReason::AllTasksComplete,
+                tasks_completed: 2,
+                tasks_total: 2,
+                tokens: TokenUsage::default(),
+            },
+        ]);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["schema_version"], EVENTS_SCHEMA_VERSION);
+        assert!(lines[0]["timestamp"].is_string());
+        assert_eq!(lines[0]["type"], "iteration_started");
+        assert_eq!(lines[0]["task"], "Task 1");
+        assert_eq!(lines[1]["type"], "done");
+        assert_eq!(lines[1]["reason"], "all_tasks_complete");
+    }
+
+    #[test]
+    fn test_flattened_events() {
+        let lines = emitted(vec![
+            BuildEvent::TokenUsage {
+                tokens: IterationTokens {
+                    iteration: 3,
+                    input_tokens: 10,
+                    model: Some("claude-opus-4-5".to_string()),
+                    ..Default::default()
+                },
+            },
+            BuildEvent::AttemptRecorded {
+                attempt: Attempt {
+                    iteration: 3,
+                    tried: "Run tests".to_string(),
+                    result: "Failed".to_string(),
+                    next: None,
+                },
+            },
+        ]);
+
+        assert_eq!(lines[0]["type"], "token_usage");
+        assert_eq!(lines[0]["iteration"], 3);
+        assert_eq!(lines[0]["input_tokens"], 10);
+        assert_eq!(lines[0]["model"], "claude-opus-4-5");
+        assert_eq!(lines[1]["type"], "attempt_recorded");
+        assert_eq!(lines[1]["tried"], "Run tests");
+    }
+}
@@ -0,0 +1,435 @@
+//! Guardrails for the progress file Claude returns.
+//!
+//! Each iteration replaces the progress file with Claude's output. Before it is
+//! written, the new file is diffed against the previous one and checked against
+//! the configured rules (e.g. tasks may be checked off but never removed).
+//! Violations are reported, rejected (keeping the previous file), or repaired
+//! by restoring what was lost. Tasks are matched by ID when both sides have
+//! one and by description otherwise, so a reworded task with an ID isn't
+//! taken for a removed one.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::progress::{ProgressFile, Task, TaskPhase};
+
+/// What to do with a progress file that breaks a guardrail rule.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    ValueEnum,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum GuardrailAction {
+    /// Accept the progress file as returned
+    Off,
+    /// Accept the progress file as returned and record the violation
+    #[default]
+    Warn,
+    /// Keep the previous progress file and record the violation
+    Reject,
+    /// Restore what the violation lost and keep the rest of the update
+    Repair,
+}
+
+/// A structural rule the returned progress file must follow.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ValueEnum,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum GuardrailRule {
+    /// Every task must still be present (tasks may be added, never removed)
+    KeepTasks,
+    /// Completed tasks stay completed
+    KeepChecked,
+    /// Phases may not be renamed or removed
+    KeepPhases,
+    /// A non-empty Analysis section is immutable
+    KeepAnalysis,
+}
+
+/// Rules enforced when none are configured.
+pub fn default_rules() -> Vec<GuardrailRule> {
+    vec![
+        GuardrailRule::KeepTasks,
+        GuardrailRule::KeepChecked,
+        GuardrailRule::KeepPhases,
+    ]
+}
+
+/// A difference between two progress files that breaks a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// A task in the previous file is missing.
+    TaskRemoved { phase: String, task: String },
+    /// A completed task was unchecked.
+    TaskUnchecked { phase: String, task: String },
+    /// A phase was renamed (`to` is None if it disappeared).
+    PhaseRenamed { from: String, to: Option<String> },
+    /// The Analysis section was rewritten.
+    AnalysisChanged,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::TaskRemoved { phase, task } => {
+                write!(f, "removed task \"{}\" from {}", task, phase)
+            }
+            Violation::TaskUnchecked { task, .. } => {
+                write!(f, "unchecked completed task \"{}\"", task)
+            }
+            Violation::PhaseRenamed { from, to: Some(to) } => {
+                write!(f, "renamed phase \"{}\" to \"{}\"", from, to)
+            }
+            Violation::PhaseRenamed { from, to: None } => {
+                write!(f, "removed phase \"{}\"", from)
+            }
+            Violation::AnalysisChanged => write!(f, "rewrote the Analysis section"),
+        }
+    }
+}
+
+/// Check whether `a` and `b` are the same task: by ID when both have one,
+/// by description otherwise.
+fn same_task(a: &Task, b: &Task) -> bool {
+    match (&a.id, &b.id) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.description == b.description,
+    }
+}
+
+/// Find `task` in any phase.
+fn find_task<'a>(progress: &'a ProgressFile, task: &Task) -> Option<&'a Task> {
+    progress
+        .tasks
+        .iter()
+        .flat_map(|p| &p.tasks)
+        .find(|t| same_task(t, task))
+}
+
+/// Find a task of `progress` by description.
+fn task_named<'a>(progress: &'a ProgressFile, description: &str) -> Option<&'a Task> {
+    progress
+        .tasks
+        .iter()
+        .flat_map(|p| &p.tasks)
+        .find(|t| t.description == description)
+}
+
+/// Diff `after` against `before` and list the rule violations.
+pub fn check(
+    before: &ProgressFile,
+    after: &ProgressFile,
+    rules: &[GuardrailRule],
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if rules.contains(&GuardrailRule::KeepPhases) {
+        for (index, phase) in before.tasks.iter().enumerate() {
+            if !after.tasks.iter().any(|p| p.name == phase.name) {
+                // A phase in the same position with an unknown name is a rename
+                let to = after
+                    .tasks
+                    .get(index)
+                    .filter(|p| !before.tasks.iter().any(|b| b.name == p.name))
+                    .map(|p| p.name.clone());
+                violations.push(Violation::PhaseRenamed {
+                    from: phase.name.clone(),
+                    to,
+                });
+            }
+        }
+    }
+
+    for phase in &before.tasks {
+        for task in &phase.tasks {
+            match find_task(after, task) {
+                None if rules.contains(&GuardrailRule::KeepTasks) => {
+                    violations.push(Violation::TaskRemoved {
+                        phase: phase.name.clone(),
+                        task: task.description.clone(),
+                    });
+                }
+                Some(new)
+                    if task.completed
+                        && !new.completed
+                        && rules.contains(&GuardrailRule::KeepChecked) =>
+                {
+                    violations.push(Violation::TaskUnchecked {
+                        phase: phase.name.clone(),
+                        task: task.description.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if rules.contains(&GuardrailRule::KeepAnalysis)
+        && !before.analysis.trim().is_empty()
+        && before.analysis.trim() != after.analysis.trim()
+    {
+        violations.push(Violation::AnalysisChanged);
+    }
+
+    violations
+}
+
+/// Undo `violations` in `after`, keeping the rest of Claude's update.
+///
+/// Renamed phases get their names back, unchecked tasks are checked again,
+/// removed tasks are re-inserted near their old position (recreating their
+/// phase if needed), and a rewritten Analysis is restored.
+pub fn repair(
+    before: &ProgressFile,
+    after: &ProgressFile,
+    violations: &[Violation],
+) -> ProgressFile {
+    let mut repaired = after.clone();
+
+    for violation in violations {
+        if let Violation::PhaseRenamed { from, to: Some(to) } = violation {
+            if let Some(phase) = repaired.tasks.iter_mut().find(|p| p.name == *to) {
+                phase.name = from.clone();
+            }
+        }
+    }
+
+    for violation in violations {
+        match violation {
+            Violation::TaskUnchecked { task, .. } => {
+                let Some(old) = task_named(before, task) else {
+                    continue;
+                };
+                for t in repaired.tasks.iter_mut().flat_map(|p| p.tasks.iter_mut()) {
+                    if same_task(t, old) {
+                        t.completed = true;
+                    }
+                }
+            }
+            Violation::TaskRemoved { phase, task } => {
+                restore_task(before, &mut repaired, phase, task)
+            }
+            Violation::AnalysisChanged => repaired.analysis = before.analysis.clone(),
+            Violation::PhaseRenamed { .. } => {}
+        }
+    }
+
+    repaired
+}
+
+/// Re-insert a removed task at its old position in its old phase.
+fn restore_task(before: &ProgressFile, repaired: &mut ProgressFile, phase: &str, task: &str) {
+    let Some((phase_index, old_phase)) = before
+        .tasks
+        .iter()
+        .enumerate()
+        .find(|(_, p)| p.name == phase)
+    else {
+        return;
+    };
+    let Some((task_index, old_task)) = old_phase
+        .tasks
+        .iter()
+        .enumerate()
+        .find(|(_, t)| t.description == task)
+    else {
+        return;
+    };
+
+    let position = match repaired.tasks.iter().position(|p| p.name == phase) {
+        Some(position) => position,
+        None => {
+            let position = phase_index.min(repaired.tasks.len());
+            repaired.tasks.insert(
+                position,
+                TaskPhase {
+                    name: phase.to_string(),
+                    description: old_phase.description.clone(),
+                    tasks: Vec::new(),
+                },
+            );
+            position
+        }
+    };
+
+    let tasks = &mut repaired.tasks[position].tasks;
+    tasks.insert(task_index.min(tasks.len()), old_task.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(phases: &[(&str, &[(&str, bool)])]) -> ProgressFile {
+        ProgressFile {
+            name: "Guard".to_string(),
+            analysis: "Original analysis".to_string(),
+            tasks: phases
+                .iter()
+                .map(|(name, tasks)| TaskPhase {
+                    name: name.to_string(),
+                    tasks: tasks
+                        .iter()
+                        .map(|(description, completed)| Task {
+                            description: description.to_string(),
+                            completed: *completed,
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn all_rules() -> Vec<GuardrailRule> {
+        let mut rules = default_rules();
+        rules.push(GuardrailRule::KeepAnalysis);
+        rules
+    }
+
+    #[test]
+    fn test_checking_and_adding_tasks_is_allowed() {
+        let before = progress(&[("Phase 1", &[("A", false), ("B", false)])]);
+        let after = progress(&[("Phase 1", &[("A", true), ("B", false), ("C", false)])]);
+        assert!(check(&before, &after, &all_rules()).is_empty());
+    }
+
+    #[test]
+    fn test_check_reports_each_violation() {
+        let before = progress(&[
+            ("Phase 1", &[("A", true), ("B", false)]),
+            ("Phase 2", &[("C", false)]),
+        ]);
+        let mut after = progress(&[("Phase 1", &[("A", false)]), ("Renamed", &[("C", false)])]);
+        after.analysis = "Rewritten".to_string();
+
+        let violations = check(&before, &after, &all_rules());
+        assert_eq!(
+            violations,
+            vec![
+                Violation::PhaseRenamed {
+                    from: "Phase 2".to_string(),
+                    to: Some("Renamed".to_string()),
+                },
+                Violation::TaskUnchecked {
+                    phase: "Phase 1".to_string(),
+                    task: "A".to_string(),
+                },
+                Violation::TaskRemoved {
+                    phase: "Phase 1".to_string(),
+                    task: "B".to_string(),
+                },
+                Violation::AnalysisChanged,
+            ]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "renamed phase \"Phase 2\" to \"Renamed\""
+        );
+    }
+
+    #[test]
+    fn test_check_only_applies_configured_rules() {
+        let before = progress(&[("Phase 1", &[("A", true), ("B", false)])]);
+        let mut after = progress(&[("Phase 1", &[("A", false)])]);
+        after.analysis = "Rewritten".to_string();
+
+        let violations = check(&before, &after, &[GuardrailRule::KeepChecked]);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], Violation::TaskUnchecked { .. }));
+        assert!(check(&before, &after, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_repair_restores_lost_structure() {
+        let before = progress(&[
+            ("Phase 1", &[("A", true), ("B", false)]),
+            ("Phase 2", &[("C", false), ("D", false)]),
+        ]);
+        let mut after = progress(&[
+            ("Phase 1", &[("A", false), ("New", false)]),
+            ("Renamed", &[("D", true)]),
+        ]);
+        after.analysis = "Rewritten".to_string();
+
+        let violations = check(&before, &after, &all_rules());
+        let repaired = repair(&before, &after, &violations);
+
+        assert!(check(&before, &repaired, &all_rules()).is_empty());
+        assert_eq!(repaired.analysis, "Original analysis");
+        let phase1: Vec<_> = repaired.tasks[0]
+            .tasks
+            .iter()
+            .map(|t| (t.description.as_str(), t.completed))
+            .collect();
+        assert_eq!(phase1, vec![("A", true), ("B", false), ("New", false)]);
+        assert_eq!(repaired.tasks[1].name, "Phase 2");
+        let phase2: Vec<_> = repaired.tasks[1]
+            .tasks
+            .iter()
+            .map(|t| (t.description.as_str(), t.completed))
+            .collect();
+        // Claude's progress on D is kept; C is back in place
+        assert_eq!(phase2, vec![("C", false), ("D", true)]);
+    }
+
+    #[test]
+    fn test_repair_recreates_removed_phase() {
+        let before = progress(&[("Phase 1", &[("A", true)]), ("Phase 2", &[("B", false)])]);
+        let after = progress(&[("Phase 1", &[("A", true)])]);
+
+        let violations = check(&before, &after, &default_rules());
+        assert_eq!(
+            violations[0],
+            Violation::PhaseRenamed {
+                from: "Phase 2".to_string(),
+                to: None,
+            }
+        );
+
+        let repaired = repair(&before, &after, &violations);
+        assert_eq!(repaired.tasks.len(), 2);
+        assert_eq!(repaired.tasks[1].name, "Phase 2");
+        assert_eq!(repaired.tasks[1].tasks[0].description, "B");
+    }
+
+    #[test]
+    fn test_tasks_with_ids_match_by_id() {
+        let mut before = progress(&[("Phase 1", &[("Write the parser", true), ("B", false)])]);
+        before.tasks[0].tasks[0].id = Some("T1".to_string());
+        let mut after = progress(&[("Phase 1", &[("Write a parser", false), ("B", false)])]);
+        after.tasks[0].tasks[0].id = Some("T1".to_string());
+
+        // Reworded, not removed; unchecking it is still caught
+        let violations = check(&before, &after, &default_rules());
+        assert_eq!(
+            violations,
+            vec![Violation::TaskUnchecked {
+                phase: "Phase 1".to_string(),
+                task: "Write the parser".to_string(),
+            }]
+        );
+
+        let repaired = repair(&before, &after, &violations);
+        assert_eq!(repaired.tasks[0].tasks.len(), 2);
+        assert_eq!(repaired.tasks[0].tasks[0].description, "Write a parser");
+        assert!(repaired.tasks[0].tasks[0].completed);
+    }
+}
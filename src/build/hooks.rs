@@ -0,0 +1,290 @@
+//! User-defined lifecycle hooks.
+//!
+//! Hook commands run through `sh -c` at fixed points of an iteration: before
+//! Claude is spawned, after its response is parsed, before the VCS commit, and
+//! when the build is done. Each hook receives a JSON description of the
+//! iteration on stdin (the hook name is also in `RSLPH_HOOK`). A non-zero exit
+//! vetoes the step: the iteration is failed or the commit skipped, and the
+//! hook's output is recorded in Recent Attempts.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::state::{BuildContext, DoneReason};
+use super::tokens::IterationTokens;
+use super::verify::summarize_output;
+
+/// Hook commands, each run through `sh -c`. Unset hooks are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HookConfig {
+    /// Before Claude is spawned; a failure skips the iteration.
+    pub pre_iteration: Option<String>,
+    /// After the response is parsed; a failure reverts the iteration's tasks.
+    pub post_iteration: Option<String>,
+    /// Before the VCS commit; a failure skips the commit.
+    pub pre_commit: Option<String>,
+    /// When the build is done; the exit status is only logged.
+    pub on_done: Option<String>,
+}
+
+/// A point in the build where a hook can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreIteration,
+    PostIteration,
+    PreCommit,
+    OnDone,
+}
+
+impl Hook {
+    /// Config key of the hook.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PreIteration => "pre_iteration",
+            Hook::PostIteration => "post_iteration",
+            Hook::PreCommit => "pre_commit",
+            Hook::OnDone => "on_done",
+        }
+    }
+
+    /// The configured command, if any.
+    pub fn command(self, hooks: &HookConfig) -> Option<&str> {
+        match self {
+            Hook::PreIteration => hooks.pre_iteration.as_deref(),
+            Hook::PostIteration => hooks.post_iteration.as_deref(),
+            Hook::PreCommit => hooks.pre_commit.as_deref(),
+            Hook::OnDone => hooks.on_done.as_deref(),
+        }
+        .filter(|cmd| !cmd.trim().is_empty())
+    }
+}
+
+/// JSON description of the iteration passed to a hook on stdin.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload {
+    pub hook: String,
+    pub project: String,
+    pub progress_path: String,
+    pub iteration: u32,
+    pub max_iterations: u32,
+    /// Task the iteration works on (None when Claude picks the next one).
+    pub task: Option<String>,
+    /// Tasks checked off by this iteration so far.
+    pub completed: Vec<String>,
+    pub tasks_completed: usize,
+    pub tasks_total: usize,
+    /// Token usage of the iteration, once Claude has run.
+    pub tokens: Option<IterationTokens>,
+    /// Why the build stopped (`on_done` only).
+    pub reason: Option<DoneReason>,
+}
+
+impl HookPayload {
+    /// Describe the current state of the build for `hook`.
+    pub fn new(ctx: &BuildContext, hook: Hook) -> Self {
+        let tokens = ctx
+            .iteration_tokens
+            .last()
+            .filter(|t| t.iteration == ctx.current_iteration)
+            .cloned();
+
+        Self {
+            hook: hook.name().to_string(),
+            project: ctx.project_name.clone(),
+            progress_path: ctx.progress_path.display().to_string(),
+            iteration: ctx.current_iteration,
+            max_iterations: ctx.max_iterations,
+            task: ctx.focus_task.clone(),
+            completed: Vec::new(),
+            tasks_completed: ctx.progress.completed_tasks(),
+            tasks_total: ctx.progress.total_tasks(),
+            tokens,
+            reason: None,
+        }
+    }
+}
+
+/// A hook that exited unsuccessfully.
+#[derive(Debug, Clone)]
+pub struct HookFailure {
+    /// Name of the hook that failed.
+    pub hook: &'static str,
+    /// One-line summary of the failure (exit code and trailing output).
+    pub summary: String,
+}
+
+/// Run `hook` if it is configured.
+///
+/// # Returns
+///
+/// * `Ok(())` - The hook passed or is not configured
+/// * `Err(HookFailure)` - The hook exited non-zero, timed out or failed to start
+pub async fn run_hook(
+    ctx: &BuildContext,
+    hook: Hook,
+    payload: &HookPayload,
+) -> Result<(), HookFailure> {
+    let Some(command) = hook.command(&ctx.config.hooks) else {
+        return Ok(());
+    };
+    let working_dir = ctx
+        .progress_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let timeout = Duration::from_secs(ctx.config.iteration_timeout);
+
+    ctx.log(&format!("[HOOK] Running {}: {}", hook.name(), command));
+    let result = run_command(hook, command, payload, working_dir, timeout).await;
+    match result {
+        Ok(()) => ctx.log(&format!("[HOOK] {} passed", hook.name())),
+        Err(ref failure) => ctx.log(&format!(
+            "[HOOK] {} failed: {}",
+            failure.hook, failure.summary
+        )),
+    }
+    result
+}
+
+/// Run the `on_done` hook for a finished build. Failures are only logged.
+pub async fn run_on_done(ctx: &BuildContext, reason: &DoneReason) {
+    let mut payload = HookPayload::new(ctx, Hook::OnDone);
+    payload.reason = Some(reason.clone());
+    let _ = run_hook(ctx, Hook::OnDone, &payload).await;
+}
+
+/// Run a hook command with the payload on stdin.
+async fn run_command(
+    hook: Hook,
+    command: &str,
+    payload: &HookPayload,
+    working_dir: &Path,
+    timeout: Duration,
+) -> Result<(), HookFailure> {
+    let failure = |summary: String| HookFailure {
+        hook: hook.name(),
+        summary,
+    };
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("RSLPH_HOOK", hook.name())
+        .current_dir(working_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| failure(format!("failed to start: {}", e)))?;
+
+    // A hook that ignores stdin may exit before reading it
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = serde_json::to_string(payload).unwrap_or_default();
+        let _ = stdin.write_all(json.as_bytes()).await;
+    }
+
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(failure(format!("failed to run: {}", e))),
+        Err(_) => return Err(failure(format!("timed out after {}s", timeout.as_secs()))),
+    };
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push('\n');
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    let exit = output
+        .status
+        .code()
+        .map(|c| format!("exit code {}", c))
+        .unwrap_or_else(|| "terminated by signal".to_string());
+
+    Err(failure(format!(
+        "{}: {}",
+        exit,
+        summarize_output(&combined)
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn payload() -> HookPayload {
+        HookPayload {
+            hook: "post_iteration".to_string(),
+            project: "Hooks".to_string(),
+            progress_path: "PROGRESS.md".to_string(),
+            iteration: 2,
+            max_iterations: 10,
+            task: None,
+            completed: vec!["Task 1".to_string()],
+            tasks_completed: 1,
+            tasks_total: 3,
+            tokens: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_unset_and_blank_hooks_are_skipped() {
+        let hooks = HookConfig {
+            pre_commit: Some("  ".to_string()),
+            on_done: Some("true".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(Hook::PreIteration.command(&hooks), None);
+        assert_eq!(Hook::PreCommit.command(&hooks), None);
+        assert_eq!(Hook::OnDone.command(&hooks), Some("true"));
+    }
+
+    #[tokio::test]
+    async fn test_hook_receives_payload_on_stdin() {
+        let dir = TempDir::new().expect("temp dir");
+        let result = run_command(
+            Hook::PostIteration,
+            "cat > payload.json && test \"$RSLPH_HOOK\" = post_iteration",
+            &payload(),
+            dir.path(),
+            Duration::from_secs(10),
+        )
+        .await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        let json: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("payload.json")).expect("payload"),
+        )
+        .expect("valid json");
+        assert_eq!(json["hook"], "post_iteration");
+        assert_eq!(json["iteration"], 2);
+        assert_eq!(json["completed"][0], "Task 1");
+    }
+
+    #[tokio::test]
+    async fn test_hook_failure_keeps_output() {
+        let dir = TempDir::new().expect("temp dir");
+        let failure = run_command(
+            Hook::PreCommit,
+            "echo 'license header missing' >&2; exit 3",
+            &payload(),
+            dir.path(),
+            Duration::from_secs(10),
+        )
+        .await
+        .expect_err("hook should fail");
+        assert_eq!(failure.hook, "pre_commit");
+        assert_eq!(failure.summary, "exit code 3: license header missing");
+    }
+}
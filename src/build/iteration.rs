@@ -1,6 +1,6 @@
 //! Single iteration execution logic.
 //!
-//! Handles spawning Claude, parsing response, and updating progress file.
+//! Handles spawning the agent, parsing response, and updating progress file.
 
 use std::path::Path;
 use std::time::Duration;
@@ -8,79 +8,395 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::error::RslphError;
-use crate::progress::ProgressFile;
-use crate::prompts::get_build_prompt_for_mode;
+use crate::planning::detect_stack;
+use crate::pricing::{format_cost, PricingTable};
+use crate::progress::{ProgressFile, ProgressFormat};
+use crate::prompts::{load_build_message, load_build_prompt, render, PromptVars};
 use crate::subprocess::{
-    build_claude_args, format_tool_summary, ClaudeRunner, OutputLine, StreamEvent, StreamResponse,
+    agent_backend, AgentKind, AgentOutput, AgentRequest, AgentResponse, ClaudeRunner, OutputLine,
 };
 use crate::tui::SubprocessEvent;
+use crate::vcs::Snapshot;
 
+use super::escalation;
+use super::events::{BuildEvent, EventSink};
+use super::guard::{self, GuardrailAction};
+use super::hooks::{run_hook, Hook, HookPayload};
+use super::pause::timeout_with_suspend;
 use super::state::{BuildContext, DoneReason, IterationResult};
-use super::tokens::IterationTokens;
+use super::tokens::{IterationTokens, TokenUsage};
+use super::transcript::Transcript;
+use super::verify::{newly_completed, resolve_gates, revert_tasks, run_gates};
 
 /// Format commit message for an iteration.
-fn format_iteration_commit(project_name: &str, iteration: u32, tasks_completed: u32) -> String {
+pub fn format_iteration_commit(project_name: &str, iteration: u32, tasks_completed: u32) -> String {
     format!(
         "[{}][iter {}] Completed {} task(s)",
         project_name, iteration, tasks_completed
     )
 }
 
-/// Parse a stream-json line and send appropriate events to TUI.
+/// Open the iteration's transcript, if transcripts are archived.
 ///
-/// Returns the parsed event for response accumulation, or None if parsing failed.
-fn parse_and_stream_line(
-    line: &str,
-    tui_tx: &mpsc::UnboundedSender<SubprocessEvent>,
-) -> Option<StreamEvent> {
-    let event = match StreamEvent::parse(line) {
-        Ok(e) => e,
-        Err(_) => return None,
+/// Failures are logged and the iteration runs without a transcript.
+fn open_transcript(
+    ctx: &BuildContext,
+    backend: AgentKind,
+    model: Option<&str>,
+    system_prompt: &str,
+    user_input: &str,
+) -> Option<Transcript> {
+    let archive = ctx.transcripts.as_ref()?;
+    match archive.start(
+        ctx.current_iteration,
+        backend,
+        model,
+        system_prompt,
+        user_input,
+    ) {
+        Ok(transcript) => Some(transcript),
+        Err(e) => {
+            ctx.log(&format!(
+                "[TRANSCRIPT] Warning: failed to open {}: {}",
+                archive.path(ctx.current_iteration).display(),
+                e
+            ));
+            None
+        }
+    }
+}
+
+/// Capture the working copy before Claude runs, if failed iterations are rolled back.
+fn take_snapshot(ctx: &BuildContext) -> Option<Snapshot> {
+    if !ctx.config.rollback_failed_iterations {
+        return None;
+    }
+
+    let Some(ref vcs) = ctx.vcs else {
+        ctx.log("[VCS] Warning: rollback_failed_iterations requires a Git or Sapling repository");
+        return None;
     };
 
-    // Send raw event for conversation view extraction
-    let _ = tui_tx.send(SubprocessEvent::StreamEvent(event.clone()));
+    match vcs.snapshot() {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            ctx.log(&format!(
+                "[VCS] Warning: Cannot snapshot working tree, rollback disabled for this iteration: {}",
+                e
+            ));
+            None
+        }
+    }
+}
+
+/// Discard the iteration's working tree changes by restoring `snapshot`.
+///
+/// Returns a note to append to the iteration's Recent Attempts entry, or None
+/// if no snapshot was taken.
+fn rollback_iteration(ctx: &BuildContext, snapshot: Option<&Snapshot>) -> Option<String> {
+    let (Some(vcs), Some(snapshot)) = (ctx.vcs.as_ref(), snapshot) else {
+        return None;
+    };
 
-    // Check if Claude is asking for user input
-    if let Some(question) = event.is_input_required() {
-        let _ = tui_tx.send(SubprocessEvent::InputRequired { question });
+    match vcs.restore(snapshot) {
+        Ok(()) => {
+            ctx.log(&format!(
+                "[VCS] Rolled back working tree changes from iteration {}",
+                ctx.current_iteration
+            ));
+            Some("working tree changes rolled back".to_string())
+        }
+        Err(e) => {
+            ctx.log(&format!("[VCS] Warning: Rollback failed: {}", e));
+            Some(format!("rollback failed: {}", e))
+        }
     }
+}
 
-    // Send assistant text as ClaudeOutput
-    if event.is_assistant() {
-        if let Some(text) = event.extract_text() {
-            if !text.is_empty() {
-                let _ = tui_tx.send(SubprocessEvent::Output(text));
-            }
+/// Append an optional rollback note to an attempt result.
+fn with_rollback_note(result: String, note: Option<String>) -> String {
+    match note {
+        Some(note) => format!("{}; {}", result, note),
+        None => result,
+    }
+}
+
+/// Run verification gates for tasks checked off this iteration.
+///
+/// If a gate fails, the newly checked tasks are reverted in `updated`, the
+/// working tree is rolled back to `snapshot` (when present), and the failure is
+/// recorded as an attempt so the next iteration sees it.
+async fn verify_iteration(
+    ctx: &BuildContext,
+    updated: &mut ProgressFile,
+    working_dir: &Path,
+    snapshot: Option<&Snapshot>,
+) {
+    let newly = newly_completed(&ctx.progress, updated);
+    if newly.is_empty() {
+        return;
+    }
+
+    let gates = resolve_gates(&ctx.config, working_dir);
+    if gates.is_empty() {
+        ctx.log("[VERIFY] No verification gates configured or detected");
+        return;
+    }
+
+    let names: Vec<&str> = gates.iter().map(|g| g.name).collect();
+    ctx.log(&format!(
+        "[VERIFY] Checking {} task(s) with gates: {}",
+        newly.len(),
+        names.join(", ")
+    ));
+
+    let timeout = Duration::from_secs(ctx.config.iteration_timeout);
+    match run_gates(&gates, working_dir, timeout).await {
+        Ok(()) => ctx.log("[VERIFY] All gates passed"),
+        Err(failure) => {
+            ctx.log(&format!(
+                "[VERIFY] {} gate failed, reverting {} task(s): {}",
+                failure.gate,
+                newly.len(),
+                failure.summary
+            ));
+            revert_tasks(updated, &newly);
+            let rollback = rollback_iteration(ctx, snapshot);
+
+            let descriptions: Vec<&str> = newly.iter().map(|(_, d)| d.as_str()).collect();
+            updated.add_attempt(
+                ctx.current_iteration,
+                &format!("Complete: {}", descriptions.join("; ")),
+                &with_rollback_note(
+                    format!(
+                        "{} gate failed (`{}`): {}",
+                        failure.gate, failure.command, failure.summary
+                    ),
+                    rollback,
+                ),
+                Some(&format!(
+                    "Fix the {} failure before checking these tasks off",
+                    failure.gate
+                )),
+            );
         }
+    }
+}
+
+/// Run the post_iteration hook on Claude's parsed progress file.
+///
+/// A failing hook fails the iteration like a verification gate: newly checked
+/// tasks are reverted, the working tree is rolled back to `snapshot` (when
+/// present), and the hook's output is recorded as an attempt.
+async fn post_iteration_hook(
+    ctx: &BuildContext,
+    updated: &mut ProgressFile,
+    task: &Option<String>,
+    snapshot: Option<&Snapshot>,
+) {
+    let newly = newly_completed(&ctx.progress, updated);
+    let descriptions: Vec<String> = newly.iter().map(|(_, d)| d.clone()).collect();
 
-        // Send tool uses as ToolUse events with formatted summary
-        for (tool_name, input) in event.extract_tool_uses() {
-            let summary = format_tool_summary(&tool_name, &input);
-            let _ = tui_tx.send(SubprocessEvent::ToolUse {
-                tool_name,
-                content: summary,
-            });
+    let mut payload = HookPayload::new(ctx, Hook::PostIteration);
+    payload.task = task.clone();
+    payload.completed = descriptions.clone();
+    payload.tasks_completed = updated.completed_tasks();
+
+    let Err(failure) = run_hook(ctx, Hook::PostIteration, &payload).await else {
+        return;
+    };
+
+    revert_tasks(updated, &newly);
+    let rollback = rollback_iteration(ctx, snapshot);
+    let tried = if descriptions.is_empty() {
+        "Run iteration".to_string()
+    } else {
+        format!("Complete: {}", descriptions.join("; "))
+    };
+    updated.add_attempt(
+        ctx.current_iteration,
+        &tried,
+        &with_rollback_note(
+            format!("post_iteration hook failed: {}", failure.summary),
+            rollback,
+        ),
+        Some("Resolve the post_iteration hook failure"),
+    );
+}
+
+/// Check Claude's progress file against the previous one.
+///
+/// Rule violations are reported, rejected (the previous progress file is kept
+/// and the working tree rolled back to `snapshot`, when present) or repaired,
+/// and recorded as an attempt so the next iteration sees them.
+fn guard_progress(ctx: &BuildContext, updated: &mut ProgressFile, snapshot: Option<&Snapshot>) {
+    if ctx.config.guardrails == GuardrailAction::Off {
+        return;
+    }
+
+    let violations = guard::check(&ctx.progress, updated, &ctx.config.guardrail_rules);
+    if violations.is_empty() {
+        return;
+    }
+
+    let summary = violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ");
+    let next = "Only check off tasks; keep every task, phase and the Analysis as they are";
+
+    match ctx.config.guardrails {
+        GuardrailAction::Warn => {
+            ctx.log(&format!("[GUARD] Warning: progress file breaks guardrails: {}", summary));
+            updated.add_attempt(
+                ctx.current_iteration,
+                "Update progress file",
+                &format!("Guardrail warning: {}", summary),
+                Some(next),
+            );
         }
+        GuardrailAction::Reject => {
+            ctx.log(&format!("[GUARD] Rejected progress file: {}", summary));
+            let rollback = rollback_iteration(ctx, snapshot);
+            *updated = ctx.progress.clone();
+            updated.add_attempt(
+                ctx.current_iteration,
+                "Update progress file",
+                &with_rollback_note(format!("Rejected by guardrails: {}", summary), rollback),
+                Some(next),
+            );
+        }
+        GuardrailAction::Repair => {
+            ctx.log(&format!("[GUARD] Repaired progress file: {}", summary));
+            *updated = guard::repair(&ctx.progress, updated, &violations);
+            updated.add_attempt(
+                ctx.current_iteration,
+                "Update progress file",
+                &format!("Repaired by guardrails: {}", summary),
+                Some(next),
+            );
+        }
+        GuardrailAction::Off => {}
+    }
+}
+
+/// Template variables for the build prompts of the current iteration.
+fn prompt_vars(
+    ctx: &BuildContext,
+    working_dir: &Path,
+    instructions: String,
+) -> Result<PromptVars, RslphError> {
+    let next_task = match (&ctx.focus_task, ctx.progress.next_task()) {
+        (Some(task), _) => task.clone(),
+        (None, Some((_, task))) => task.description.clone(),
+        (None, None) => String::new(),
+    };
+
+    let recent_attempts = if ctx.progress.recent_attempts.is_empty() {
+        "None".to_string()
+    } else {
+        ctx.progress
+            .recent_attempts
+            .iter()
+            .map(|a| {
+                let next = a
+                    .next
+                    .as_deref()
+                    .map(|n| format!(" (next: {})", n))
+                    .unwrap_or_default();
+                format!(
+                    "- Iteration {}: {} -> {}{}",
+                    a.iteration, a.tried, a.result, next
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let format = ProgressFormat::from_path(&ctx.progress_path);
+    Ok(PromptVars::new()
+        .with("progress", ctx.progress.render(format)?)
+        .with("next_task", next_task)
+        .with("iteration", ctx.current_iteration.to_string())
+        .with("max_iterations", ctx.max_iterations.to_string())
+        .with("stack", detect_stack(working_dir).to_summary())
+        .with("recent_attempts", recent_attempts)
+        .with("instructions", instructions))
+}
+
+/// Report the tools the agent used to the `--events` stream.
+fn emit_tool_uses(events: Option<&EventSink>, iteration: u32, output: &AgentOutput) {
+    let Some(events) = events else {
+        return;
+    };
+    for (tool, summary) in &output.tool_uses {
+        events.emit(BuildEvent::ToolUse {
+            iteration,
+            tool: tool.clone(),
+            summary: summary.clone(),
+        });
+    }
+}
+
+/// Send a normalized line of agent output to the TUI.
+pub fn stream_output(
+    output: &AgentOutput,
+    tui_tx: &mpsc::UnboundedSender<SubprocessEvent>,
+    pricing: &PricingTable,
+) {
+    // Items for the conversation view
+    if !output.items.is_empty() {
+        let _ = tui_tx.send(SubprocessEvent::Conversation(output.items.clone()));
+    }
 
-        // Send context usage if available
-        if let Some(usage) = event.usage() {
-            // Send token usage event for TUI display
-            let _ = tui_tx.send(SubprocessEvent::TokenUsage {
-                input_tokens: usage.input_tokens,
-                output_tokens: usage.output_tokens,
-                cache_creation_input_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
-                cache_read_input_tokens: usage.cache_read_input_tokens.unwrap_or(0),
-            });
-
-            // Estimate context usage as output_tokens / 200k (rough estimate)
-            // A more accurate approach would track input+output vs max context
-            let ratio = (usage.input_tokens + usage.output_tokens) as f64 / 200_000.0;
-            let _ = tui_tx.send(SubprocessEvent::Usage(ratio.min(1.0)));
+    // Check if the agent is asking for user input
+    if let Some(ref question) = output.input_required {
+        let _ = tui_tx.send(SubprocessEvent::InputRequired {
+            question: question.clone(),
+        });
+    }
+
+    // Send response text as ClaudeOutput
+    if let Some(ref text) = output.text {
+        let text = text.trim_end_matches('\n');
+        if !text.is_empty() {
+            let _ = tui_tx.send(SubprocessEvent::Output(text.to_string()));
         }
     }
 
-    Some(event)
+    // Send tool uses as ToolUse events with formatted summary
+    for (tool_name, summary) in &output.tool_uses {
+        let _ = tui_tx.send(SubprocessEvent::ToolUse {
+            tool_name: tool_name.clone(),
+            content: summary.clone(),
+        });
+    }
+
+    // Send context usage if available
+    if let Some(ref usage) = output.usage {
+        let tokens = TokenUsage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_creation_input_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+            cache_read_input_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+            ..Default::default()
+        };
+
+        // Send token usage event for TUI display
+        let _ = tui_tx.send(SubprocessEvent::TokenUsage {
+            input_tokens: tokens.input_tokens,
+            output_tokens: tokens.output_tokens,
+            cache_creation_input_tokens: tokens.cache_creation_input_tokens,
+            cache_read_input_tokens: tokens.cache_read_input_tokens,
+            cost_usd: pricing.cost_usd(output.model.as_deref(), &tokens),
+        });
+
+        // Estimate context usage as output_tokens / 200k (rough estimate)
+        // A more accurate approach would track input+output vs max context
+        let ratio = (usage.input_tokens + usage.output_tokens) as f64 / 200_000.0;
+        let _ = tui_tx.send(SubprocessEvent::Usage(ratio.min(1.0)));
+    }
 }
 
 /// Run a single iteration of the build loop.
@@ -88,7 +404,7 @@ fn parse_and_stream_line(
 /// This function:
 /// 1. Re-reads the progress file (handles external edits)
 /// 2. Checks for early exit conditions (RALPH_DONE, all tasks complete)
-/// 3. Spawns a fresh Claude subprocess with the build prompt
+/// 3. Spawns a fresh agent subprocess (Claude by default) with the build prompt
 /// 4. Parses the response and updates the progress file atomically
 ///
 /// # Arguments
@@ -103,6 +419,8 @@ fn parse_and_stream_line(
 pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationResult, RslphError> {
     // Step 1: Re-read progress file (may have been updated externally)
     ctx.progress = ProgressFile::load(&ctx.progress_path)?;
+    // As read, to spot edits made on disk while the agent runs
+    let loaded = ctx.progress.clone();
 
     // Track tasks before iteration for diff
     let tasks_before = ctx.progress.completed_tasks();
@@ -118,29 +436,16 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         return Ok(IterationResult::Done(DoneReason::AllTasksComplete));
     }
 
-    // Step 3: Build prompt with current progress context
-    let system_prompt = get_build_prompt_for_mode(ctx.mode);
-
-    // Clear completed this iteration from previous iteration
-    ctx.progress.clear_iteration_completed();
-
-    let user_input = format!(
-        "## Current Progress\n\n{}\n\n## Instructions\n\nExecute the next incomplete task. Output the complete updated progress file.",
-        ctx.progress.to_markdown()
-    );
+    if ctx.progress.is_finished_with_blocked() {
+        return Ok(IterationResult::Done(DoneReason::CompleteWithBlocked));
+    }
 
-    // Step 4: Build Claude CLI args for headless mode
-    let args = vec![
-        "-p".to_string(),        // Print mode (headless)
-        "--verbose".to_string(), // Required for stream-json with -p
-        "--output-format".to_string(),
-        "stream-json".to_string(), // JSONL for structured parsing
-        "--system-prompt".to_string(),
-        system_prompt,
-        user_input,
-    ];
+    // Everything left waits on a blocked task
+    if ctx.progress.next_task().is_none() && ctx.progress.blocked_tasks() > 0 {
+        return Ok(IterationResult::Done(DoneReason::Stalled));
+    }
 
-    // Step 5: Spawn fresh Claude subprocess
+    // Step 3: Build prompt with current progress context
     // Handle both None parent and empty parent (when path is just filename)
     let working_dir = ctx
         .progress_path
@@ -148,18 +453,112 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         .filter(|p| !p.as_os_str().is_empty())
         .unwrap_or(Path::new("."));
 
-    // Build combined args: base_args + dsp (if enabled) + command args
-    let combined_args = build_claude_args(&ctx.config.claude_cmd.base_args, &args, ctx.no_dsp);
+    // Pick the model: a stall escalation wins over the per-task ladder
+    let target = match ctx.focus_task {
+        Some(ref task) => Some(task.clone()),
+        None => ctx.progress.next_task().map(|(_, t)| t.description.clone()),
+    };
+    let failures = ctx.task_attempts.start(target.as_deref());
+    ctx.emit(BuildEvent::IterationStarted {
+        iteration: ctx.current_iteration,
+        task: target.clone(),
+    });
+    let model = match (ctx.stall.escalated, &ctx.config.stall_model) {
+        (true, Some(model)) => Some(model.clone()),
+        _ => escalation::build_model(&ctx.config, failures),
+    };
+    if failures > 0 && model != ctx.config.build_model {
+        if let Some(ref model) = model {
+            ctx.log(&format!(
+                "[BUILD] Escalating to {} after {} unfinished iteration(s) on: {}",
+                model,
+                failures,
+                target.as_deref().unwrap_or("current task")
+            ));
+        }
+    }
+
+    // Clear completed this iteration from previous iteration
+    ctx.progress.clear_iteration_completed();
+
+    // Name the ready task, so the agent follows dependency order rather than file order
+    let instruction = match (&ctx.focus_task, ctx.progress.next_task()) {
+        (Some(task), _) => format!(
+            "Execute only this task: {}. Other tasks are handled separately; leave them unchanged.",
+            task
+        ),
+        (None, Some((_, task))) => format!(
+            "Execute this task, the next one that is ready to start: {}{}.",
+            task.id.as_ref().map(|id| format!("{}: ", id)).unwrap_or_default(),
+            task.description
+        ),
+        (None, None) if ctx.progress.blocked_tasks() > 0 => {
+            "Execute the next incomplete task that is not marked blocked ([-]).".to_string()
+        }
+        (None, None) => "Execute the next incomplete task.".to_string(),
+    };
+    let instruction = if ctx.stall.escalated {
+        format!(
+            "{} Recent iterations made no progress; step back and try a different approach.",
+            instruction
+        )
+    } else {
+        instruction
+    };
+    // A structured progress file is exchanged as-is instead of as markdown
+    let format = ProgressFormat::from_path(&ctx.progress_path);
+    let instruction = if format.is_structured() {
+        let name = format.to_string().to_uppercase();
+        format!(
+            "{} The progress file is {}: respond with the complete updated progress file \
+             as {} in the same structure instead of markdown, with no other text.",
+            instruction, name, name
+        )
+    } else {
+        instruction
+    };
+    let vars = prompt_vars(ctx, working_dir, instruction)?;
+    let system_prompt = render(&load_build_prompt(&ctx.config, ctx.mode)?, &vars);
+    let user_input = render(&load_build_message(&ctx.config)?, &vars);
+
+    // Step 4: Build the agent command line
+    let backend = agent_backend(&ctx.config, ctx.no_dsp)?;
+    let invocation = backend.invocation(&AgentRequest {
+        system_prompt: &system_prompt,
+        user_input: &user_input,
+        model: model.as_deref(),
+    });
+
+    // The pre_iteration hook can veto the iteration before the agent is spawned
+    let mut payload = HookPayload::new(ctx, Hook::PreIteration);
+    payload.task = target.clone();
+    if let Err(failure) = run_hook(ctx, Hook::PreIteration, &payload).await {
+        ctx.progress.add_attempt(
+            ctx.current_iteration,
+            "Start iteration",
+            &format!("Vetoed by pre_iteration hook: {}", failure.summary),
+            Some("Resolve the pre_iteration hook failure"),
+        );
+        ctx.progress
+            .trim_attempts(ctx.config.recent_threads as usize);
+        ctx.progress.write(&ctx.progress_path)?;
+        return Ok(IterationResult::Continue { tasks_completed: 0 });
+    }
+
+    // Step 5: Spawn fresh agent subprocess
+    // Snapshot the working copy so a failed iteration can be discarded
+    let snapshot = take_snapshot(ctx);
 
     ctx.log(&format!(
-        "[TRACE] Iteration {}: Spawning Claude: {} {}",
+        "[TRACE] Iteration {}: Spawning {}: {} {}",
         ctx.current_iteration,
-        ctx.config.claude_cmd.command,
-        combined_args.join(" ")
+        backend.kind().label(),
+        invocation.command,
+        invocation.args.join(" ")
     ));
 
     let runner_result =
-        ClaudeRunner::spawn(&ctx.config.claude_cmd.command, &combined_args, working_dir).await;
+        ClaudeRunner::spawn(&invocation.command, &invocation.args, working_dir).await;
 
     let mut runner = match runner_result {
         Ok(r) => r,
@@ -167,17 +566,20 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
             // Log attempt on spawn failure
             ctx.progress.add_attempt(
                 ctx.current_iteration,
-                "Spawn Claude subprocess",
+                &format!("Spawn {} subprocess", backend.kind().label()),
                 &format!("Error: {}", e),
-                Some("Check claude_path configuration"),
+                Some(backend.spawn_hint()),
             );
             ctx.progress
                 .trim_attempts(ctx.config.recent_threads as usize);
             ctx.progress.write(&ctx.progress_path)?;
             let path_env = std::env::var("PATH").unwrap_or_else(|_| "(not set)".to_string());
             return Err(RslphError::Subprocess(format!(
-                "Failed to spawn '{}': {}. Ensure claude is in PATH or set RSLPH_CLAUDE_CMD environment variable. PATH: {}",
-                ctx.config.claude_cmd.command, e, path_env
+                "Failed to spawn '{}': {}. {}. PATH: {}",
+                invocation.command,
+                e,
+                backend.spawn_hint(),
+                path_env
             )));
         }
     };
@@ -186,24 +588,42 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         "[TRACE] Spawned subprocess with PID: {:?}",
         runner.id()
     ));
+    let mut transcript = open_transcript(
+        ctx,
+        backend.kind(),
+        model.as_deref(),
+        &system_prompt,
+        &user_input,
+    );
 
     // Step 6: Run subprocess and collect output
     // When TUI is active, stream output to TUI while collecting for response parsing
     let timeout = Duration::from_secs(ctx.config.iteration_timeout);
-    let mut stream_response = StreamResponse::new();
+    let mut response = AgentResponse::new();
 
     let run_result = if let Some(ref tui_tx) = ctx.tui_tx {
         // Streaming mode: use run_with_channel and parse+stream each line
         let (line_tx, mut line_rx) = mpsc::unbounded_channel::<OutputLine>();
 
-        // Spawn the runner with channel
-        let cancel_token = ctx.cancel_token.clone();
+        // Capture PID before the runner moves into its task (for pause suspension)
+        let pid = runner.id();
+        let suspend_rx = ctx
+            .pause_rx
+            .clone()
+            .filter(|_| ctx.config.pause_suspends_subprocess);
+
+        // Spawn the runner with channel; `stop` ends it when the timeout fires
+        let stop = ctx.cancel_token.child_token();
+        let runner_stop = stop.clone();
         let runner_handle =
-            tokio::spawn(async move { runner.run_with_channel(line_tx, cancel_token).await });
+            tokio::spawn(async move { runner.run_with_channel(line_tx, runner_stop).await });
 
-        // Process lines as they arrive, with timeout
+        // Process lines as they arrive, with timeout (suspended time excluded)
         let tui_tx_clone = tui_tx.clone();
-        let process_result = tokio::time::timeout(timeout, async {
+        let pricing = PricingTable::from_config(&ctx.config);
+        let events = ctx.events.clone();
+        let iteration = ctx.current_iteration;
+        let process_lines = async {
             let _ = tui_tx_clone.send(SubprocessEvent::Log("[TRACE] Starting subprocess output streaming".to_string()));
             while let Some(line) = line_rx.recv().await {
                 match &line {
@@ -213,21 +633,32 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
                     }
                     OutputLine::Stderr(s) => {
                         let _ = tui_tx_clone.send(SubprocessEvent::Log(format!("[TRACE] Received stderr: {}", s)));
+                        if let Some(ref mut transcript) = transcript {
+                            transcript.stderr(s);
+                        }
                         // Forward stderr to TUI with [stderr] prefix
                         let _ = tui_tx_clone.send(SubprocessEvent::Stderr(s.clone()));
                     }
                 }
                 if let OutputLine::Stdout(s) = &line {
+                    if let Some(ref mut transcript) = transcript {
+                        transcript.stdout(s);
+                    }
                     // Stream to TUI
-                    if let Some(event) = parse_and_stream_line(s, &tui_tx_clone) {
-                        stream_response.process_event(&event);
+                    if let Some(output) = backend.parse_line(s) {
+                        stream_output(&output, &tui_tx_clone, &pricing);
+                        emit_tool_uses(events.as_ref(), iteration, &output);
+                        response.process(&output);
                     }
                 }
             }
             let _ = tui_tx_clone.send(SubprocessEvent::Log("[TRACE] Subprocess output stream ended".to_string()));
             Ok::<(), RslphError>(())
-        })
-        .await;
+        };
+        let process_result = timeout_with_suspend(timeout, process_lines, pid, suspend_rx).await;
+        if process_result.is_none() {
+            stop.cancel();
+        }
 
         // Wait for runner to complete
         let runner_result = runner_handle
@@ -236,44 +667,132 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
 
         // Check for timeout or runner error
         match process_result {
-            Ok(Ok(())) => runner_result,
-            Ok(Err(e)) => Err(e),
-            Err(_) => Err(RslphError::Timeout(timeout.as_secs())),
+            Some(Ok(())) => runner_result,
+            Some(Err(e)) => Err(e),
+            None => Err(RslphError::Timeout(timeout.as_secs())),
         }
     } else {
-        // Non-streaming mode: collect all output then parse
-        // Don't use ? here - we want to catch timeout errors and handle them below
+        // Non-streaming mode: log instead of streaming to the TUI, but still read
+        // lines as they arrive so a timed-out run keeps the usage reported so far
         ctx.log("[TRACE] Processing subprocess output (non-streaming)");
-        match runner
-            .run_with_timeout(timeout, ctx.cancel_token.clone())
-            .await
-        {
-            Ok(output) => {
-                // Parse JSONL response
-                for line in &output {
-                    if let OutputLine::Stdout(s) = line {
-                        stream_response.process_line(s);
-                    } else if let OutputLine::Stderr(s) = line {
+        let (line_tx, mut line_rx) = mpsc::unbounded_channel::<OutputLine>();
+        let stop = ctx.cancel_token.child_token();
+        let runner_stop = stop.clone();
+        let runner_handle =
+            tokio::spawn(async move { runner.run_with_channel(line_tx, runner_stop).await });
+
+        let mut lines = 0;
+        let process_lines = async {
+            while let Some(line) = line_rx.recv().await {
+                lines += 1;
+                match &line {
+                    OutputLine::Stdout(s) => {
+                        if let Some(ref mut transcript) = transcript {
+                            transcript.stdout(s);
+                        }
+                        if let Some(output) = backend.parse_line(s) {
+                            emit_tool_uses(ctx.events.as_ref(), ctx.current_iteration, &output);
+                            response.process(&output);
+                        }
+                    }
+                    OutputLine::Stderr(s) => {
+                        if let Some(ref mut transcript) = transcript {
+                            transcript.stderr(s);
+                        }
                         // Log stderr in non-TUI mode
                         ctx.log(&format!("[TRACE] Received stderr: {}", s));
                         ctx.log(&format!("[stderr] {}", s));
                     }
                 }
-                ctx.log(&format!("[TRACE] Processed {} output lines", output.len()));
-                Ok(())
             }
-            Err(e) => Err(e),
+        };
+        let process_result = tokio::time::timeout(timeout, process_lines).await;
+        if process_result.is_err() {
+            stop.cancel();
+        }
+
+        let runner_result = runner_handle
+            .await
+            .map_err(|e| RslphError::Subprocess(format!("Runner task failed: {}", e)))?;
+        ctx.log(&format!("[TRACE] Processed {} output lines", lines));
+
+        match process_result {
+            Ok(()) => runner_result,
+            Err(_) => Err(RslphError::Timeout(timeout.as_secs())),
         }
     };
 
+    if let Some(transcript) = transcript {
+        match transcript.finish() {
+            Ok(path) => ctx.log(&format!("[TRANSCRIPT] Saved {}", path.display())),
+            Err(e) => ctx.log(&format!(
+                "[TRANSCRIPT] Warning: failed to write transcript: {}",
+                e
+            )),
+        }
+    }
+
+    ctx.log(&format!(
+        "[TRACE] Tokens: {} in / {} out / {} cache_write / {} cache_read",
+        response.input_tokens,
+        response.output_tokens,
+        response.cache_creation_input_tokens,
+        response.cache_read_input_tokens
+    ));
+
+    // Accumulate tokens and cost, including what a failed or timed-out run used
+    let usage = TokenUsage {
+        input_tokens: response.input_tokens,
+        output_tokens: response.output_tokens,
+        cache_creation_input_tokens: response.cache_creation_input_tokens,
+        cache_read_input_tokens: response.cache_read_input_tokens,
+        ..Default::default()
+    };
+    let cost_usd =
+        PricingTable::from_config(&ctx.config).cost_usd(response.model.as_deref(), &usage);
+    ctx.log(&format!("[TRACE] Cost: {}", format_cost(cost_usd)));
+
+    let iteration_tokens = IterationTokens {
+        iteration: ctx.current_iteration,
+        input_tokens: response.input_tokens,
+        output_tokens: response.output_tokens,
+        cache_creation_input_tokens: response.cache_creation_input_tokens,
+        cache_read_input_tokens: response.cache_read_input_tokens,
+        cost_usd,
+        model: response.model.clone().or(model.clone()),
+    };
+    ctx.emit(BuildEvent::TokenUsage {
+        tokens: iteration_tokens.clone(),
+    });
+    ctx.iteration_tokens.push(iteration_tokens);
+    ctx.total_tokens.input_tokens += response.input_tokens;
+    ctx.total_tokens.output_tokens += response.output_tokens;
+    ctx.total_tokens.cache_creation_input_tokens += response.cache_creation_input_tokens;
+    ctx.total_tokens.cache_read_input_tokens += response.cache_read_input_tokens;
+    ctx.total_tokens.cost_usd += cost_usd;
+
     // Handle run errors
     if let Err(e) = run_result {
+        let spent = usage.total();
+        if spent > 0 {
+            ctx.log(&format!(
+                "[BUILD] Iteration {} used {} tokens ({}) before stopping; counted toward the budget",
+                ctx.current_iteration,
+                spent,
+                format_cost(cost_usd)
+            ));
+        }
+
         // Check if this is a timeout error - return Timeout result for retry
         if matches!(e, RslphError::Timeout(_)) {
+            let rollback = rollback_iteration(ctx, snapshot.as_ref());
             ctx.progress.add_attempt(
                 ctx.current_iteration,
-                "Execute Claude subprocess",
-                &format!("Timeout after {}s", ctx.config.iteration_timeout),
+                &format!("Execute {} subprocess", backend.kind().label()),
+                &with_rollback_note(
+                    format!("Timeout after {}s", ctx.config.iteration_timeout),
+                    rollback,
+                ),
                 Some("Retrying iteration"),
             );
             ctx.progress
@@ -284,7 +803,7 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
 
         ctx.progress.add_attempt(
             ctx.current_iteration,
-            "Execute Claude subprocess",
+            &format!("Execute {} subprocess", backend.kind().label()),
             &format!("Error: {}", e),
             Some("Retry or check subprocess"),
         );
@@ -295,46 +814,26 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
     }
 
     // Step 7: Extract response text
-    let response_text = stream_response.text;
+    let response_text = response.text;
 
     ctx.log(&format!(
-        "[TRACE] Claude output length: {} chars",
+        "[TRACE] {} output length: {} chars",
+        backend.kind().label(),
         response_text.len()
     ));
-    if let Some(model) = &stream_response.model {
+    if let Some(model) = &response.model {
         ctx.log(&format!("[TRACE] Model: {}", model));
     }
-    ctx.log(&format!(
-        "[TRACE] Tokens: {} in / {} out / {} cache_write / {} cache_read",
-        stream_response.input_tokens,
-        stream_response.output_tokens,
-        stream_response.cache_creation_input_tokens,
-        stream_response.cache_read_input_tokens
-    ));
-
-    // Accumulate tokens from this iteration
-    let iteration_tokens = IterationTokens {
-        iteration: ctx.current_iteration,
-        input_tokens: stream_response.input_tokens,
-        output_tokens: stream_response.output_tokens,
-        cache_creation_input_tokens: stream_response.cache_creation_input_tokens,
-        cache_read_input_tokens: stream_response.cache_read_input_tokens,
-    };
-    ctx.iteration_tokens.push(iteration_tokens);
-    ctx.total_tokens.input_tokens += stream_response.input_tokens;
-    ctx.total_tokens.output_tokens += stream_response.output_tokens;
-    ctx.total_tokens.cache_creation_input_tokens += stream_response.cache_creation_input_tokens;
-    ctx.total_tokens.cache_read_input_tokens += stream_response.cache_read_input_tokens;
-
     // Step 8: Parse response into ProgressFile
-    let updated_progress = match ProgressFile::parse(&response_text) {
+    let updated_progress = match ProgressFile::parse_response(&response_text, format) {
         Ok(p) => p,
         Err(e) => {
             // Log attempt on parse failure
+            let rollback = rollback_iteration(ctx, snapshot.as_ref());
             ctx.progress.add_attempt(
                 ctx.current_iteration,
-                "Parse Claude response",
-                &format!("Error: {}", e),
+                &format!("Parse {} response", backend.kind().label()),
+                &with_rollback_note(format!("Error: {}", e), rollback),
                 Some("Check response format"),
             );
             ctx.progress
@@ -344,8 +843,27 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         }
     };
 
-    // Step 9: Write updated progress file atomically with trimmed attempts
+    // Step 9: Enforce guardrails, then verify newly checked tasks before accepting them
     let mut updated_progress = updated_progress;
+    guard_progress(ctx, &mut updated_progress, snapshot.as_ref());
+    if ctx.config.verify_gates {
+        let working_dir = working_dir.to_path_buf();
+        verify_iteration(ctx, &mut updated_progress, &working_dir, snapshot.as_ref()).await;
+    }
+    post_iteration_hook(ctx, &mut updated_progress, &target, snapshot.as_ref()).await;
+
+    // Step 10: Write updated progress file atomically with trimmed attempts,
+    // keeping edits made to it on disk (e.g. by `rslph task`) while the agent ran
+    if let Ok(current) = ProgressFile::load(&ctx.progress_path) {
+        if current != loaded {
+            ctx.log(&format!(
+                "[BUILD] {} changed while iteration {} ran; applying the iteration's changes on top of it",
+                ctx.progress_path.display(),
+                ctx.current_iteration
+            ));
+            updated_progress = updated_progress.rebase(&loaded, &current);
+        }
+    }
     updated_progress.trim_attempts(ctx.config.recent_threads as usize);
     updated_progress.write(&ctx.progress_path)?;
 
@@ -354,18 +872,40 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         ctx.progress_path.display()
     ));
 
-    // Step 10: Calculate tasks completed this iteration
+    // Step 11: Calculate tasks completed this iteration
     let tasks_after = updated_progress.completed_tasks();
     let tasks_completed = tasks_after.saturating_sub(tasks_before) as u32;
 
-    // Step 11: VCS auto-commit if tasks were completed
-    if tasks_completed > 0 {
-        if let Some(ref vcs) = ctx.vcs {
+    // Step 12: VCS auto-commit if tasks were completed (unless pre_commit vetoes it)
+    if tasks_completed > 0 && ctx.vcs.is_some() {
+        let mut payload = HookPayload::new(ctx, Hook::PreCommit);
+        payload.task = target;
+        payload.completed = newly_completed(&ctx.progress, &updated_progress)
+            .into_iter()
+            .map(|(_, description)| description)
+            .collect();
+        payload.tasks_completed = tasks_after;
+        if let Err(failure) = run_hook(ctx, Hook::PreCommit, &payload).await {
+            ctx.log("[VCS] Commit vetoed by pre_commit hook");
+            updated_progress.add_attempt(
+                ctx.current_iteration,
+                "Commit iteration changes",
+                &format!("Vetoed by pre_commit hook: {}", failure.summary),
+                Some("Changes were left uncommitted; resolve the pre_commit hook failure"),
+            );
+            updated_progress.trim_attempts(ctx.config.recent_threads as usize);
+            updated_progress.write(&ctx.progress_path)?;
+        } else if let Some(ref vcs) = ctx.vcs {
             let commit_msg =
                 format_iteration_commit(&ctx.project_name, ctx.current_iteration, tasks_completed);
             match vcs.commit_all(&commit_msg) {
                 Ok(Some(hash)) => {
                     ctx.log(&format!("[VCS] Committed: {} ({})", hash, vcs.vcs_type()));
+                    ctx.emit(BuildEvent::Commit {
+                        iteration: ctx.current_iteration,
+                        hash,
+                        vcs: vcs.vcs_type().to_string(),
+                    });
                 }
                 Ok(None) => {
                     ctx.log("[VCS] No file changes to commit");
@@ -392,6 +932,10 @@ pub async fn run_single_iteration(ctx: &mut BuildContext) -> Result<IterationRes
         return Ok(IterationResult::Done(DoneReason::AllTasksComplete));
     }
 
+    if ctx.progress.is_finished_with_blocked() {
+        return Ok(IterationResult::Done(DoneReason::CompleteWithBlocked));
+    }
+
     Ok(IterationResult::Continue { tasks_completed })
 }
 
@@ -415,17 +959,21 @@ mod tests {
                     Task {
                         description: "Task 1".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             testing_strategy: "Unit tests".to_string(),
             completed_this_iteration: vec![],
             recent_attempts: vec![],
             iteration_log: vec![],
+            ..Default::default()
         }
     }
 
@@ -475,7 +1023,9 @@ mod tests {
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: true,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -0,0 +1,433 @@
+//! Crash-safe build journal.
+//!
+//! Persists the parts of `BuildContext` that are not recorded in the progress
+//! file (iteration counter, timeout retries, token usage, per-task attempts
+//! and stall tracking) to
+//! `.rslph/runs/<run-id>/state.json` at every `BuildState` transition, so an
+//! interrupted build can be resumed with `rslph build --resume`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::RslphError;
+use crate::progress::write_atomically;
+
+use super::escalation::TaskAttempts;
+use super::stall::StallTracker;
+use super::state::{BuildState, DoneReason};
+use super::tokens::{IterationTokens, TokenUsage};
+
+/// Directory (relative to the progress file) holding per-run state.
+pub const RUNS_DIR: &str = ".rslph/runs";
+
+/// Journal file name inside a run directory.
+const STATE_FILE: &str = "state.json";
+
+/// An iteration that was started but has not reached `IterationComplete`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InFlightIteration {
+    /// Iteration number that was running.
+    pub iteration: u32,
+    /// Completed task count in the progress file when the iteration started.
+    pub completed_before: usize,
+    /// When the iteration started (RFC 3339).
+    pub started_at: String,
+}
+
+/// Persisted build state for a single run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildJournal {
+    /// Unique run identifier (also the run directory name).
+    pub run_id: String,
+    /// Progress file this run operates on.
+    pub progress_path: PathBuf,
+    /// When the run was created (RFC 3339).
+    pub started_at: String,
+    /// When the journal was last written (RFC 3339).
+    pub updated_at: String,
+    /// Last recorded state machine state.
+    pub state: BuildState,
+    /// Current iteration number (1-indexed, 0 before the first iteration).
+    pub current_iteration: u32,
+    /// Number of timeout retries for the current iteration.
+    pub timeout_retry_count: u32,
+    /// Iteration that was running when the journal was last written, if any.
+    pub in_flight: Option<InFlightIteration>,
+    /// Per-iteration token usage history.
+    pub iteration_tokens: Vec<IterationTokens>,
+    /// Cumulative token usage across all iterations.
+    pub total_tokens: TokenUsage,
+    /// Iterations spent on each task, for model escalation.
+    #[serde(default)]
+    pub task_attempts: TaskAttempts,
+    /// Iterations without progress, for stall detection.
+    #[serde(default)]
+    pub stall: StallTracker,
+    /// Run directory (not serialized, derived from where the journal lives).
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl BuildJournal {
+    /// Get the runs directory for a progress file.
+    pub fn runs_dir(progress_path: &Path) -> PathBuf {
+        progress_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .join(RUNS_DIR)
+    }
+
+    /// Create a journal for a new run and write its initial state.
+    pub fn create(progress_path: &Path) -> Result<Self, RslphError> {
+        let (run_id, dir) = create_run_dir(progress_path)?;
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let journal = Self {
+            run_id,
+            progress_path: progress_path.to_path_buf(),
+            started_at: now.clone(),
+            updated_at: now,
+            state: BuildState::Starting,
+            current_iteration: 0,
+            timeout_retry_count: 0,
+            in_flight: None,
+            iteration_tokens: Vec::new(),
+            total_tokens: TokenUsage::default(),
+            task_attempts: TaskAttempts::default(),
+            stall: StallTracker::default(),
+            dir,
+        };
+        journal.save()?;
+
+        Ok(journal)
+    }
+
+    /// Load a journal from a run directory.
+    pub fn load(dir: &Path) -> Result<Self, RslphError> {
+        let content = std::fs::read_to_string(dir.join(STATE_FILE))?;
+        let mut journal: Self = serde_json::from_str(&content).map_err(|e| {
+            RslphError::Journal(format!("{}: {}", dir.join(STATE_FILE).display(), e))
+        })?;
+        journal.dir = dir.to_path_buf();
+        Ok(journal)
+    }
+
+    /// Find the most recent run for a progress file.
+    ///
+    /// Runs whose journal cannot be read are skipped.
+    pub fn latest(progress_path: &Path) -> Result<Option<Self>, RslphError> {
+        let runs_dir = Self::runs_dir(progress_path);
+        if !runs_dir.is_dir() {
+            return Ok(None);
+        }
+
+        let mut run_dirs: Vec<PathBuf> = std::fs::read_dir(&runs_dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.join(STATE_FILE).is_file())
+            .collect();
+        run_dirs.sort();
+
+        let target = normalize(progress_path);
+        Ok(run_dirs
+            .iter()
+            .rev()
+            .filter_map(|dir| Self::load(dir).ok())
+            .find(|j| normalize(&j.progress_path) == target))
+    }
+
+    /// Run directory for this journal.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Write the journal atomically (crash-safe).
+    pub fn save(&self) -> Result<(), RslphError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| RslphError::Journal(e.to_string()))?;
+        write_atomically(&self.dir.join(STATE_FILE), &json)
+    }
+
+    /// Record a state transition.
+    ///
+    /// Entering `Running` marks the iteration as in flight (a timeout retry of
+    /// the same iteration keeps the original marker). Reaching
+    /// `IterationComplete`, or finishing the build normally, clears it. A
+    /// cancelled or failed iteration keeps the marker so resume can detect it.
+    pub fn record_state(&mut self, state: &BuildState, completed_tasks: usize) {
+        match state {
+            BuildState::Running { iteration }
+                if self.in_flight.as_ref().map(|f| f.iteration) != Some(*iteration) =>
+            {
+                self.in_flight = Some(InFlightIteration {
+                    iteration: *iteration,
+                    completed_before: completed_tasks,
+                    started_at: chrono::Utc::now().to_rfc3339(),
+                });
+            }
+            BuildState::IterationComplete { .. } => {
+                self.in_flight = None;
+            }
+            BuildState::Done { reason } if *reason != DoneReason::UserCancelled => {
+                self.in_flight = None;
+            }
+            _ => {}
+        }
+
+        self.state = state.clone();
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+    }
+
+    /// Decide where a resumed build should pick up.
+    ///
+    /// An interrupted iteration whose task changes already reached the progress
+    /// file is treated as complete; otherwise it is run again. A run that
+    /// stopped between iterations continues with the next iteration number.
+    pub fn resume_state(&self, completed_now: usize) -> BuildState {
+        if let Some(ref in_flight) = self.in_flight {
+            return if completed_now > in_flight.completed_before {
+                BuildState::IterationComplete {
+                    iteration: in_flight.iteration,
+                    tasks_completed: (completed_now - in_flight.completed_before) as u32,
+                }
+            } else {
+                BuildState::Running {
+                    iteration: in_flight.iteration,
+                }
+            };
+        }
+
+        match self.state {
+            BuildState::IterationComplete { .. } => self.state.clone(),
+            _ if self.current_iteration == 0 => BuildState::Starting,
+            _ => BuildState::Running {
+                iteration: self.current_iteration + 1,
+            },
+        }
+    }
+}
+
+/// Create a new, empty run directory for a progress file.
+///
+/// Returns the run ID and the directory path.
+pub fn create_run_dir(progress_path: &Path) -> Result<(String, PathBuf), RslphError> {
+    let runs_dir = BuildJournal::runs_dir(progress_path);
+    std::fs::create_dir_all(&runs_dir)?;
+    ignore_runs_dir(&runs_dir)?;
+
+    // Timestamp IDs sort chronologically; suffix on collision
+    let base_id = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut run_id = base_id.clone();
+    let mut suffix = 2;
+    while runs_dir.join(&run_id).exists() {
+        run_id = format!("{}-{}", base_id, suffix);
+        suffix += 1;
+    }
+
+    let dir = runs_dir.join(&run_id);
+    std::fs::create_dir_all(&dir)?;
+    Ok((run_id, dir))
+}
+
+/// Keep run state out of auto-commits (`.rslph/runs/.gitignore` ignoring everything).
+fn ignore_runs_dir(runs_dir: &Path) -> Result<(), RslphError> {
+    let gitignore = runs_dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(gitignore, "*\n")?;
+    }
+    Ok(())
+}
+
+/// Canonicalize a path for comparison, falling back to the path itself.
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn progress_in(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("progress.md");
+        std::fs::write(&path, "# Progress: Test\n").expect("write progress");
+        path
+    }
+
+    #[test]
+    fn test_create_writes_state_file() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress_path = progress_in(&dir);
+
+        let journal = BuildJournal::create(&progress_path).expect("create journal");
+
+        assert!(journal.dir().join(STATE_FILE).is_file());
+        assert!(journal.dir().starts_with(dir.path().join(RUNS_DIR)));
+        assert_eq!(journal.state, BuildState::Starting);
+        assert!(dir.path().join(RUNS_DIR).join(".gitignore").is_file());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress_path = progress_in(&dir);
+
+        let mut journal = BuildJournal::create(&progress_path).expect("create journal");
+        journal.current_iteration = 3;
+        journal.timeout_retry_count = 1;
+        journal.total_tokens.input_tokens = 1234;
+        journal.iteration_tokens.push(IterationTokens {
+            iteration: 1,
+            input_tokens: 1234,
+            ..Default::default()
+        });
+        journal.task_attempts.start(Some("Hard task"));
+        journal.stall.idle_iterations = 2;
+        journal.stall.escalated = true;
+        journal.record_state(&BuildState::Running { iteration: 3 }, 2);
+        journal.save().expect("save");
+
+        let loaded = BuildJournal::load(journal.dir()).expect("load");
+        assert_eq!(loaded.run_id, journal.run_id);
+        assert_eq!(loaded.current_iteration, 3);
+        assert_eq!(loaded.timeout_retry_count, 1);
+        assert_eq!(loaded.total_tokens.input_tokens, 1234);
+        assert_eq!(loaded.iteration_tokens.len(), 1);
+        assert_eq!(loaded.state, BuildState::Running { iteration: 3 });
+        assert_eq!(loaded.in_flight.map(|f| f.completed_before), Some(2));
+        assert_eq!(loaded.task_attempts, journal.task_attempts);
+        assert_eq!(loaded.stall, journal.stall);
+    }
+
+    #[test]
+    fn test_latest_picks_most_recent_run_for_progress_file() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress_path = progress_in(&dir);
+
+        let first = BuildJournal::create(&progress_path).expect("create first");
+        let second = BuildJournal::create(&progress_path).expect("create second");
+        assert_ne!(first.run_id, second.run_id);
+
+        let latest = BuildJournal::latest(&progress_path)
+            .expect("latest")
+            .expect("should find a run");
+        assert_eq!(latest.run_id, second.run_id);
+
+        // A different progress file in the same directory has no runs
+        let other = dir.path().join("other.md");
+        assert!(BuildJournal::latest(&other).expect("latest").is_none());
+    }
+
+    #[test]
+    fn test_record_state_tracks_in_flight_iteration() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut journal = BuildJournal::create(&progress_in(&dir)).expect("create");
+
+        journal.record_state(&BuildState::Running { iteration: 1 }, 0);
+        let started = journal.in_flight.clone().expect("in flight");
+        assert_eq!(started.iteration, 1);
+
+        // Timeout retry of the same iteration keeps the original marker
+        journal.record_state(&BuildState::Running { iteration: 1 }, 5);
+        assert_eq!(journal.in_flight, Some(started));
+
+        journal.record_state(
+            &BuildState::IterationComplete {
+                iteration: 1,
+                tasks_completed: 1,
+            },
+            1,
+        );
+        assert!(journal.in_flight.is_none());
+
+        // Cancelling mid-iteration keeps the marker; finishing normally clears it
+        journal.record_state(&BuildState::Running { iteration: 2 }, 1);
+        journal.record_state(
+            &BuildState::Done {
+                reason: DoneReason::UserCancelled,
+            },
+            1,
+        );
+        assert!(journal.in_flight.is_some());
+        journal.record_state(
+            &BuildState::Done {
+                reason: DoneReason::AllTasksComplete,
+            },
+            2,
+        );
+        assert!(journal.in_flight.is_none());
+    }
+
+    #[test]
+    fn test_resume_state_reruns_interrupted_iteration() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut journal = BuildJournal::create(&progress_in(&dir)).expect("create");
+        journal.current_iteration = 2;
+        journal.record_state(&BuildState::Running { iteration: 2 }, 1);
+        journal.record_state(
+            &BuildState::Done {
+                reason: DoneReason::UserCancelled,
+            },
+            1,
+        );
+
+        assert_eq!(
+            journal.resume_state(1),
+            BuildState::Running { iteration: 2 }
+        );
+    }
+
+    #[test]
+    fn test_resume_state_accepts_interrupted_iteration_with_progress() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut journal = BuildJournal::create(&progress_in(&dir)).expect("create");
+        journal.current_iteration = 2;
+        journal.record_state(&BuildState::Running { iteration: 2 }, 1);
+
+        assert_eq!(
+            journal.resume_state(3),
+            BuildState::IterationComplete {
+                iteration: 2,
+                tasks_completed: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_resume_state_between_iterations() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut journal = BuildJournal::create(&progress_in(&dir)).expect("create");
+        assert_eq!(journal.resume_state(0), BuildState::Starting);
+
+        journal.current_iteration = 4;
+        journal.record_state(&BuildState::Running { iteration: 4 }, 0);
+        journal.record_state(
+            &BuildState::IterationComplete {
+                iteration: 4,
+                tasks_completed: 1,
+            },
+            1,
+        );
+        // Iteration complete but not yet logged: re-enter that state
+        assert_eq!(
+            journal.resume_state(1),
+            BuildState::IterationComplete {
+                iteration: 4,
+                tasks_completed: 1
+            }
+        );
+
+        journal.record_state(
+            &BuildState::Done {
+                reason: DoneReason::MaxIterationsReached,
+            },
+            1,
+        );
+        assert_eq!(
+            journal.resume_state(1),
+            BuildState::Running { iteration: 5 }
+        );
+    }
+}
@@ -0,0 +1,1269 @@
+//! Progress file linter (`rslph lint`).
+//!
+//! `ProgressFile::parse` is lenient: content it can't place is kept as prose
+//! or notes (often in a different place), some is misread or dropped, and a
+//! file it can't read at all fails with one message.
+//! The linter walks the same markdown events with their source offsets and
+//! reports each problem at its line and column, with a mechanical fix where
+//! there is one (`--fix`).
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use strum_macros::Display;
+
+use crate::error::RslphError;
+use crate::progress::{
+    heading_source, is_block_start, is_inline, line_start, source_lines, strip_bullet,
+    trailing_group, write_atomically, ProgressFile, ProgressFormat, Task, KNOWN_SECTIONS,
+    NOTE_SECTIONS,
+};
+
+/// Heading added above tasks that sit outside any phase.
+const UNGROUPED_PHASE: &str = "Ungrouped tasks";
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    /// Content is lost or misread when the file is parsed
+    Error,
+    /// Likely a mistake, but the file reads as written
+    Warning,
+}
+
+/// A text edit that repairs a diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    /// Byte range to replace; empty for an insertion
+    pub range: Range<usize>,
+    pub replacement: String,
+    pub description: String,
+}
+
+/// A problem found in a progress file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Short kebab-case name of the check
+    pub code: &'static str,
+    pub message: String,
+    /// Byte range of the offending source
+    pub range: Range<usize>,
+    pub fix: Option<Fix>,
+}
+
+impl Diagnostic {
+    fn error(code: &'static str, range: Range<usize>, message: String) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message,
+            range,
+            fix: None,
+        }
+    }
+
+    fn warning(code: &'static str, range: Range<usize>, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(code, range, message)
+        }
+    }
+
+    fn with_fix(mut self, range: Range<usize>, replacement: &str, description: &str) -> Self {
+        self.fix = Some(Fix {
+            range,
+            replacement: replacement.to_string(),
+            description: description.to_string(),
+        });
+        self
+    }
+
+    /// Compiler-style report: the message, `path:line:column`, and the first
+    /// source line of the span underlined.
+    pub fn render(&self, path: &str, content: &str) -> String {
+        let (line, column) = line_col(content, self.range.start);
+        let start = line_start(content, self.range.start);
+        let source = content[start..].lines().next().unwrap_or("");
+        let underline_start = content[start..self.range.start].chars().count();
+        let underline_len = content[self.range.start..self.range.end.max(self.range.start)]
+            .lines()
+            .next()
+            .map_or(0, |l| l.chars().count())
+            .max(1);
+        let gutter = " ".repeat(line.to_string().len());
+
+        let mut out = format!(
+            "{}[{}]: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+            self.severity,
+            self.code,
+            self.message,
+            gutter,
+            path,
+            line,
+            column,
+            gutter,
+            line,
+            source,
+            gutter,
+            " ".repeat(underline_start),
+            "^".repeat(underline_len)
+        );
+        if let Some(fix) = &self.fix {
+            out.push_str(&format!("{} = fix: {}\n", gutter, fix.description));
+        }
+        out
+    }
+}
+
+/// One-based line and column (in characters) of byte `offset`.
+pub fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start(content, offset)..].chars().count() + 1;
+    (line, column)
+}
+
+/// Check a markdown progress file, returning diagnostics in source order.
+pub fn lint(content: &str) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(content);
+    linter.run();
+    linter.finish()
+}
+
+/// Apply the fixes of `diagnostics` to `content`, skipping any that overlap
+/// an earlier one. Returns the new content and the number of fixes applied.
+pub fn apply_fixes(content: &str, diagnostics: &[Diagnostic]) -> (String, usize) {
+    let mut fixes: Vec<&Fix> = diagnostics.iter().filter_map(|d| d.fix.as_ref()).collect();
+    fixes.sort_by_key(|f| (f.range.start, f.range.end));
+
+    let mut out = String::with_capacity(content.len());
+    let mut pos = 0;
+    let mut applied = 0;
+    for fix in fixes {
+        if fix.range.start < pos {
+            continue;
+        }
+        out.push_str(&content[pos..fix.range.start]);
+        out.push_str(&fix.replacement);
+        pos = fix.range.end;
+        applied += 1;
+    }
+    out.push_str(&content[pos..]);
+    (out, applied)
+}
+
+/// Lint the progress file at `path` and print the diagnostics, after
+/// applying fixes when `fix` is set. Returns whether the file is free of
+/// errors.
+pub fn run_lint_command(path: &Path, fix: bool) -> Result<bool, RslphError> {
+    let name = path.display().to_string();
+    let format = ProgressFormat::from_path(path);
+    if format.is_structured() {
+        // serde reports its own line and column
+        if fix {
+            return Err(RslphError::ProgressParse(format!(
+                "--fix only applies to markdown progress files, not {}",
+                format
+            )));
+        }
+        return match ProgressFile::load(path) {
+            Ok(_) => {
+                println!("{}: no problems found", name);
+                Ok(true)
+            }
+            Err(e) => {
+                println!("error: {}\n  --> {}", e, name);
+                Ok(false)
+            }
+        };
+    }
+
+    let mut content = std::fs::read_to_string(path)?;
+    let mut diagnostics = lint(&content);
+    if fix {
+        let (fixed, applied) = apply_fixes(&content, &diagnostics);
+        if applied > 0 {
+            write_atomically(path, &fixed)?;
+            println!("Fixed {} problem(s) in {}\n", applied, name);
+            content = fixed;
+            diagnostics = lint(&content);
+        }
+    }
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic.render(&name, &content));
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    let fixable = diagnostics.iter().filter(|d| d.fix.is_some()).count();
+    if diagnostics.is_empty() {
+        println!("{}: no problems found", name);
+    } else {
+        print!("{}: {} error(s), {} warning(s)", name, errors, warnings);
+        if fixable > 0 {
+            print!("; {} can be fixed with --fix", fixable);
+        }
+        println!();
+    }
+    Ok(errors == 0)
+}
+
+/// Number of cells written in a table row, split on unescaped `|`.
+fn source_cells(row: &str) -> usize {
+    let row = row.trim();
+    let row = row.strip_prefix('|').unwrap_or(row);
+    let mut pipes = 0;
+    let mut escaped = false;
+    let mut closed = false;
+    for c in row.chars() {
+        closed = c == '|' && !escaped;
+        if closed {
+            pipes += 1;
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    // A trailing `|` closes the last cell rather than starting one
+    if closed {
+        pipes
+    } else {
+        pipes + 1
+    }
+}
+
+/// A task line seen in the Tasks section.
+struct TaskSite {
+    task: Task,
+    range: Range<usize>,
+}
+
+/// The `### Iteration N` block being read in Recent Attempts.
+struct AttemptSite {
+    iteration: u32,
+    heading: Range<usize>,
+    has_tried: bool,
+    has_result: bool,
+}
+
+/// Walk state; mirrors the bookkeeping of `ProgressFile::parse`.
+struct Linter<'a> {
+    content: &'a str,
+    diagnostics: Vec<Diagnostic>,
+    title_end: Option<usize>,
+    h2: String,
+    h3: String,
+    heading_level: Option<HeadingLevel>,
+    heading_text_range: Option<Range<usize>>,
+    heading_range: Range<usize>,
+    /// Known sections seen so far, for duplicates and missing ones
+    sections: HashMap<&'static str, usize>,
+    /// End of the `## Status` heading while its body is being read
+    status_body_start: Option<usize>,
+    /// Current phase heading, and whether anything follows it yet
+    phase: Option<(Range<usize>, bool)>,
+    phase_names: HashMap<String, usize>,
+    /// A fix adding a phase heading was already offered for this Tasks section
+    ungrouped_fix_offered: bool,
+    tasks: Vec<TaskSite>,
+    attempt: Option<AttemptSite>,
+    /// The current Iteration Log table has the five columns rows need
+    log_table_ok: bool,
+    item_depth: usize,
+    item_range: Range<usize>,
+    /// Source span of the item's own text, after its marker
+    item_text: Range<usize>,
+    /// The item's own text has ended (a nested block started)
+    item_text_done: bool,
+    item_checkbox: bool,
+    row: Vec<(String, Range<usize>)>,
+    cell_text: String,
+    cell_range: Range<usize>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            diagnostics: Vec::new(),
+            title_end: None,
+            h2: String::new(),
+            h3: String::new(),
+            heading_level: None,
+            heading_text_range: None,
+            heading_range: 0..0,
+            sections: HashMap::new(),
+            status_body_start: None,
+            phase: None,
+            phase_names: HashMap::new(),
+            ungrouped_fix_offered: false,
+            tasks: Vec::new(),
+            attempt: None,
+            log_table_ok: false,
+            item_depth: 0,
+            item_range: 0..0,
+            item_text: 0..0,
+            item_text_done: false,
+            item_checkbox: false,
+            row: Vec::new(),
+            cell_text: String::new(),
+            cell_range: 0..0,
+        }
+    }
+
+    fn run(&mut self) {
+        let mut options = Options::empty();
+        options.insert(Options::ENABLE_TASKLISTS);
+        options.insert(Options::ENABLE_TABLES);
+
+        for (event, range) in Parser::new_ext(self.content, options).into_offset_iter() {
+            if let Event::Start(Tag::Item) = event {
+                self.item_depth += 1;
+            }
+            if self.heading_level.is_none() && !matches!(event, Event::Start(Tag::Heading { .. })) {
+                if let Some((_, has_content)) = &mut self.phase {
+                    *has_content = true;
+                }
+            }
+            if self.item_depth == 0 && is_block_start(&event) {
+                self.top_level_block(&event, range.clone());
+            }
+            if self.item_depth == 1 && !self.item_text.is_empty() && is_block_start(&event) {
+                self.item_text_done = true;
+            }
+            if self.heading_level.is_none()
+                && self.item_depth == 1
+                && !self.item_text_done
+                && is_inline(&event)
+            {
+                self.item_text.end = self.item_text.end.max(range.end);
+            }
+
+            match event {
+                Event::Start(Tag::Heading { level, .. }) => {
+                    if matches!(level, HeadingLevel::H1 | HeadingLevel::H2 | HeadingLevel::H3) {
+                        self.close_phase();
+                        self.close_attempt();
+                    } else if self.item_depth == 0 {
+                        self.sub_heading(range.clone());
+                    }
+                    if matches!(level, HeadingLevel::H1 | HeadingLevel::H2) {
+                        self.close_section(range.start);
+                    }
+                    self.heading_level = Some(level);
+                    self.heading_text_range = None;
+                    self.heading_range = range;
+                }
+                Event::End(TagEnd::Heading(_)) => self.heading(),
+                Event::Text(text) | Event::Code(text) => {
+                    if self.heading_level.is_some() {
+                        let start = self
+                            .heading_text_range
+                            .as_ref()
+                            .map_or(range.start, |r| r.start);
+                        self.heading_text_range = Some(start..range.end);
+                    } else if !self.cell_range.is_empty() {
+                        self.cell_text.push_str(&text);
+                    }
+                }
+                Event::TaskListMarker(_) if self.item_depth == 1 => {
+                    self.item_checkbox = true;
+                    self.item_text = range.end..range.end;
+                }
+                Event::Start(Tag::Item) if self.item_depth == 1 => {
+                    let start = range.end - strip_bullet(&self.content[range.clone()]).len();
+                    self.item_range = range;
+                    self.item_text = start..start;
+                    self.item_text_done = false;
+                    self.item_checkbox = false;
+                }
+                Event::End(TagEnd::Item) => {
+                    self.item_depth -= 1;
+                    if self.item_depth == 0 {
+                        self.item();
+                    }
+                }
+                Event::Start(Tag::TableCell) => {
+                    self.cell_text.clear();
+                    self.cell_range = range;
+                }
+                Event::End(TagEnd::TableCell) => {
+                    let cell = std::mem::take(&mut self.cell_text);
+                    self.row
+                        .push((cell.trim().to_string(), self.cell_range.clone()));
+                    self.cell_range = 0..0;
+                }
+                Event::End(TagEnd::TableHead) => self.table_head(range),
+                Event::End(TagEnd::TableRow) => self.table_row(range),
+                _ => {}
+            }
+        }
+        self.close_phase();
+        self.close_attempt();
+        self.close_section(self.content.len());
+    }
+
+    fn finish(mut self) -> Vec<Diagnostic> {
+        self.check_document();
+        self.check_dependencies();
+        if !self
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Error)
+        {
+            if let Err(e) = ProgressFile::parse(self.content) {
+                // Whatever the checks above don't cover, e.g. dependency cycles
+                let range = self.cycle_site(&e.to_string()).unwrap_or(0..0);
+                self.diagnostics
+                    .push(Diagnostic::error("parse", range, e.to_string()));
+            }
+        }
+        self.diagnostics.sort_by_key(|d| d.range.start);
+        self.diagnostics
+    }
+
+    fn heading(&mut self) {
+        let title = heading_source(&self.content[self.heading_range.clone()]).to_string();
+        let range = self.heading_range.clone();
+        match self.heading_level.take() {
+            Some(HeadingLevel::H1) => {
+                self.title_end = Some(range.end);
+                self.h2.clear();
+                self.h3.clear();
+            }
+            Some(HeadingLevel::H2) => {
+                self.h3.clear();
+                self.ungrouped_fix_offered = false;
+                self.log_table_ok = false;
+                self.h2 = match KNOWN_SECTIONS.iter().copied().find(|k| *k == title) {
+                    Some(known) => {
+                        self.section_seen(known, range);
+                        known.to_string()
+                    }
+                    None => match KNOWN_SECTIONS
+                        .iter()
+                        .copied()
+                        .find(|k| title.trim_end_matches(':').trim().eq_ignore_ascii_case(k))
+                    {
+                        Some(known) => {
+                            let mut diagnostic = Diagnostic::error(
+                                "section-name",
+                                range.clone(),
+                                format!(
+                                    "section \"{}\" isn't recognized and is kept as free text; did you mean \"{}\"?",
+                                    title, known
+                                ),
+                            );
+                            if let Some(text) = self.heading_text_range.clone() {
+                                diagnostic = diagnostic.with_fix(
+                                    text,
+                                    known,
+                                    &format!("rename the section to \"{}\"", known),
+                                );
+                            }
+                            self.diagnostics.push(diagnostic);
+                            self.section_seen(known, range);
+                            known.to_string()
+                        }
+                        None => title,
+                    },
+                };
+                if self.h2 == "Status" {
+                    self.status_body_start = Some(self.heading_range.end);
+                }
+            }
+            Some(HeadingLevel::H3) => {
+                self.h3 = title.clone();
+                match self.h2.as_str() {
+                    "Tasks" => self.phase_heading(title, range),
+                    "Recent Attempts" => self.attempt_heading(&title, range),
+                    "Completed This Iteration" | "Iteration Log" => self.note(range),
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn section_seen(&mut self, known: &'static str, range: Range<usize>) {
+        let count = self.sections.entry(known).or_default();
+        *count += 1;
+        if *count > 1 && known != "Tasks" {
+            self.diagnostics.push(Diagnostic::warning(
+                "duplicate-section",
+                range,
+                format!(
+                    "second \"{}\" section; when the file is rewritten only one is kept",
+                    known
+                ),
+            ));
+        }
+    }
+
+    fn phase_heading(&mut self, name: String, range: Range<usize>) {
+        if let Some(first) = self.phase_names.get(&name) {
+            let (line, _) = line_col(self.content, *first);
+            self.diagnostics.push(Diagnostic::warning(
+                "duplicate-phase",
+                range.clone(),
+                format!(
+                    "phase \"{}\" is already defined on line {}; tasks are found by phase name, so rename or merge them",
+                    name, line
+                ),
+            ));
+        } else {
+            self.phase_names.insert(name, range.start);
+        }
+        self.phase = Some((range, false));
+    }
+
+    fn attempt_heading(&mut self, title: &str, range: Range<usize>) {
+        if let Some(iteration) = title
+            .strip_prefix("Iteration ")
+            .and_then(|n| n.trim().parse().ok())
+        {
+            self.attempt = Some(AttemptSite {
+                iteration,
+                heading: range,
+                has_tried: false,
+                has_result: false,
+            });
+            return;
+        }
+
+        let number = title
+            .get(.."iteration".len())
+            .filter(|word| word.eq_ignore_ascii_case("iteration"))
+            .and_then(|_| title["iteration".len()..].trim().parse::<u32>().ok());
+        let mut diagnostic = Diagnostic::error(
+            "attempt-heading",
+            range.clone(),
+            format!(
+                "attempt heading \"{}\" should read \"Iteration N\"; the entries under it aren't read as an attempt and are kept as notes",
+                title
+            ),
+        );
+        if let (Some(number), Some(text)) = (number, self.heading_text_range.clone()) {
+            let fixed = format!("Iteration {}", number);
+            diagnostic = diagnostic.with_fix(
+                text,
+                &fixed,
+                &format!("rename the heading to \"{}\"", fixed),
+            );
+            self.attempt = Some(AttemptSite {
+                iteration: number,
+                heading: range,
+                has_tried: false,
+                has_result: false,
+            });
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// A paragraph, list, table or other block outside any list item.
+    fn top_level_block(&mut self, event: &Event, range: Range<usize>) {
+        if matches!(event, Event::Start(Tag::List(_))) {
+            return;
+        }
+        let text = self.content[range.clone()].trim_end();
+        if self.h2 == "Tasks" && self.h3.is_empty() {
+            self.outside_phase(range);
+        } else if self.h2 == "Iteration Log" && matches!(event, Event::Start(Tag::Table(_))) {
+            // Checked by `table_head`
+        } else if self.h2 == "Iteration Log"
+            && matches!(event, Event::Start(Tag::Paragraph))
+            && text.starts_with('|')
+        {
+            self.diagnostics.push(Diagnostic::error(
+                "log-table",
+                range,
+                "Iteration Log rows aren't a table (is the \"|---|\" line under the header missing?); they are kept as notes, not read as log entries"
+                    .to_string(),
+            ));
+        } else if NOTE_SECTIONS.contains(&self.h2.as_str()) {
+            self.note(range);
+        }
+    }
+
+    /// A `####` or deeper heading outside any list item.
+    fn sub_heading(&mut self, range: Range<usize>) {
+        if let Some((_, has_content)) = &mut self.phase {
+            *has_content = true;
+        }
+        if self.h2 != "Tasks" {
+            if NOTE_SECTIONS.contains(&self.h2.as_str()) {
+                self.note(range);
+            }
+            return;
+        }
+        if self.h3.is_empty() {
+            self.outside_phase(range);
+            return;
+        }
+        let heading = self.content[range.clone()].trim().to_string();
+        self.diagnostics.push(Diagnostic::warning(
+            "sub-heading",
+            range,
+            format!(
+                "\"{}\" is text of phase \"{}\", not a phase of its own; phase text is written above the phase's tasks, so use \"###\" for a new phase",
+                heading, self.h3
+            ),
+        ));
+    }
+
+    /// Text under `## Tasks` that isn't in any phase.
+    fn outside_phase(&mut self, range: Range<usize>) {
+        self.diagnostics.push(Diagnostic::warning(
+            "outside-phase",
+            range,
+            "text under \"## Tasks\" before the first \"###\" phase isn't part of any phase; it stays at the top of the section"
+                .to_string(),
+        ));
+    }
+
+    /// Text in a structured section that isn't one of its entries.
+    fn note(&mut self, range: Range<usize>) {
+        let entries = match self.h2.as_str() {
+            "Completed This Iteration" => "checked-off tasks",
+            "Recent Attempts" => "\"### Iteration N\" attempts",
+            _ => "the log table",
+        };
+        self.diagnostics.push(Diagnostic::warning(
+            "notes",
+            range,
+            format!(
+                "\"{}\" holds {}; this text is kept as a note below them when the file is rewritten",
+                self.h2, entries
+            ),
+        ));
+    }
+
+    /// A top-level list item has ended.
+    fn item(&mut self) {
+        let content = self.content;
+        let range = self.item_range.clone();
+        let raw = &content[range.clone()];
+        let marker_end = raw.len() - strip_bullet(raw).len();
+        let body = &raw[marker_end..];
+        let blocked = ["[-]", "[~]"]
+            .iter()
+            .any(|b| body.starts_with(b) && body[3..].starts_with(char::is_whitespace));
+        let text = source_lines(&content[self.item_text.clone()]);
+
+        match self.h2.as_str() {
+            "Tasks" if self.item_checkbox || blocked => {
+                if self.h3.is_empty() {
+                    let mut diagnostic = Diagnostic::error(
+                        "task-outside-phase",
+                        range.clone(),
+                        "task isn't under a \"###\" phase heading and is attributed to the following phase"
+                            .to_string(),
+                    );
+                    if !self.ungrouped_fix_offered {
+                        self.ungrouped_fix_offered = true;
+                        let at = line_start(content, range.start);
+                        diagnostic = diagnostic.with_fix(
+                            at..at,
+                            &format!("### {}\n\n", UNGROUPED_PHASE),
+                            &format!("add a \"### {}\" phase heading above it", UNGROUPED_PHASE),
+                        );
+                    }
+                    self.diagnostics.push(diagnostic);
+                }
+                let text = if blocked {
+                    text[3..].trim_start()
+                } else {
+                    &text
+                };
+                self.task(Task::from_markdown(text, false, blocked, None), range);
+            }
+            "Tasks" if body.starts_with("[]") => {
+                let at = range.start + marker_end;
+                self.diagnostics.push(
+                    Diagnostic::warning(
+                        "checkbox",
+                        at..at + 2,
+                        "\"[]\" isn't a checkbox, so this item is read as phase text rather than a task"
+                            .to_string(),
+                    )
+                    .with_fix(at..at + 2, "[ ]", "write the checkbox as \"[ ]\""),
+                );
+            }
+            "Tasks" if self.h3.is_empty() => self.outside_phase(range),
+            "Recent Attempts" if self.h3.is_empty() => {
+                self.diagnostics.push(Diagnostic::error(
+                    "attempt-entry",
+                    range,
+                    "attempt entry isn't under an \"### Iteration N\" heading, so it's kept as a note rather than read as an attempt"
+                        .to_string(),
+                ));
+            }
+            "Completed This Iteration" if !self.item_checkbox => self.note(range),
+            "Iteration Log" => self.note(range),
+            "Recent Attempts" => {
+                if let Some(attempt) = &mut self.attempt {
+                    let key = text.split_once(':').map(|(k, _)| k.trim());
+                    match key {
+                        Some("Tried") => attempt.has_tried = true,
+                        Some("Result") => attempt.has_result = true,
+                        Some("Next") => {}
+                        _ => {
+                            let proper = key.and_then(|k| {
+                                ["Tried", "Result", "Next"]
+                                    .into_iter()
+                                    .find(|p| p.eq_ignore_ascii_case(k))
+                            });
+                            let mut diagnostic = Diagnostic::warning(
+                                "attempt-entry",
+                                range.clone(),
+                                "attempt entries start with \"Tried:\", \"Result:\" or \"Next:\"; this one is kept as a note"
+                                    .to_string(),
+                            );
+                            if let Some(proper) = proper {
+                                if body
+                                    .get(..proper.len())
+                                    .is_some_and(|k| k.eq_ignore_ascii_case(proper))
+                                {
+                                    let at = range.start + marker_end;
+                                    diagnostic = diagnostic.with_fix(
+                                        at..at + proper.len(),
+                                        proper,
+                                        &format!("write \"{}:\"", proper),
+                                    );
+                                }
+                                match proper {
+                                    "Tried" => attempt.has_tried = true,
+                                    "Result" => attempt.has_result = true,
+                                    _ => {}
+                                }
+                            }
+                            self.diagnostics.push(diagnostic);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn task(&mut self, task: Task, range: Range<usize>) {
+        if let Some(open) = trailing_group(&task.description) {
+            // Parsing strips a group only when every entry is an annotation
+            let group = &task.description[open + 1..task.description.len() - 1];
+            let (known, unknown): (Vec<&str>, Vec<&str>) = group
+                .split(';')
+                .map(str::trim)
+                .partition(|entry| Task::default().annotate(entry));
+            if !known.is_empty() {
+                self.diagnostics.push(Diagnostic::warning(
+                    "annotation",
+                    range.clone(),
+                    format!(
+                        "unrecognized annotation \"{}\", so the whole \"({})\" group is read as part of the description",
+                        unknown.join("\", \""),
+                        group
+                    ),
+                ));
+            }
+        }
+        self.tasks.push(TaskSite { task, range });
+    }
+
+    fn table_head(&mut self, range: Range<usize>) {
+        let columns = self.row.len();
+        self.row.clear();
+        if self.h2 != "Iteration Log" {
+            return;
+        }
+        self.log_table_ok = columns >= 5;
+        if !self.log_table_ok {
+            self.diagnostics.push(Diagnostic::error(
+                "log-table",
+                range,
+                format!(
+                    "Iteration Log table has {} column(s); rows need 5 (Iteration | Started | Duration | Tasks Completed | Notes), so the table is kept as notes",
+                    columns
+                ),
+            ));
+        }
+    }
+
+    fn table_row(&mut self, range: Range<usize>) {
+        let row = std::mem::take(&mut self.row);
+        if self.h2 != "Iteration Log" || !self.log_table_ok {
+            return;
+        }
+        // Short rows are padded with empty cells, so count them in the source
+        let cells = source_cells(&self.content[range.clone()]).min(row.len());
+        if cells < 5 {
+            self.diagnostics.push(Diagnostic::warning(
+                "log-row",
+                range.clone(),
+                format!(
+                    "row has {} cell(s) instead of 5; the missing ones are read as empty",
+                    cells
+                ),
+            ));
+        }
+        let Some((iteration, cell)) = row.first().filter(|_| cells > 0) else {
+            return;
+        };
+        if iteration.parse::<u32>().is_err() {
+            let mut diagnostic = Diagnostic::error(
+                "log-row",
+                range.clone(),
+                format!(
+                    "iteration \"{}\" isn't a number, so the row is dropped",
+                    iteration
+                ),
+            );
+            let digits = iteration.trim_start_matches('#').trim_end_matches('.');
+            let at = self.content[cell.clone()]
+                .find(iteration.as_str())
+                .map(|i| cell.start + i);
+            if let (Ok(_), Some(at)) = (digits.parse::<u32>(), at) {
+                diagnostic = diagnostic.with_fix(
+                    at..at + iteration.len(),
+                    digits,
+                    &format!("write the iteration as \"{}\"", digits),
+                );
+            }
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+        let Some((tasks, tasks_range)) = row.get(3).filter(|_| cells > 3) else {
+            return;
+        };
+        if tasks.parse::<u32>().is_err() {
+            self.diagnostics.push(Diagnostic::warning(
+                "log-row",
+                tasks_range.clone(),
+                format!(
+                    "tasks completed \"{}\" isn't a number and is read as 0",
+                    tasks
+                ),
+            ));
+        }
+    }
+
+    fn close_phase(&mut self) {
+        if let Some((range, false)) = self.phase.take() {
+            let end = self.content[range.end..]
+                .find(|c: char| !c.is_whitespace())
+                .map_or(self.content.len(), |i| range.end + i);
+            self.diagnostics.push(
+                Diagnostic::warning(
+                    "empty-phase",
+                    range.clone(),
+                    "phase has no tasks and is dropped when the file is rewritten".to_string(),
+                )
+                .with_fix(range.start..end, "", "remove the heading"),
+            );
+        }
+    }
+
+    fn close_attempt(&mut self) {
+        if let Some(attempt) = self.attempt.take() {
+            let missing: Vec<&str> = [("Tried", attempt.has_tried), ("Result", attempt.has_result)]
+                .into_iter()
+                .filter(|(_, has)| !has)
+                .map(|(key, _)| key)
+                .collect();
+            if !missing.is_empty() {
+                self.diagnostics.push(Diagnostic::warning(
+                    "attempt-entry",
+                    attempt.heading,
+                    format!(
+                        "attempt for iteration {} has no \"{}:\" entry",
+                        attempt.iteration,
+                        missing.join(":\" or \"")
+                    ),
+                ));
+            }
+        }
+    }
+
+    /// A `#` or `##` heading (or the end of the file) at `end` closes the
+    /// current section.
+    fn close_section(&mut self, end: usize) {
+        if let Some(start) = self.status_body_start.take() {
+            if self.content[start..end].trim().is_empty() {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        "missing-status",
+                        self.heading_range_of_status(start),
+                        "Status section is empty".to_string(),
+                    )
+                    .with_fix(
+                        start..start,
+                        "\nIn Progress\n",
+                        "set the status to \"In Progress\"",
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The `## Status` heading ending at `end`.
+    fn heading_range_of_status(&self, end: usize) -> Range<usize> {
+        line_start(self.content, end.saturating_sub(1))..end
+    }
+
+    fn check_document(&mut self) {
+        if self.title_end.is_none() {
+            self.diagnostics.push(Diagnostic::warning(
+                "missing-title",
+                0..0,
+                "no \"# Progress: <name>\" title, so the plan has no name".to_string(),
+            ));
+        }
+        if !self.sections.contains_key("Status") {
+            let at = self.title_end.map_or(0, |end| {
+                let rest = &self.content[end..];
+                end + (rest.len() - rest.trim_start().len())
+            });
+            let mut insert = String::new();
+            if at > 0 && !self.content[..at].ends_with("\n\n") {
+                insert.push_str(if self.content[..at].ends_with('\n') {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            insert.push_str("## Status\n\nIn Progress\n");
+            if at < self.content.len() {
+                insert.push('\n');
+            }
+            self.diagnostics.push(
+                Diagnostic::error(
+                    "missing-status",
+                    at..at,
+                    "no \"## Status\" section; the build reads the plan's status from it"
+                        .to_string(),
+                )
+                .with_fix(
+                    at..at,
+                    &insert,
+                    "add \"## Status\" set to \"In Progress\"",
+                ),
+            );
+        }
+        if !self.sections.contains_key("Tasks") {
+            self.diagnostics.push(Diagnostic::warning(
+                "missing-tasks",
+                0..0,
+                "no \"## Tasks\" section, so the plan has nothing to build".to_string(),
+            ));
+        }
+    }
+
+    fn check_dependencies(&mut self) {
+        let mut ids: HashMap<&str, usize> = HashMap::new();
+        for site in &self.tasks {
+            if let Some(id) = &site.task.id {
+                if let Some(first) = ids.get(id.as_str()) {
+                    self.diagnostics.push(Diagnostic::warning(
+                        "task-id",
+                        site.range.clone(),
+                        format!(
+                            "task ID {} is already used on line {}, so neither task has an ID and \"{}:\" is read as part of the description",
+                            id,
+                            line_col(self.content, *first).0,
+                            id
+                        ),
+                    ));
+                } else {
+                    ids.insert(id, site.range.start);
+                }
+            }
+        }
+        for site in &self.tasks {
+            for dep in &site.task.depends_on {
+                if !ids.contains_key(dep.as_str()) {
+                    self.diagnostics.push(Diagnostic::warning(
+                        "dependency",
+                        site.range.clone(),
+                        format!(
+                            "task depends on unknown task {}, so its annotations are read as part of the description",
+                            dep
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Line of the first task named in a dependency cycle error.
+    fn cycle_site(&self, message: &str) -> Option<Range<usize>> {
+        let cycle = message.split("cycle: ").nth(1)?;
+        let first = cycle.split(" -> ").next()?.trim();
+        self.tasks
+            .iter()
+            .find(|site| site.task.id.as_deref() == Some(first))
+            .map(|site| site.range.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLEAN: &str = r#"# Progress: Todo App
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1: Setup
+
+- [x] T1: Create project
+- [-] T2: Deploy (after T1; blocked: no credentials)
+
+## Recent Attempts
+
+### Iteration 1
+
+- Tried: Run tests
+- Result: Two failures
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+| 1 | 2026-01-01 10:00 | 2m 5s | 1 | Setup |
+"#;
+
+    const BROKEN: &str = r#"# Progress: Todo App
+
+## Tasks
+
+- [ ] Orphan task
+
+### Phase 1
+
+- [ ] T1: First (after T9)
+- [] Not a checkbox
+- [ ] T1: Again (priorty: high; after T1)
+
+### Phase 1
+
+- [ ] Second phase task
+
+### Empty
+
+## Recent attempts
+
+### Iter 2
+
+- Tried: Something
+
+### iteration 3
+
+- tried: Something else
+- Outcome: Worked
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+| #4 | 2026-01-01 10:00 | 1m 0s | one | Notes |
+"#;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(&str, usize)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.code, line_col(BROKEN, d.range.start).0))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_file_has_no_diagnostics() {
+        assert_eq!(lint(CLEAN), vec![]);
+    }
+
+    #[test]
+    fn test_reports_problems_with_lines() {
+        let diagnostics = lint(BROKEN);
+        assert_eq!(
+            codes(&diagnostics),
+            vec![
+                ("missing-status", 3),
+                ("task-outside-phase", 5),
+                ("dependency", 9),
+                ("checkbox", 10),
+                ("annotation", 11),
+                ("task-id", 11),
+                ("duplicate-phase", 13),
+                ("empty-phase", 17),
+                ("section-name", 19),
+                ("attempt-heading", 21),
+                ("attempt-heading", 25),
+                ("attempt-entry", 25),
+                ("attempt-entry", 27),
+                ("attempt-entry", 28),
+                ("log-row", 34),
+            ]
+        );
+        let by_code = |code: &str| diagnostics.iter().find(|d| d.code == code).expect(code);
+        assert_eq!(by_code("task-outside-phase").severity, Severity::Error);
+        assert_eq!(
+            by_code("dependency").message,
+            "task depends on unknown task T9, so its annotations are read as part of the description"
+        );
+        assert_eq!(by_code("dependency").severity, Severity::Warning);
+        assert_eq!(
+            by_code("annotation").message,
+            "unrecognized annotation \"priorty: high\", so the whole \"(priorty: high; after T1)\" group is read as part of the description"
+        );
+        assert_eq!(line_col(BROKEN, by_code("checkbox").range.start), (10, 3));
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message == "attempt for iteration 3 has no \"Result:\" entry"));
+    }
+
+    #[test]
+    fn test_render_underlines_span() {
+        let diagnostic = lint(BROKEN)
+            .into_iter()
+            .find(|d| d.code == "checkbox")
+            .expect("checkbox");
+        assert_eq!(
+            diagnostic.render("progress.md", BROKEN),
+            "warning[checkbox]: \"[]\" isn't a checkbox, so this item is read as phase text rather than a task\n\
+             \x20 --> progress.md:10:3\n\
+             \x20  |\n\
+             10 | - [] Not a checkbox\n\
+             \x20  |   ^^\n\
+             \x20  = fix: write the checkbox as \"[ ]\"\n"
+        );
+    }
+
+    #[test]
+    fn test_fix_repairs_mechanical_problems() {
+        let (fixed, applied) = apply_fixes(BROKEN, &lint(BROKEN));
+        assert_eq!(applied, 8);
+        assert!(fixed.starts_with(
+            "# Progress: Todo App\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### Ungrouped tasks\n\n- [ ] Orphan task\n"
+        ));
+        assert!(fixed.contains("- [ ] Not a checkbox\n"));
+        assert!(!fixed.contains("### Empty"));
+        assert!(fixed.contains("## Recent Attempts\n"));
+        assert!(fixed.contains("### Iteration 3\n\n- Tried: Something else\n"));
+        assert!(fixed.contains("| 4 | 2026-01-01 10:00 |"));
+
+        let remaining: Vec<&str> = lint(&fixed).iter().map(|d| d.code).collect();
+        assert_eq!(
+            remaining,
+            vec![
+                "dependency",
+                "annotation",
+                "task-id",
+                "duplicate-phase",
+                "attempt-heading",
+                "attempt-entry",
+                "attempt-entry",
+                "log-row"
+            ]
+        );
+        assert_eq!(apply_fixes(&fixed, &lint(&fixed)).1, 0);
+    }
+
+    #[test]
+    fn test_short_log_row_is_counted_from_source() {
+        let content = "# Progress: X\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### P\n\n- [ ] A\n\n\
+                       ## Iteration Log\n\n\
+                       | Iteration | Started | Duration | Tasks Completed | Notes |\n\
+                       |-----------|---------|----------|-----------------|-------|\n\
+                       | 1 | 2026-01-01 10:00 | 1m 0s | 1 | a \\| b |\n\
+                       | 2 | 2026-01-01 10:05 | 2m 0s |\n\
+                       \n## Notes\n";
+        let diagnostics = lint(content);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            diagnostics[0].message,
+            "row has 3 cell(s) instead of 5; the missing ones are read as empty"
+        );
+        assert_eq!(line_col(content, diagnostics[0].range.start), (18, 1));
+    }
+
+    #[test]
+    fn test_reports_text_kept_as_notes() {
+        let content = "# Progress: X\n\n## Status\n\nIn Progress\n\n## Tasks\n\nWork top down.\n\n\
+                       ### P\n\n- [ ] A\n\n#### Later\n\n- [ ] B\n\n\
+                       ## Recent Attempts\n\nKeep the last few.\n\n### Iteration 1\n\n- Tried: A\n- Result: B\n\n\
+                       ## Iteration Log\n\n| Iteration | Notes |\n|---|---|\n| 1 | ok |\n";
+        let diagnostics = lint(content);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.code, line_col(content, d.range.start).0))
+                .collect::<Vec<_>>(),
+            vec![
+                ("outside-phase", 9),
+                ("sub-heading", 15),
+                ("notes", 21),
+                ("log-table", 30)
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "\"#### Later\" is text of phase \"P\", not a phase of its own; phase text is written above the phase's tasks, so use \"###\" for a new phase"
+        );
+
+        // None of it is lost when the file is rewritten
+        let pf = ProgressFile::parse(content).expect("parse");
+        assert_eq!(pf.tasks.len(), 1);
+        assert_eq!(pf.attempts_notes, "Keep the last few.");
+        assert!(pf.log_notes.contains("| 1 | ok |"));
+    }
+
+    #[test]
+    fn test_missing_and_empty_status() {
+        let (fixed, _) = apply_fixes("# Progress: X\n", &lint("# Progress: X\n"));
+        assert_eq!(fixed, "# Progress: X\n\n## Status\n\nIn Progress\n");
+
+        let content = "# Progress: X\n\n## Status\n\n## Tasks\n\n### P\n\n- [ ] A\n";
+        let diagnostics = lint(content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "Status section is empty");
+        assert_eq!(line_col(content, diagnostics[0].range.start), (3, 1));
+        let (fixed, _) = apply_fixes(content, &diagnostics);
+        assert_eq!(
+            ProgressFile::parse(&fixed).expect("parse").status,
+            "In Progress"
+        );
+    }
+
+    #[test]
+    fn test_log_rows_without_table_and_cycles() {
+        let content = "# Progress: X\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### P\n\n\
+                       - [ ] T1: A (after T2)\n- [ ] T2: B (after T1)\n\n## Iteration Log\n\n\
+                       | Iteration | Started | Duration | Tasks Completed | Notes |\n| 1 | a | b | 1 | c |\n";
+        let diagnostics = lint(content);
+        assert_eq!(
+            diagnostics.iter().map(|d| d.code).collect::<Vec<_>>(),
+            vec!["log-table"]
+        );
+
+        let content = content.replace(
+            "\n| 1 | a | b | 1 | c |",
+            "\n|---|---|---|---|---|\n| 1 | a | b | 1 | c |",
+        );
+        let diagnostics = lint(&content);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "parse");
+        assert_eq!(
+            diagnostics[0].message,
+            "Progress file parse error: Task dependency cycle: T1 -> T2 -> T1"
+        );
+        assert_eq!(line_col(&content, diagnostics[0].range.start), (11, 1));
+    }
+}
@@ -1,13 +1,44 @@
 //! Build loop module for autonomous task execution.
 //!
 //! Provides the core build loop that iterates through a progress file,
-//! spawning Claude subprocesses to complete tasks one at a time.
+//! spawning Claude subprocesses to complete tasks one at a time, or several
+//! at once in separate git worktrees (`--parallel`).
 
+pub mod budget;
 mod command;
+pub mod escalation;
+pub mod events;
+pub mod guard;
+pub mod hooks;
 mod iteration;
+pub mod journal;
+pub mod lint;
+pub mod notify;
+mod parallel;
+mod pause;
+pub mod replay;
+pub mod stall;
+pub mod status;
+pub mod tasks;
 mod state;
 pub mod tokens;
+pub mod transcript;
+mod verify;
 
+pub use budget::Budget;
 pub use command::run_build_command;
+pub use escalation::TaskAttempts;
+pub use events::{BuildEvent, EventSink};
+pub use guard::{GuardrailAction, GuardrailRule};
+pub use hooks::{Hook, HookConfig, HookPayload};
+pub use journal::BuildJournal;
+pub use lint::{run_lint_command, Diagnostic};
+pub use notify::{NotifyEvent, NotifyPayload};
+pub use parallel::run_parallel_build;
+pub use replay::run_replay_command;
+pub use stall::{StallPolicy, StallTracker};
+pub use status::{run_status_command, StatusReport};
+pub use tasks::{format_task_list, run_task_edit, TaskAddress, TaskEdit};
 pub use state::{BuildContext, BuildState, DoneReason, IterationResult};
 pub use tokens::{format_tokens, IterationTokens, TokenUsage};
+pub use transcript::{TranscriptArchive, TranscriptRecord};
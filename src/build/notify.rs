@@ -0,0 +1,299 @@
+//! Build notifications.
+//!
+//! When `notify_script` is set, it is run through `notify_shell -c` every
+//! `notify_interval` iterations and once when the build finishes, fails,
+//! stalls or runs out of budget. The script receives the build state as
+//! `RSLPH_*` environment variables, with the full payload as JSON in
+//! `RSLPH_NOTIFY_JSON`. The build waits for the script, and one still running
+//! after `notify_timeout` seconds is killed so it can't hold up the build for
+//! long. Its output is captured and logged rather than written over the TUI.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use super::state::{BuildContext, DoneReason};
+use super::tokens::TokenUsage;
+
+/// Something worth telling the user about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotifyEvent {
+    /// `notify_interval` iterations have passed.
+    Progress,
+    /// The build stopped.
+    Done(DoneReason),
+    /// The build failed with an error.
+    Failed(String),
+}
+
+impl NotifyEvent {
+    /// Short event name passed as `RSLPH_EVENT`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotifyEvent::Progress => "progress",
+            NotifyEvent::Done(DoneReason::Stalled) => "stalled",
+            NotifyEvent::Done(DoneReason::BudgetExhausted) => "budget",
+            NotifyEvent::Done(_) => "done",
+            NotifyEvent::Failed(_) => "failed",
+        }
+    }
+}
+
+/// Build state sent to the notify script.
+#[derive(Debug, Clone, Serialize)]
+pub struct NotifyPayload {
+    pub event: String,
+    /// The `DoneReason` for terminal events.
+    pub reason: Option<String>,
+    /// The error for failed builds.
+    pub error: Option<String>,
+    pub project: String,
+    pub progress_path: String,
+    pub iteration: u32,
+    pub max_iterations: u32,
+    pub tasks_completed: usize,
+    pub tasks_total: usize,
+    pub tasks_blocked: usize,
+    pub tokens: TokenUsage,
+}
+
+impl NotifyPayload {
+    /// Capture the current build state for `event`.
+    pub fn new(ctx: &BuildContext, event: &NotifyEvent) -> Self {
+        let (reason, error) = match event {
+            NotifyEvent::Progress => (None, None),
+            NotifyEvent::Done(reason) => (Some(reason.to_string()), None),
+            NotifyEvent::Failed(error) => (None, Some(error.clone())),
+        };
+
+        Self {
+            event: event.name().to_string(),
+            reason,
+            error,
+            project: ctx.project_name.clone(),
+            progress_path: ctx.progress_path.display().to_string(),
+            iteration: ctx.current_iteration,
+            max_iterations: ctx.max_iterations,
+            tasks_completed: ctx.progress.completed_tasks(),
+            tasks_total: ctx.progress.total_tasks(),
+            tasks_blocked: ctx.progress.blocked_tasks(),
+            tokens: ctx.total_tokens.clone(),
+        }
+    }
+
+    /// Environment variables for the notify script.
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        vec![
+            ("RSLPH_EVENT", self.event.clone()),
+            ("RSLPH_REASON", self.reason.clone().unwrap_or_default()),
+            ("RSLPH_ERROR", self.error.clone().unwrap_or_default()),
+            ("RSLPH_PROJECT", self.project.clone()),
+            ("RSLPH_PROGRESS_PATH", self.progress_path.clone()),
+            ("RSLPH_ITERATION", self.iteration.to_string()),
+            ("RSLPH_MAX_ITERATIONS", self.max_iterations.to_string()),
+            ("RSLPH_TASKS_COMPLETED", self.tasks_completed.to_string()),
+            ("RSLPH_TASKS_TOTAL", self.tasks_total.to_string()),
+            ("RSLPH_TASKS_BLOCKED", self.tasks_blocked.to_string()),
+            ("RSLPH_TOTAL_TOKENS", self.tokens.total().to_string()),
+            ("RSLPH_COST_USD", format!("{:.4}", self.tokens.cost_usd)),
+            ("RSLPH_NOTIFY_JSON", json),
+        ]
+    }
+}
+
+/// Run the notify script for `event`, if one is configured.
+///
+/// The build waits for the script up to `notify_timeout`; its output is
+/// logged, and a failing or timed-out script is otherwise ignored.
+pub async fn notify(ctx: &BuildContext, event: NotifyEvent) {
+    let Some(ref script) = ctx.config.notify_script else {
+        return;
+    };
+
+    let payload = NotifyPayload::new(ctx, &event);
+    let working_dir = ctx
+        .progress_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+
+    let timeout = Duration::from_secs(ctx.config.notify_timeout);
+
+    let child = Command::new(&ctx.config.notify_shell)
+        .arg("-c")
+        .arg(script)
+        .envs(payload.env())
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn();
+    let child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            ctx.log(&format!(
+                "[NOTIFY] Warning: failed to run '{}': {}",
+                ctx.config.notify_shell, e
+            ));
+            return;
+        }
+    };
+
+    let output = tokio::time::timeout(timeout, child.wait_with_output()).await;
+    if let Ok(Ok(ref output)) = output {
+        for stream in [&output.stdout, &output.stderr] {
+            for line in String::from_utf8_lossy(stream).lines() {
+                if !line.trim().is_empty() {
+                    ctx.log(&format!("[NOTIFY] {}", line));
+                }
+            }
+        }
+    }
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            ctx.log(&format!("[NOTIFY] Sent {} notification", payload.event))
+        }
+        Ok(Ok(output)) => ctx.log(&format!(
+            "[NOTIFY] Warning: notify script {}",
+            output.status
+        )),
+        Ok(Err(e)) => ctx.log(&format!(
+            "[NOTIFY] Warning: failed to run '{}': {}",
+            ctx.config.notify_shell, e
+        )),
+        Err(_) => ctx.log(&format!(
+            "[NOTIFY] Warning: notify script timed out after {}s and was killed",
+            timeout.as_secs()
+        )),
+    }
+}
+
+/// Send a progress notification if `iteration` is on the `notify_interval`.
+pub async fn notify_iteration(ctx: &BuildContext, iteration: u32) {
+    let interval = ctx.config.notify_interval;
+    if interval > 0 && iteration.is_multiple_of(interval) {
+        notify(ctx, NotifyEvent::Progress).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::progress::ProgressFile;
+    use crate::prompts::PromptMode;
+    use tempfile::TempDir;
+    use tokio_util::sync::CancellationToken;
+
+    fn context(dir: &TempDir, script: &str) -> BuildContext {
+        let config = Config {
+            notify_script: Some(script.to_string()),
+            ..Default::default()
+        };
+        let mut ctx = BuildContext::new(
+            dir.path().join("PROGRESS.md"),
+            ProgressFile::default(),
+            config,
+            PromptMode::Basic,
+            CancellationToken::new(),
+            false,
+            false,
+            false,
+        );
+        ctx.current_iteration = 4;
+        ctx
+    }
+
+    #[test]
+    fn test_event_names() {
+        assert_eq!(NotifyEvent::Progress.name(), "progress");
+        assert_eq!(
+            NotifyEvent::Done(DoneReason::AllTasksComplete).name(),
+            "done"
+        );
+        assert_eq!(NotifyEvent::Done(DoneReason::Stalled).name(), "stalled");
+        assert_eq!(
+            NotifyEvent::Done(DoneReason::BudgetExhausted).name(),
+            "budget"
+        );
+        assert_eq!(NotifyEvent::Failed("boom".to_string()).name(), "failed");
+    }
+
+    #[tokio::test]
+    async fn test_notify_runs_script_with_payload() {
+        let dir = TempDir::new().expect("temp dir");
+        let ctx = context(
+            &dir,
+            "printf '%s %s %s' \"$RSLPH_EVENT\" \"$RSLPH_ITERATION\" \"$RSLPH_REASON\" > notified.txt",
+        );
+
+        notify(&ctx, NotifyEvent::Done(DoneReason::Stalled)).await;
+
+        let written = std::fs::read_to_string(dir.path().join("notified.txt")).expect("notified");
+        assert_eq!(written, "stalled 4 Build stalled");
+    }
+
+    #[tokio::test]
+    async fn test_notify_iteration_honors_interval() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut ctx = context(&dir, "echo \"$RSLPH_ITERATION\" >> notified.txt");
+        ctx.config.notify_interval = 2;
+
+        for iteration in 1..=5 {
+            notify_iteration(&ctx, iteration).await;
+        }
+
+        let written = std::fs::read_to_string(dir.path().join("notified.txt")).expect("notified");
+        assert_eq!(written.lines().count(), 2);
+
+        let payload = NotifyPayload::new(&ctx, &NotifyEvent::Progress);
+        let json: serde_json::Value =
+            serde_json::from_str(&payload.env().last().expect("json").1).expect("valid json");
+        assert_eq!(json["event"], "progress");
+        assert_eq!(json["iteration"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_notify_logs_script_output() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut ctx = context(&dir, "echo sent; echo 'curl: 100%' >&2");
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        ctx.tui_tx = Some(tx);
+
+        notify(&ctx, NotifyEvent::Progress).await;
+
+        let mut logs = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let crate::tui::SubprocessEvent::Log(line) = event {
+                logs.push(line);
+            }
+        }
+        assert_eq!(
+            logs,
+            vec![
+                "[NOTIFY] sent",
+                "[NOTIFY] curl: 100%",
+                "[NOTIFY] Sent progress notification"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_kills_slow_script() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut ctx = context(&dir, "sleep 30; touch notified.txt");
+        ctx.config.notify_timeout = 1;
+
+        let started = std::time::Instant::now();
+        notify(&ctx, NotifyEvent::Failed("boom".to_string())).await;
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!dir.path().join("notified.txt").exists());
+    }
+}
@@ -0,0 +1,706 @@
+//! Parallel build execution in git worktrees.
+//!
+//! Each iteration picks up to N incomplete tasks, one per phase, and runs each
+//! in its own detached worktree with a fresh Claude subprocess. Workers commit
+//! their changes (without the progress file) and the commits are merged back in
+//! plan order. The `pre_commit` hook runs in each worker's worktree before its
+//! commit, so a veto keeps the task's changes out of the main checkout. The
+//! main progress file is then reconciled: merged tasks are
+//! checked off, and merge conflicts are recorded as Recent Attempts so the task
+//! is retried on a later iteration.
+//!
+//! Workers run on a `JoinSet` like eval trials, but without the semaphore
+//! `eval::parallel` uses: a batch never holds more than `--parallel` tasks, so
+//! every worker can start at once and the next batch waits for the merge.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+use crate::error::{RslphError, VcsError};
+use crate::progress::{Attempt, ProgressFile};
+use crate::prompts::PromptMode;
+use crate::vcs::{detect_vcs, GitVcs, VcsType};
+
+use super::command::{
+    emit_build_started, emit_done, handle_stall, log_iteration, print_completion_message,
+};
+use super::escalation::TaskAttempts;
+use super::events::{BuildEvent, EventSink};
+use super::hooks::{run_hook, run_on_done, Hook, HookPayload};
+use super::iteration::{format_iteration_commit, run_single_iteration};
+use super::journal::create_run_dir;
+use super::notify::{notify, notify_iteration, NotifyEvent};
+use super::state::{BuildContext, DoneReason, IterationResult};
+use super::tokens::{IterationTokens, TokenUsage};
+use super::transcript::{start_archive, TranscriptArchive};
+use super::verify::newly_completed;
+
+/// A task identified by (phase name, description).
+type TaskRef = (String, String);
+
+/// The main checkout that worktrees branch from and merge back into.
+struct Checkout {
+    git: GitVcs,
+    root: PathBuf,
+    /// Progress file path relative to the repository root.
+    progress_rel: PathBuf,
+}
+
+impl Checkout {
+    /// Locate the Git repository containing the progress file.
+    fn open(progress_path: &Path) -> Result<Self, RslphError> {
+        let progress_abs = progress_path.canonicalize()?;
+        let detection = detect_vcs(&progress_abs)?
+            .filter(|d| d.vcs_type == VcsType::Git)
+            .ok_or_else(|| {
+                VcsError::Detection("parallel builds require a Git repository".to_string())
+            })?;
+
+        let progress_rel = progress_abs
+            .strip_prefix(&detection.root)
+            .map(Path::to_path_buf)
+            .map_err(|_| {
+                VcsError::Detection(format!(
+                    "progress file {} is outside the repository",
+                    progress_path.display()
+                ))
+            })?;
+
+        let git = GitVcs::new(detection.root.clone());
+        git.head().map_err(|_| {
+            VcsError::Detection(
+                "parallel builds need at least one commit to create worktrees from".to_string(),
+            )
+        })?;
+
+        Ok(Self {
+            git,
+            root: detection.root,
+            progress_rel,
+        })
+    }
+}
+
+/// Everything a worker needs, owned so it can run on its own task.
+struct WorkerJob {
+    task: TaskRef,
+    iteration: u32,
+    root: PathBuf,
+    progress_rel: PathBuf,
+    progress: ProgressFile,
+    config: Config,
+    mode: PromptMode,
+    no_dsp: bool,
+    cancel_token: CancellationToken,
+    /// The stall policy escalated; workers switch model and approach.
+    escalated: bool,
+    /// Earlier iterations per task, for the model escalation ladder.
+    task_attempts: TaskAttempts,
+    events: Option<EventSink>,
+    transcripts: Option<TranscriptArchive>,
+}
+
+/// What a worker produced.
+struct WorkerOutcome {
+    task: TaskRef,
+    /// Tasks the worker checked off.
+    completed: Vec<TaskRef>,
+    /// Commit holding the worker's changes (None if it changed nothing), or
+    /// why the worktree couldn't be set up or committed.
+    commit: Result<Option<String>, RslphError>,
+    /// Attempts the worker recorded for this iteration.
+    attempts: Vec<Attempt>,
+    tokens: TokenUsage,
+    /// Model the worker's iteration ran on.
+    model: Option<String>,
+}
+
+impl WorkerOutcome {
+    /// A worker that failed before its agent ran.
+    fn failed(task: TaskRef, error: RslphError) -> Self {
+        Self {
+            task,
+            completed: Vec::new(),
+            commit: Err(error),
+            attempts: Vec::new(),
+            tokens: TokenUsage::default(),
+            model: None,
+        }
+    }
+}
+
+/// Run the build loop with up to `parallel` tasks per iteration.
+///
+/// Requires the progress file to live in a Git repository with at least one
+/// commit. Workers start from the checked-out commit, so uncommitted changes
+/// (other than the progress file) are not visible to them.
+///
+/// # Returns
+///
+/// * `Ok(TokenUsage)` - Build completed with token usage
+/// * `Err(e)` - Build failed with error
+pub async fn run_parallel_build(
+    progress_path: PathBuf,
+    parallel: usize,
+    mode: PromptMode,
+    no_dsp: bool,
+    config: &Config,
+    cancel_token: CancellationToken,
+    events: Option<EventSink>,
+) -> color_eyre::Result<TokenUsage> {
+    let progress = ProgressFile::load(&progress_path)?;
+    let checkout = Checkout::open(&progress_path)?;
+
+    println!("Build started: {}", progress_path.display());
+    println!(
+        "Tasks: {}/{} complete",
+        progress.completed_tasks(),
+        progress.total_tasks()
+    );
+
+    let mut ctx = BuildContext::new(
+        progress_path,
+        progress,
+        config.clone(),
+        mode,
+        cancel_token,
+        false,
+        false,
+        no_dsp,
+    );
+    ctx.events = events;
+    if ctx.config.archive_transcripts {
+        // Parallel builds keep no journal; the run directory only holds transcripts
+        match create_run_dir(&ctx.progress_path) {
+            Ok((run_id, dir)) => {
+                ctx.log(&format!("[TRANSCRIPT] Run {} ({})", run_id, dir.display()));
+                start_archive(&mut ctx, &dir);
+            }
+            Err(e) => ctx.log(&format!(
+                "[TRANSCRIPT] Warning: transcripts disabled: {}",
+                e
+            )),
+        }
+    }
+    emit_build_started(&ctx);
+
+    let reason = loop {
+        if ctx.cancel_token.is_cancelled() {
+            break DoneReason::UserCancelled;
+        }
+
+        ctx.progress = ProgressFile::load(&ctx.progress_path)?;
+        if ctx.progress.is_done() {
+            break DoneReason::RalphDoneMarker;
+        }
+        if ctx.progress.completed_tasks() == ctx.progress.total_tasks()
+            && ctx.progress.total_tasks() > 0
+        {
+            break DoneReason::AllTasksComplete;
+        }
+        if ctx.progress.is_finished_with_blocked() {
+            break DoneReason::CompleteWithBlocked;
+        }
+        if let Some(limit) = ctx.budget_exceeded() {
+            ctx.log(&format!("[BUILD] Budget exhausted: {}", limit));
+            break DoneReason::BudgetExhausted;
+        }
+        if ctx.current_iteration >= ctx.max_iterations {
+            break DoneReason::MaxIterationsReached;
+        }
+        if ctx.progress.next_task().is_none() && ctx.progress.blocked_tasks() > 0 {
+            break DoneReason::Stalled;
+        }
+
+        ctx.current_iteration += 1;
+        ctx.iteration_start = Some(Instant::now());
+
+        let batch = next_batch(&ctx.progress, parallel);
+        ctx.log(&format!(
+            "\n--- Iteration {} ({} task(s) in parallel) ---",
+            ctx.current_iteration,
+            batch.len()
+        ));
+
+        let started: Vec<String> = batch.iter().map(|(_, task)| task.clone()).collect();
+        let outcomes = run_workers(&ctx, &checkout, batch).await;
+        for task in &started {
+            ctx.task_attempts.start(Some(task));
+        }
+        let tasks_completed = reconcile(&mut ctx, &checkout, outcomes)?;
+
+        let iteration = ctx.current_iteration;
+        ctx.log(&format!(
+            "[BUILD] Iteration {} complete: {} task(s) merged",
+            iteration, tasks_completed
+        ));
+        log_iteration(&mut ctx, iteration, tasks_completed)?;
+        notify_iteration(&ctx, iteration).await;
+        let stalled = handle_stall(&mut ctx, iteration, tasks_completed)?;
+
+        // Commit the reconciled progress file
+        if tasks_completed > 0 && ctx.vcs.is_some() {
+            let mut payload = HookPayload::new(&ctx, Hook::PreCommit);
+            payload.completed = ctx.progress.completed_this_iteration.clone();
+            if let Err(failure) = run_hook(&ctx, Hook::PreCommit, &payload).await {
+                ctx.log("[VCS] Commit vetoed by pre_commit hook");
+                ctx.progress.add_attempt(
+                    iteration,
+                    "Commit iteration changes",
+                    &format!("Vetoed by pre_commit hook: {}", failure.summary),
+                    Some("Changes were left uncommitted; resolve the pre_commit hook failure"),
+                );
+                ctx.progress
+                    .trim_attempts(ctx.config.recent_threads as usize);
+                ctx.progress.write(&ctx.progress_path)?;
+            } else if let Some(ref vcs) = ctx.vcs {
+                let message =
+                    format_iteration_commit(&ctx.project_name, iteration, tasks_completed);
+                match vcs.commit_all(&message) {
+                    Ok(Some(hash)) => {
+                        ctx.log(&format!("[VCS] Committed: {} ({})", hash, vcs.vcs_type()));
+                        ctx.emit(BuildEvent::Commit {
+                            iteration,
+                            hash,
+                            vcs: vcs.vcs_type().to_string(),
+                        });
+                    }
+                    Ok(None) => ctx.log("[VCS] No file changes to commit"),
+                    Err(e) => ctx.log(&format!("[VCS] Warning: {}", e)),
+                }
+            }
+        }
+
+        if let Some(reason) = stalled {
+            break reason;
+        }
+    };
+
+    print_completion_message(&reason, &ctx);
+    emit_done(&ctx, &reason);
+    run_on_done(&ctx, &reason).await;
+    notify(&ctx, NotifyEvent::Done(reason)).await;
+
+    Ok(ctx.total_tokens)
+}
+
+/// Pick up to `limit` tasks to run at once: the first ready task of each
+/// phase, in plan order. Phases are assumed to be independent of each other
+/// apart from explicit task dependencies, which must already be complete.
+fn next_batch(progress: &ProgressFile, limit: usize) -> Vec<TaskRef> {
+    progress
+        .tasks
+        .iter()
+        .filter_map(|phase| {
+            phase
+                .tasks
+                .iter()
+                .find(|t| progress.is_ready(t))
+                .map(|t| (phase.name.clone(), t.description.clone()))
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Run one worker per task concurrently, returning outcomes in batch order.
+///
+/// The batch is already capped at the `--parallel` limit, so every task gets
+/// its own worker at once.
+async fn run_workers(
+    ctx: &BuildContext,
+    checkout: &Checkout,
+    batch: Vec<TaskRef>,
+) -> Vec<WorkerOutcome> {
+    let mut set = JoinSet::new();
+
+    for (index, task) in batch.into_iter().enumerate() {
+        let job = WorkerJob {
+            task,
+            iteration: ctx.current_iteration,
+            root: checkout.root.clone(),
+            progress_rel: checkout.progress_rel.clone(),
+            progress: ctx.progress.clone(),
+            config: ctx.config.clone(),
+            mode: ctx.mode,
+            no_dsp: ctx.no_dsp,
+            cancel_token: ctx.cancel_token.clone(),
+            escalated: ctx.stall.escalated,
+            task_attempts: ctx.task_attempts.clone(),
+            events: ctx.events.clone(),
+            transcripts: ctx.transcripts.as_ref().map(|t| t.for_worker(index + 1)),
+        };
+
+        set.spawn(async move { (index, run_worker(job).await) });
+    }
+
+    let mut outcomes = Vec::new();
+    while let Some(result) = set.join_next().await {
+        match result {
+            Ok((index, outcome)) => outcomes.push((index, outcome)),
+            Err(e) => ctx.log(&format!("[BUILD] Worker task panicked: {}", e)),
+        }
+    }
+
+    outcomes.sort_by_key(|(index, _)| *index);
+    outcomes.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// Run a single task in a fresh worktree and commit what it changed.
+///
+/// Failures are returned in the outcome so the usage of an agent that already
+/// ran still reaches the build totals.
+async fn run_worker(job: WorkerJob) -> WorkerOutcome {
+    let main = GitVcs::new(job.root.clone());
+    let tmp = match tempfile::Builder::new().prefix("rslph-worktree-").tempdir() {
+        Ok(tmp) => tmp,
+        Err(e) => return WorkerOutcome::failed(job.task, e.into()),
+    };
+    let tree_path = tmp.path().join("tree");
+    if let Err(e) = main.add_worktree(&tree_path) {
+        return WorkerOutcome::failed(job.task, e.into());
+    }
+
+    let outcome = run_in_worktree(&job, &tree_path).await;
+
+    // Unmerged changes are discarded with the worktree
+    if let Err(e) = main.remove_worktree(&tree_path) {
+        eprintln!("[VCS] Warning: failed to remove worktree: {}", e);
+    }
+
+    outcome
+}
+
+/// Run Claude on the job's task inside `tree_path`.
+async fn run_in_worktree(job: &WorkerJob, tree_path: &Path) -> WorkerOutcome {
+    let progress_path = tree_path.join(&job.progress_rel);
+    let prepared = progress_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(RslphError::from)
+        .and_then(|()| job.progress.write(&progress_path));
+    if let Err(e) = prepared {
+        return WorkerOutcome::failed(job.task.clone(), e);
+    }
+
+    let mut ctx = BuildContext::new(
+        progress_path.clone(),
+        job.progress.clone(),
+        job.config.clone(),
+        job.mode,
+        job.cancel_token.clone(),
+        true,
+        false,
+        job.no_dsp,
+    );
+    // Worktree changes are committed below without the progress file, and a
+    // failed task's worktree is discarded rather than rolled back
+    ctx.vcs = None;
+    ctx.config.rollback_failed_iterations = false;
+    ctx.current_iteration = job.iteration;
+    ctx.iteration_start = Some(Instant::now());
+    ctx.focus_task = Some(job.task.1.clone());
+    ctx.stall.escalated = job.escalated;
+    ctx.task_attempts = job.task_attempts.clone();
+    ctx.events = job.events.clone();
+    ctx.transcripts = job.transcripts.clone();
+
+    ctx.log(&format!("[BUILD] Starting task: {}", job.task.1));
+    let result = run_single_iteration(&mut ctx).await;
+
+    let after = ProgressFile::load(&progress_path).unwrap_or_else(|_| job.progress.clone());
+    let mut attempts: Vec<Attempt> = after
+        .recent_attempts
+        .iter()
+        .filter(|a| a.iteration == job.iteration)
+        .cloned()
+        .collect();
+
+    let mut completed = match result {
+        Ok(IterationResult::Continue { .. }) => newly_completed(&job.progress, &after),
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            ctx.log(&format!("[BUILD] Task '{}' failed: {}", job.task.1, e));
+            Vec::new()
+        }
+    };
+
+    // The worker has no VCS of its own, so pre_commit runs here, before the
+    // commit that would be merged
+    if !completed.is_empty() {
+        let mut payload = HookPayload::new(&ctx, Hook::PreCommit);
+        payload.task = Some(job.task.1.clone());
+        payload.completed = completed.iter().map(|(_, d)| d.clone()).collect();
+        payload.tasks_completed = after.completed_tasks();
+        payload.tasks_total = after.total_tasks();
+        if let Err(failure) = run_hook(&ctx, Hook::PreCommit, &payload).await {
+            ctx.log(&format!(
+                "[VCS] Commit of '{}' vetoed by pre_commit hook",
+                job.task.1
+            ));
+            attempts.push(Attempt {
+                iteration: job.iteration,
+                tried: format!("Commit parallel task: {}", job.task.1),
+                result: format!("Vetoed by pre_commit hook: {}", failure.summary),
+                next: Some(
+                    "Changes were discarded; resolve the pre_commit hook failure".to_string(),
+                ),
+            });
+            completed.clear();
+        }
+    }
+
+    let commit = if completed.is_empty() {
+        Ok(None)
+    } else {
+        let message = format!(
+            "[{}][iter {}] {}",
+            ctx.project_name, job.iteration, job.task.1
+        );
+        GitVcs::new(tree_path.to_path_buf())
+            .commit_all_except(&message, &job.progress_rel)
+            .map_err(RslphError::from)
+    };
+
+    WorkerOutcome {
+        task: job.task.clone(),
+        completed,
+        commit,
+        attempts,
+        tokens: ctx.total_tokens,
+        model: ctx.iteration_tokens.last().and_then(|t| t.model.clone()),
+    }
+}
+
+/// Merge worker commits in order and update the main progress file.
+///
+/// Returns the number of tasks checked off.
+fn reconcile(
+    ctx: &mut BuildContext,
+    checkout: &Checkout,
+    outcomes: Vec<WorkerOutcome>,
+) -> Result<u32, RslphError> {
+    let iteration = ctx.current_iteration;
+    let mut round_tokens = TokenUsage::default();
+    let mut merged: Vec<TaskRef> = Vec::new();
+    let mut models: Vec<String> = Vec::new();
+
+    ctx.progress.clear_iteration_completed();
+
+    for outcome in outcomes {
+        round_tokens.input_tokens += outcome.tokens.input_tokens;
+        round_tokens.output_tokens += outcome.tokens.output_tokens;
+        round_tokens.cache_creation_input_tokens += outcome.tokens.cache_creation_input_tokens;
+        round_tokens.cache_read_input_tokens += outcome.tokens.cache_read_input_tokens;
+        round_tokens.cost_usd += outcome.tokens.cost_usd;
+
+        ctx.progress.recent_attempts.extend(outcome.attempts);
+        if let Some(model) = outcome.model {
+            if !models.contains(&model) {
+                models.push(model);
+            }
+        }
+
+        let commit = match outcome.commit {
+            Ok(commit) => commit,
+            Err(e) => {
+                ctx.log(&format!(
+                    "[BUILD] Worker for '{}' failed: {}",
+                    outcome.task.1, e
+                ));
+                ctx.progress.add_attempt(
+                    iteration,
+                    &format!("Run parallel task: {}", outcome.task.1),
+                    &format!("Error: {}", e),
+                    Some("Task left incomplete; retry on a later iteration"),
+                );
+                continue;
+            }
+        };
+
+        if outcome.completed.is_empty() {
+            ctx.log(&format!("[BUILD] Task not completed: {}", outcome.task.1));
+            continue;
+        }
+
+        let merge_result = match commit {
+            Some(ref commit) => checkout
+                .git
+                .merge(commit, &format!("Merge parallel task: {}", outcome.task.1)),
+            None => Ok(()),
+        };
+
+        match merge_result {
+            Ok(()) => {
+                ctx.log(&format!("[VCS] Merged task: {}", outcome.task.1));
+                merged.extend(outcome.completed);
+            }
+            Err(e) => {
+                ctx.log(&format!(
+                    "[VCS] Could not merge '{}': {}",
+                    outcome.task.1, e
+                ));
+                let result = match e {
+                    VcsError::MergeConflict(_) => e.to_string(),
+                    _ => format!("Merge failed: {}", e),
+                };
+                ctx.progress.add_attempt(
+                    iteration,
+                    &format!("Merge parallel task: {}", outcome.task.1),
+                    &result,
+                    Some("Task left incomplete; retry on a later iteration"),
+                );
+            }
+        }
+    }
+
+    for phase in &mut ctx.progress.tasks {
+        for task in &mut phase.tasks {
+            if merged
+                .iter()
+                .any(|(p, d)| *p == phase.name && *d == task.description)
+            {
+                task.completed = true;
+            }
+        }
+    }
+    ctx.progress
+        .completed_this_iteration
+        .extend(merged.iter().map(|(_, d)| d.clone()));
+    ctx.progress
+        .trim_attempts(ctx.config.recent_threads as usize);
+
+    ctx.iteration_tokens.push(IterationTokens {
+        iteration,
+        input_tokens: round_tokens.input_tokens,
+        output_tokens: round_tokens.output_tokens,
+        cache_creation_input_tokens: round_tokens.cache_creation_input_tokens,
+        cache_read_input_tokens: round_tokens.cache_read_input_tokens,
+        cost_usd: round_tokens.cost_usd,
+        model: (!models.is_empty()).then(|| models.join(", ")),
+    });
+    ctx.total_tokens.input_tokens += round_tokens.input_tokens;
+    ctx.total_tokens.output_tokens += round_tokens.output_tokens;
+    ctx.total_tokens.cache_creation_input_tokens += round_tokens.cache_creation_input_tokens;
+    ctx.total_tokens.cache_read_input_tokens += round_tokens.cache_read_input_tokens;
+    ctx.total_tokens.cost_usd += round_tokens.cost_usd;
+
+    Ok(merged.len() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::{Task, TaskPhase};
+    use tempfile::TempDir;
+
+    fn phase(name: &str, tasks: &[(&str, bool)]) -> TaskPhase {
+        TaskPhase {
+            name: name.to_string(),
+            tasks: tasks
+                .iter()
+                .map(|(d, c)| Task {
+                    description: d.to_string(),
+                    completed: *c,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_next_batch_takes_one_task_per_phase() {
+        let progress = ProgressFile {
+            tasks: vec![
+                phase("Backend", &[("API", true), ("DB", false), ("Auth", false)]),
+                phase("Docs", &[("README", true)]),
+                phase("Frontend", &[("Form", false)]),
+                phase("Ops", &[("CI", false)]),
+            ],
+            ..Default::default()
+        };
+
+        let batch = next_batch(&progress, 2);
+        assert_eq!(
+            batch,
+            vec![
+                ("Backend".to_string(), "DB".to_string()),
+                ("Frontend".to_string(), "Form".to_string()),
+            ]
+        );
+        assert_eq!(next_batch(&progress, 8).len(), 3);
+    }
+
+    #[test]
+    fn test_next_batch_waits_for_dependencies() {
+        let progress = ProgressFile::parse(
+            "# Progress: Deps\n\n## Tasks\n\n### Backend\n\n- [ ] B1: API\n\n### Frontend\n\n- [ ] F1: Form (after B1)\n- [ ] F2: Styles\n",
+        )
+        .expect("parse");
+
+        let batch = next_batch(&progress, 4);
+        assert_eq!(
+            batch,
+            vec![
+                ("Backend".to_string(), "API".to_string()),
+                ("Frontend".to_string(), "Styles".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reconcile_keeps_usage_of_failed_worker() {
+        let dir = TempDir::new().expect("temp dir");
+        let progress = ProgressFile {
+            tasks: vec![phase("Backend", &[("API", false)])],
+            ..Default::default()
+        };
+        let mut ctx = BuildContext::new(
+            dir.path().join("PROGRESS.md"),
+            progress,
+            Config::default(),
+            PromptMode::Basic,
+            CancellationToken::new(),
+            false,
+            false,
+            false,
+        );
+        ctx.current_iteration = 2;
+        let checkout = Checkout {
+            git: GitVcs::new(dir.path().to_path_buf()),
+            root: dir.path().to_path_buf(),
+            progress_rel: PathBuf::from("PROGRESS.md"),
+        };
+        let task = ("Backend".to_string(), "API".to_string());
+        let outcome = WorkerOutcome {
+            completed: vec![task.clone()],
+            commit: Err(VcsError::CommitFailed("index.lock exists".to_string()).into()),
+            tokens: TokenUsage {
+                input_tokens: 1000,
+                output_tokens: 200,
+                cost_usd: 0.5,
+                ..Default::default()
+            },
+            ..WorkerOutcome::failed(task, RslphError::Cancelled)
+        };
+
+        let merged = reconcile(&mut ctx, &checkout, vec![outcome]).expect("reconcile");
+
+        assert_eq!(merged, 0);
+        assert!(!ctx.progress.tasks[0].tasks[0].completed);
+        assert_eq!(ctx.total_tokens.total(), 1200);
+        assert_eq!(ctx.total_tokens.cost_usd, 0.5);
+        let attempt = ctx.progress.recent_attempts.last().expect("attempt");
+        assert_eq!(attempt.iteration, 2);
+        assert_eq!(attempt.tried, "Run parallel task: API");
+        assert!(
+            attempt.result.contains("index.lock exists"),
+            "{}",
+            attempt.result
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! Pause support for the build loop.
+//!
+//! The TUI publishes its pause flag on a watch channel. The build loop holds
+//! before starting the next iteration while the flag is set, and can optionally
+//! suspend the running subprocess (SIGSTOP) until the flag is cleared.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::subprocess::{resume_process, suspend_process};
+
+/// Wait until the build is resumed or cancelled.
+///
+/// Returns immediately if the build is not paused. If the TUI drops its end of
+/// the channel the wait ends as well, so a closed TUI never blocks the loop.
+///
+/// # Returns
+///
+/// Time spent waiting.
+pub async fn wait_while_paused(
+    pause_rx: &mut watch::Receiver<bool>,
+    cancel_token: &CancellationToken,
+) -> Duration {
+    let start = Instant::now();
+
+    tokio::select! {
+        _ = cancel_token.cancelled() => {}
+        _ = pause_rx.wait_for(|paused| !*paused) => {}
+    }
+
+    start.elapsed()
+}
+
+/// Drive `fut` to completion under `duration`, suspending `pid` and its
+/// process group while paused.
+///
+/// Without a pause receiver this behaves like `tokio::time::timeout`. With one,
+/// the process is stopped whenever the pause flag is set and continued when it
+/// is cleared. Time spent suspended does not count against the timeout.
+///
+/// # Returns
+///
+/// * `Some(output)` - The future completed in time
+/// * `None` - The timeout elapsed
+pub async fn timeout_with_suspend<F: Future>(
+    duration: Duration,
+    fut: F,
+    pid: Option<u32>,
+    pause_rx: Option<watch::Receiver<bool>>,
+) -> Option<F::Output> {
+    let (Some(pid), Some(mut pause_rx)) = (pid, pause_rx) else {
+        return tokio::time::timeout(duration, fut).await.ok();
+    };
+
+    tokio::pin!(fut);
+    let mut deadline = tokio::time::Instant::now() + duration;
+    let mut suspended_at: Option<tokio::time::Instant> = None;
+    let mut watching = true;
+
+    // Honor a pause requested before the subprocess started
+    if *pause_rx.borrow_and_update() && suspend_process(pid) {
+        suspended_at = Some(tokio::time::Instant::now());
+    }
+
+    loop {
+        tokio::select! {
+            output = &mut fut => {
+                if suspended_at.is_some() {
+                    resume_process(pid);
+                }
+                return Some(output);
+            }
+
+            _ = tokio::time::sleep_until(deadline), if suspended_at.is_none() => {
+                return None;
+            }
+
+            changed = pause_rx.changed(), if watching => {
+                // Sender dropped (TUI gone): resume and stop watching
+                let paused = changed.is_ok() && *pause_rx.borrow_and_update();
+                watching = changed.is_ok();
+
+                match (paused, suspended_at) {
+                    (true, None) if suspend_process(pid) => {
+                        suspended_at = Some(tokio::time::Instant::now());
+                    }
+                    (false, Some(at)) => {
+                        resume_process(pid);
+                        deadline += at.elapsed();
+                        suspended_at = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Format a paused duration for log messages (e.g., "1m 5s").
+pub fn format_paused(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}m {}s", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_returns_immediately_when_not_paused() {
+        let (_tx, mut rx) = watch::channel(false);
+        let token = CancellationToken::new();
+
+        let waited = wait_while_paused(&mut rx, &token).await;
+        assert!(waited < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_resumed() {
+        let (tx, mut rx) = watch::channel(true);
+        let token = CancellationToken::new();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = tx.send(false);
+            // Keep sender alive until the waiter observed the change
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        });
+
+        let waited = wait_while_paused(&mut rx, &token).await;
+        assert!(waited >= Duration::from_millis(50));
+        assert!(!*rx.borrow());
+    }
+
+    #[tokio::test]
+    async fn test_wait_ends_on_cancel() {
+        let (_tx, mut rx) = watch::channel(true);
+        let token = CancellationToken::new();
+
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel.cancel();
+        });
+
+        wait_while_paused(&mut rx, &token).await;
+        assert!(token.is_cancelled());
+        assert!(*rx.borrow(), "still paused, wait ended due to cancel");
+    }
+
+    #[tokio::test]
+    async fn test_wait_ends_when_sender_dropped() {
+        let (tx, mut rx) = watch::channel(true);
+        let token = CancellationToken::new();
+        drop(tx);
+
+        let waited = wait_while_paused(&mut rx, &token).await;
+        assert!(waited < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_without_pause_receiver() {
+        let result = timeout_with_suspend(
+            Duration::from_millis(20),
+            tokio::time::sleep(Duration::from_secs(5)),
+            Some(1),
+            None,
+        )
+        .await;
+        assert!(result.is_none());
+
+        let result = timeout_with_suspend(Duration::from_secs(5), async { 42 }, None, None).await;
+        assert_eq!(result, Some(42));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_suspended_time_does_not_count_against_timeout() {
+        let mut child = tokio::process::Command::new("/bin/sleep")
+            .arg("0.3")
+            .spawn()
+            .expect("spawn should succeed");
+        let pid = child.id();
+
+        let (tx, rx) = watch::channel(true);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(600)).await;
+            let _ = tx.send(false);
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        });
+
+        // Without suspension accounting the 500ms timeout would fire while paused
+        let result =
+            timeout_with_suspend(Duration::from_millis(500), child.wait(), pid, Some(rx)).await;
+        let status = result.expect("should complete after resume");
+        assert!(status.expect("wait should succeed").success());
+    }
+
+    #[test]
+    fn test_format_paused() {
+        assert_eq!(format_paused(Duration::from_secs(0)), "0m 0s");
+        assert_eq!(format_paused(Duration::from_secs(65)), "1m 5s");
+    }
+}
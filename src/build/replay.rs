@@ -0,0 +1,493 @@
+//! Playback of archived iteration transcripts (`rslph replay`).
+//!
+//! Loads the `iter-NNN.jsonl` transcripts of a run directory and feeds the
+//! agent's output lines through the same TUI pipeline as a live build.
+//! Transcripts carry no per-line timing, so lines are paced at a fixed
+//! interval scaled by the playback speed.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+use crate::error::RslphError;
+use crate::pricing::PricingTable;
+use crate::progress::{ProgressFile, ProgressFormat};
+use crate::subprocess::{AgentBackend, AgentKind, AgentResponse};
+use crate::tui::{run_tui, App, Playback, SubprocessEvent, MAX_PLAYBACK_SPEED, MIN_PLAYBACK_SPEED};
+
+use super::iteration::stream_output;
+use super::pause::wait_while_paused;
+use super::transcript::{transcripts_in, TranscriptRecord};
+
+/// Delay between transcript lines at 1x speed.
+const LINE_DELAY: Duration = Duration::from_millis(250);
+
+/// A line to play back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayLine {
+    /// Start of a session; later stdout lines are parsed by `backend`.
+    Session { backend: AgentKind, note: String },
+    /// Raw stdout line from the agent.
+    Stdout(String),
+    /// Stderr line from the agent.
+    Stderr(String),
+    /// Note from the recording (truncation).
+    Note(String),
+}
+
+/// All recorded sessions of one iteration.
+#[derive(Debug, Clone)]
+pub struct ReplayIteration {
+    pub iteration: u32,
+    /// Model the first session was started with, if recorded.
+    pub model: Option<String>,
+    pub lines: Vec<ReplayLine>,
+    /// Progress file returned by the last session, if it parsed.
+    pub progress: Option<ProgressFile>,
+}
+
+/// Load the transcripts of a run directory in iteration order.
+///
+/// Timeout retries and parallel workers of the same iteration are merged
+/// into one `ReplayIteration`, each session introduced by a note.
+pub fn load_run(run_dir: &Path) -> Result<Vec<ReplayIteration>, RslphError> {
+    let paths = transcripts_in(run_dir)?;
+    if paths.is_empty() {
+        return Err(RslphError::Journal(format!(
+            "No transcripts found in {}",
+            run_dir.display()
+        )));
+    }
+
+    let mut iterations: Vec<ReplayIteration> = Vec::new();
+    let mut parser = AgentKind::default().parser();
+    let mut response = AgentResponse::new();
+    for path in paths {
+        let content = std::fs::read_to_string(&path)?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match TranscriptRecord::parse(line) {
+                Some(TranscriptRecord::Input {
+                    iteration,
+                    started_at,
+                    backend,
+                    model,
+                    ..
+                }) => {
+                    finish_session(iterations.last_mut(), &response);
+                    parser = backend.parser();
+                    response = AgentResponse::new();
+
+                    if iterations.last().map(|i| i.iteration) != Some(iteration) {
+                        iterations.push(ReplayIteration {
+                            iteration,
+                            model: model.clone(),
+                            lines: Vec::new(),
+                            progress: None,
+                        });
+                    }
+                    let current = iterations.last_mut().expect("pushed above");
+                    current.lines.push(ReplayLine::Session {
+                        backend,
+                        note: format!(
+                            "[REPLAY] Session started {} ({}, {}, model: {})",
+                            started_at,
+                            path.file_name().unwrap_or_default().to_string_lossy(),
+                            backend,
+                            model.as_deref().unwrap_or("default")
+                        ),
+                    });
+                }
+                Some(TranscriptRecord::Stderr { line }) => {
+                    push_line(&mut iterations, ReplayLine::Stderr(line));
+                }
+                Some(TranscriptRecord::Truncated { max_bytes }) => {
+                    push_line(
+                        &mut iterations,
+                        ReplayLine::Note(format!(
+                            "[REPLAY] Transcript truncated at {} bytes",
+                            max_bytes
+                        )),
+                    );
+                }
+                None => {
+                    if let Some(output) = parser.parse_line(line) {
+                        response.process(&output);
+                    }
+                    push_line(&mut iterations, ReplayLine::Stdout(line.to_string()));
+                }
+            }
+        }
+    }
+    finish_session(iterations.last_mut(), &response);
+
+    Ok(iterations)
+}
+
+/// Add a line to the current iteration (lines before any input record are dropped).
+fn push_line(iterations: &mut [ReplayIteration], line: ReplayLine) {
+    if let Some(current) = iterations.last_mut() {
+        current.lines.push(line);
+    }
+}
+
+/// Keep the progress file a session returned, in whichever format it used.
+fn finish_session(iteration: Option<&mut ReplayIteration>, response: &AgentResponse) {
+    let Some(iteration) = iteration else {
+        return;
+    };
+    let progress = [
+        ProgressFormat::Markdown,
+        ProgressFormat::Json,
+        ProgressFormat::Yaml,
+        ProgressFormat::Toml,
+    ]
+    .into_iter()
+    .filter_map(|format| ProgressFile::parse_response(&response.text, format).ok())
+    .find(|progress| progress.total_tasks() > 0);
+    if progress.is_some() {
+        iteration.progress = progress;
+    }
+}
+
+/// Replay a recorded run in the TUI.
+///
+/// # Arguments
+///
+/// * `run_dir` - Run directory holding `iter-NNN.jsonl` transcripts
+/// * `speed` - Initial playback speed (1.0 is normal)
+/// * `from` - Fast-forward to this iteration before pacing playback
+/// * `config` - Configuration (recent message count, pricing)
+pub async fn run_replay_command(
+    run_dir: PathBuf,
+    speed: f64,
+    from: Option<u32>,
+    config: &Config,
+) -> color_eyre::Result<()> {
+    let iterations = load_run(&run_dir)?;
+
+    let project_name = iterations
+        .iter()
+        .rev()
+        .find_map(|i| i.progress.as_ref())
+        .map(|p| p.name.clone())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| run_dir.display().to_string());
+    let model_name = iterations
+        .iter()
+        .find_map(|i| i.model.clone())
+        .unwrap_or_else(|| "Claude".to_string());
+    let last_iteration = iterations.last().map(|i| i.iteration).unwrap_or(0);
+
+    let playback = Playback {
+        speed: speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED),
+        skip_to: from.unwrap_or(0),
+        restarts: 0,
+    };
+
+    let mut app = App::new(last_iteration, model_name, project_name);
+    app.total_tasks = iterations
+        .iter()
+        .rev()
+        .find_map(|i| i.progress.as_ref())
+        .map(|p| p.total_tasks() as u32)
+        .unwrap_or(0);
+    app.log_path = Some(run_dir.clone());
+    app.playback = Some(playback);
+
+    let cancel_token = CancellationToken::new();
+    let (pause_tx, pause_rx) = watch::channel(false);
+    let (playback_tx, playback_rx) = watch::channel(playback);
+    let tui_tx = run_tui(
+        app,
+        config.tui_recent_messages,
+        cancel_token.clone(),
+        pause_tx,
+        Some(playback_tx),
+    )
+    .await?;
+
+    let pricing = PricingTable::from_config(config);
+    play(
+        &iterations,
+        &tui_tx,
+        pause_rx,
+        playback_rx,
+        &cancel_token,
+        &pricing,
+    )
+    .await;
+
+    // The TUI drops its receiver once it has restored the terminal
+    tui_tx.closed().await;
+    Ok(())
+}
+
+/// Send the recorded iterations to the TUI until the user quits.
+///
+/// A restart request (going back) clears the TUI and plays the recording
+/// again from the start, fast-forwarding to `skip_to`. Restarts are honored
+/// after the end of the recording too.
+async fn play(
+    iterations: &[ReplayIteration],
+    tui_tx: &mpsc::UnboundedSender<SubprocessEvent>,
+    mut pause_rx: watch::Receiver<bool>,
+    mut playback_rx: watch::Receiver<Playback>,
+    cancel_token: &CancellationToken,
+    pricing: &PricingTable,
+) {
+    loop {
+        let restarts = playback_rx.borrow_and_update().restarts;
+        let finished = play_once(
+            iterations,
+            tui_tx,
+            &mut pause_rx,
+            &mut playback_rx,
+            restarts,
+            cancel_token,
+            pricing,
+        )
+        .await;
+        if finished {
+            let _ = tui_tx.send(SubprocessEvent::Log(
+                "[REPLAY] End of recording - press b to go back or q to quit".to_string(),
+            ));
+            while playback_rx.borrow().restarts == restarts {
+                tokio::select! {
+                    changed = playback_rx.changed() => {
+                        if changed.is_err() {
+                            return;
+                        }
+                    }
+                    _ = cancel_token.cancelled() => return,
+                }
+            }
+        }
+        if cancel_token.is_cancelled() {
+            return;
+        }
+        let _ = tui_tx.send(SubprocessEvent::ReplayRestart);
+    }
+}
+
+/// Play the recording once, honoring pause and speed.
+///
+/// Returns false if playback was cancelled or restarted before the end.
+async fn play_once(
+    iterations: &[ReplayIteration],
+    tui_tx: &mpsc::UnboundedSender<SubprocessEvent>,
+    pause_rx: &mut watch::Receiver<bool>,
+    playback_rx: &mut watch::Receiver<Playback>,
+    restarts: u32,
+    cancel_token: &CancellationToken,
+    pricing: &PricingTable,
+) -> bool {
+    let mut tasks_done = 0;
+    let mut tasks_blocked = 0;
+    let mut parser: Box<dyn AgentBackend> = AgentKind::default().parser();
+    for recorded in iterations {
+        let _ = tui_tx.send(SubprocessEvent::IterationStart {
+            iteration: recorded.iteration,
+        });
+        let _ = tui_tx.send(SubprocessEvent::Log(format!(
+            "--- Iteration {} ---",
+            recorded.iteration
+        )));
+
+        for line in &recorded.lines {
+            if cancel_token.is_cancelled() || playback_rx.borrow().restarts != restarts {
+                return false;
+            }
+            match line {
+                ReplayLine::Session { backend, note } => {
+                    parser = backend.parser();
+                    let _ = tui_tx.send(SubprocessEvent::Log(note.clone()));
+                }
+                ReplayLine::Stdout(s) => {
+                    if let Some(output) = parser.parse_line(s) {
+                        stream_output(&output, tui_tx, pricing);
+                    }
+                }
+                ReplayLine::Stderr(s) => {
+                    let _ = tui_tx.send(SubprocessEvent::Stderr(s.clone()));
+                }
+                ReplayLine::Note(s) => {
+                    let _ = tui_tx.send(SubprocessEvent::Log(s.clone()));
+                }
+            }
+
+            if recorded.iteration < playback_rx.borrow().skip_to {
+                continue;
+            }
+            wait_while_paused(pause_rx, cancel_token).await;
+            let delay = LINE_DELAY.div_f64(playback_rx.borrow().speed);
+
+            // A speed change or skip takes effect immediately
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = playback_rx.changed() => {}
+                _ = cancel_token.cancelled() => {}
+            }
+        }
+
+        if let Some(ref progress) = recorded.progress {
+            tasks_done = progress.completed_tasks() as u32;
+            tasks_blocked = progress.blocked_tasks() as u32;
+        }
+        let _ = tui_tx.send(SubprocessEvent::IterationDone {
+            tasks_done,
+            tasks_blocked,
+        });
+    }
+    !cancel_token.is_cancelled()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::transcript::TranscriptArchive;
+    use tempfile::TempDir;
+
+    fn assistant_line(text: &str) -> String {
+        serde_json::json!({
+            "type": "assistant",
+            "message": {"content": [{"type": "text", "text": text}]}
+        })
+        .to_string()
+    }
+
+    fn progress(done: &str) -> String {
+        format!(
+            "# Progress: Replay\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### Phase 1\n\n- [{}] Task 1\n- [ ] Task 2\n",
+            done
+        )
+    }
+
+    #[test]
+    fn test_load_run_groups_sessions_by_iteration() {
+        let dir = TempDir::new().expect("temp dir");
+        let archive = TranscriptArchive::new(dir.path(), &Config::default()).expect("enabled");
+
+        // Iteration 1 timed out once, then finished Task 1
+        let mut first = archive
+            .start(1, AgentKind::Claude, None, "system", "input")
+            .expect("start");
+        first.stderr("timeout");
+        first.finish().expect("finish");
+        let mut retry = archive
+            .start(1, AgentKind::Claude, None, "system", "input")
+            .expect("start");
+        retry.stdout(&assistant_line(&progress("x")));
+        retry.finish().expect("finish");
+
+        // Iteration 2 ran a plain-text agent command
+        let mut second = archive
+            .start(2, AgentKind::Command, Some("gpt-5"), "system", "input")
+            .expect("start");
+        second.stdout("not json");
+        second.finish().expect("finish");
+
+        let iterations = load_run(dir.path()).expect("load");
+        assert_eq!(iterations.len(), 2);
+
+        let first = &iterations[0];
+        assert_eq!(first.iteration, 1);
+        assert_eq!(first.lines.len(), 4);
+        assert!(matches!(first.lines[0], ReplayLine::Session { .. }));
+        assert_eq!(first.lines[1], ReplayLine::Stderr("timeout".to_string()));
+        assert!(matches!(first.lines[2], ReplayLine::Session { .. }));
+        assert!(matches!(first.lines[3], ReplayLine::Stdout(_)));
+        let progress = first.progress.as_ref().expect("returned progress");
+        assert_eq!(progress.name, "Replay");
+        assert_eq!(progress.completed_tasks(), 1);
+
+        let second = &iterations[1];
+        assert_eq!(second.model.as_deref(), Some("gpt-5"));
+        assert!(matches!(
+            second.lines[0],
+            ReplayLine::Session {
+                backend: AgentKind::Command,
+                ..
+            }
+        ));
+        assert_eq!(second.lines[1], ReplayLine::Stdout("not json".to_string()));
+        assert!(second.progress.is_none());
+    }
+
+    /// Receive TUI events until the end-of-recording note.
+    async fn until_end(rx: &mut mpsc::UnboundedReceiver<SubprocessEvent>) -> Vec<SubprocessEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            if matches!(&event, SubprocessEvent::Log(s) if s.contains("End of recording")) {
+                return events;
+            }
+            events.push(event);
+        }
+        panic!("playback stopped before the end: {:?}", events);
+    }
+
+    #[tokio::test]
+    async fn test_play_restarts_after_going_back() {
+        let iterations: Vec<ReplayIteration> = (1..=2)
+            .map(|iteration| ReplayIteration {
+                iteration,
+                model: None,
+                lines: vec![ReplayLine::Note(format!("note {}", iteration))],
+                progress: None,
+            })
+            .collect();
+        let (tui_tx, mut tui_rx) = mpsc::unbounded_channel();
+        let (_pause_tx, pause_rx) = watch::channel(false);
+        let mut playback = Playback {
+            speed: MAX_PLAYBACK_SPEED,
+            skip_to: 3,
+            restarts: 0,
+        };
+        let (playback_tx, playback_rx) = watch::channel(playback);
+        let cancel_token = CancellationToken::new();
+
+        let player = {
+            let cancel_token = cancel_token.clone();
+            tokio::spawn(async move {
+                play(
+                    &iterations,
+                    &tui_tx,
+                    pause_rx,
+                    playback_rx,
+                    &cancel_token,
+                    &PricingTable::default(),
+                )
+                .await
+            })
+        };
+
+        let first = until_end(&mut tui_rx).await;
+        assert!(matches!(
+            first[0],
+            SubprocessEvent::IterationStart { iteration: 1 }
+        ));
+
+        // Going back after the end plays the recording again from a cleared TUI
+        playback.restarts += 1;
+        playback_tx.send(playback).expect("send");
+        let second = until_end(&mut tui_rx).await;
+        assert!(matches!(second[0], SubprocessEvent::ReplayRestart));
+        assert!(matches!(
+            second[1],
+            SubprocessEvent::IterationStart { iteration: 1 }
+        ));
+        assert_eq!(second.len(), first.len() + 1);
+
+        cancel_token.cancel();
+        player.await.expect("player");
+    }
+
+    #[test]
+    fn test_load_run_without_transcripts_fails() {
+        let dir = TempDir::new().expect("temp dir");
+        let err = load_run(dir.path()).expect_err("no transcripts");
+        assert!(err.to_string().contains("No transcripts found"));
+    }
+}
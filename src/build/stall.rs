@@ -0,0 +1,206 @@
+//! Stall detection for the build loop.
+//!
+//! A build is stalled when several iterations in a row check off no tasks, or
+//! when the latest Recent Attempts all tried the same thing. The configured
+//! policy then stops the build, blocks the stuck task, or escalates the next
+//! iterations to a different model with a nudge to change approach.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::progress::ProgressFile;
+
+/// What to do when the build stalls.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    ValueEnum,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum StallPolicy {
+    /// Stop the build with "Build stalled"
+    #[default]
+    Stop,
+    /// Mark the stuck task blocked (`[-]`) and move on to the next one
+    Skip,
+    /// Retry with `stall_model` and a change-of-approach note; stop if that stalls too
+    Escalate,
+}
+
+/// Tracks how long the build has gone without progress.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StallTracker {
+    /// Iterations (or failed attempts) that count as a stall. 0 disables detection.
+    pub threshold: u32,
+    /// Consecutive iterations that completed no tasks.
+    pub idle_iterations: u32,
+    /// Attempts from this iteration or earlier were seen before the last reset.
+    pub handled_through: u32,
+    /// The escalation policy is active for the following iterations.
+    pub escalated: bool,
+}
+
+impl StallTracker {
+    /// Create a tracker with the given threshold.
+    pub fn new(threshold: u32) -> Self {
+        Self {
+            threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Record the outcome of an iteration.
+    pub fn record(&mut self, tasks_completed: u32) {
+        if tasks_completed == 0 {
+            self.idle_iterations += 1;
+        } else {
+            self.idle_iterations = 0;
+        }
+    }
+
+    /// Start counting afresh after a stall was handled at `iteration`.
+    pub fn reset(&mut self, iteration: u32) {
+        self.idle_iterations = 0;
+        self.handled_through = iteration;
+    }
+
+    /// Check whether the build is stalled.
+    ///
+    /// # Returns
+    ///
+    /// A description of the stall, or None if the build is making progress.
+    pub fn check(&self, progress: &ProgressFile) -> Option<String> {
+        if self.threshold == 0 {
+            return None;
+        }
+
+        if self.idle_iterations >= self.threshold {
+            return Some(format!(
+                "{} consecutive iteration(s) completed no tasks",
+                self.idle_iterations
+            ));
+        }
+
+        // The same thing tried (and recorded as an attempt) in each of the
+        // last `threshold` iterations
+        let recent: Vec<_> = progress
+            .recent_attempts
+            .iter()
+            .filter(|a| a.iteration > self.handled_through)
+            .collect();
+        let window = self.threshold as usize;
+        if recent.len() < window {
+            return None;
+        }
+        let window = &recent[recent.len() - window..];
+        let tried = window[0].tried.trim();
+        let same_tried = window
+            .iter()
+            .all(|a| a.tried.trim().eq_ignore_ascii_case(tried));
+        let distinct_iterations = window.windows(2).all(|w| w[0].iteration != w[1].iteration);
+
+        if same_tried && distinct_iterations {
+            Some(format!(
+                "\"{}\" failed {} time(s) in a row",
+                tried,
+                window.len()
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress_with_attempts(attempts: &[(u32, &str)]) -> ProgressFile {
+        let mut progress = ProgressFile::default();
+        for (iteration, tried) in attempts {
+            progress.add_attempt(*iteration, tried, "Failed", None);
+        }
+        progress
+    }
+
+    #[test]
+    fn test_idle_iterations_trigger_stall() {
+        let progress = ProgressFile::default();
+        let mut tracker = StallTracker::new(2);
+
+        tracker.record(0);
+        assert!(tracker.check(&progress).is_none());
+        tracker.record(0);
+        let stall = tracker.check(&progress).expect("should stall");
+        assert!(stall.contains("2 consecutive"), "got: {}", stall);
+
+        tracker.record(1);
+        assert!(tracker.check(&progress).is_none());
+    }
+
+    #[test]
+    fn test_repeated_attempt_triggers_stall() {
+        let tracker = StallTracker::new(3);
+
+        let progress = progress_with_attempts(&[
+            (1, "Run migrations"),
+            (2, "run migrations"),
+            (3, "Run migrations"),
+        ]);
+        let stall = tracker.check(&progress).expect("should stall");
+        assert!(
+            stall.contains("\"Run migrations\" failed 3"),
+            "got: {}",
+            stall
+        );
+
+        let progress = progress_with_attempts(&[
+            (1, "Run migrations"),
+            (2, "Fix schema"),
+            (3, "Run migrations"),
+        ]);
+        assert!(tracker.check(&progress).is_none());
+    }
+
+    #[test]
+    fn test_attempts_in_one_iteration_do_not_stall() {
+        let tracker = StallTracker::new(2);
+        let progress = progress_with_attempts(&[(4, "Build"), (4, "Build")]);
+        assert!(tracker.check(&progress).is_none());
+    }
+
+    #[test]
+    fn test_reset_ignores_handled_attempts() {
+        let mut tracker = StallTracker::new(2);
+        tracker.record(0);
+        tracker.record(0);
+        let progress = progress_with_attempts(&[(1, "Build"), (2, "Build")]);
+        assert!(tracker.check(&progress).is_some());
+
+        tracker.reset(2);
+        assert!(tracker.check(&progress).is_none());
+
+        let progress = progress_with_attempts(&[(1, "Build"), (2, "Build"), (3, "Build")]);
+        assert!(tracker.check(&progress).is_none());
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_detection() {
+        let mut tracker = StallTracker::new(0);
+        tracker.record(0);
+        tracker.record(0);
+        let progress = progress_with_attempts(&[(1, "Build"), (2, "Build")]);
+        assert!(tracker.check(&progress).is_none());
+    }
+}
@@ -2,8 +2,9 @@
 //!
 //! Provides state enum, done reason, iteration result, and build context.
 
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 
 use crate::config::Config;
@@ -12,10 +13,17 @@ use crate::prompts::PromptMode;
 use crate::tui::SubprocessEvent;
 use crate::vcs::{create_vcs, Vcs};
 
+use super::budget::Budget;
+use super::escalation::TaskAttempts;
+use super::events::{BuildEvent, EventSink};
+use super::journal::BuildJournal;
+use super::stall::StallTracker;
 use super::tokens::{IterationTokens, TokenUsage};
+use super::transcript::TranscriptArchive;
 
 /// Build loop states for the state machine.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum BuildState {
     /// Initial state, about to start first iteration.
     Starting,
@@ -37,10 +45,13 @@ pub enum BuildState {
 }
 
 /// Reason for build loop termination.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DoneReason {
     /// All tasks in progress file are marked complete.
     AllTasksComplete,
+    /// Every task is complete except those marked blocked.
+    CompleteWithBlocked,
     /// RALPH_DONE marker detected in status.
     RalphDoneMarker,
     /// Maximum iterations reached.
@@ -49,16 +60,23 @@ pub enum DoneReason {
     UserCancelled,
     /// Single iteration mode (--once flag).
     SingleIterationComplete,
+    /// A token or spend limit was reached.
+    BudgetExhausted,
+    /// No progress was being made (see `stall_policy`).
+    Stalled,
 }
 
 impl std::fmt::Display for DoneReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DoneReason::AllTasksComplete => write!(f, "All tasks complete"),
+            DoneReason::CompleteWithBlocked => write!(f, "All unblocked tasks complete"),
             DoneReason::RalphDoneMarker => write!(f, "RALPH_DONE marker detected"),
             DoneReason::MaxIterationsReached => write!(f, "Maximum iterations reached"),
             DoneReason::UserCancelled => write!(f, "Cancelled by user"),
             DoneReason::SingleIterationComplete => write!(f, "Single iteration complete (--once)"),
+            DoneReason::BudgetExhausted => write!(f, "Budget exhausted"),
+            DoneReason::Stalled => write!(f, "Build stalled"),
         }
     }
 }
@@ -125,6 +143,20 @@ pub struct BuildContext {
     pub current_iteration_tokens: TokenUsage,
     /// Number of timeout retries for the current iteration.
     pub timeout_retry_count: u32,
+    /// Pause flag published by the TUI (None when running headless).
+    pub pause_rx: Option<watch::Receiver<bool>>,
+    /// Crash-safe journal for resuming (None for dry runs and tests).
+    pub journal: Option<BuildJournal>,
+    /// Machine-readable event stream (`--events`), if enabled.
+    pub events: Option<EventSink>,
+    /// Where iteration transcripts are archived (None when disabled).
+    pub transcripts: Option<TranscriptArchive>,
+    /// Task assigned by a parallel build (None means the next incomplete task).
+    pub focus_task: Option<String>,
+    /// Stall detection state (idle iterations, escalation).
+    pub stall: StallTracker,
+    /// Iterations spent on each task, for the build model escalation ladder.
+    pub task_attempts: TaskAttempts,
 }
 
 impl BuildContext {
@@ -167,6 +199,7 @@ impl BuildContext {
         tui_tx: Option<mpsc::UnboundedSender<SubprocessEvent>>,
     ) -> Self {
         let max_iterations = config.max_iterations;
+        let stall = StallTracker::new(config.stall_threshold);
 
         // Detect and create VCS for auto-commit
         // Handle both None parent and empty parent (when path is just filename)
@@ -203,6 +236,13 @@ impl BuildContext {
             total_tokens: TokenUsage::default(),
             current_iteration_tokens: TokenUsage::default(),
             timeout_retry_count: 0,
+            pause_rx: None,
+            journal: None,
+            events: None,
+            transcripts: None,
+            focus_task: None,
+            stall,
+            task_attempts: TaskAttempts::default(),
         };
 
         // Log initialization info
@@ -218,6 +258,42 @@ impl BuildContext {
         ctx
     }
 
+    /// Persist a state transition to the build journal, if one is active.
+    ///
+    /// Journal write failures are logged but never stop the build.
+    pub fn record_state(&mut self, state: &BuildState) {
+        let completed = self.progress.completed_tasks();
+        let Some(journal) = self.journal.as_mut() else {
+            return;
+        };
+
+        journal.current_iteration = self.current_iteration;
+        journal.timeout_retry_count = self.timeout_retry_count;
+        journal.iteration_tokens = self.iteration_tokens.clone();
+        journal.total_tokens = self.total_tokens.clone();
+        journal.task_attempts = self.task_attempts.clone();
+        journal.stall = self.stall.clone();
+        journal.record_state(state, completed);
+
+        if let Err(e) = journal.save() {
+            self.log(&format!("[JOURNAL] Failed to write build journal: {}", e));
+        }
+    }
+
+    /// Check the configured token and spend limits.
+    ///
+    /// Returns a description of the first limit crossed, or None if within budget.
+    pub fn budget_exceeded(&self) -> Option<String> {
+        Budget::from_config(&self.config).exceeded(&self.total_tokens, self.iteration_tokens.last())
+    }
+
+    /// Write an event to the `--events` stream, if enabled.
+    pub fn emit(&self, event: BuildEvent) {
+        if let Some(ref events) = self.events {
+            events.emit(event);
+        }
+    }
+
     /// Log a message to TUI or stderr depending on mode.
     pub fn log(&self, msg: &str) {
         if let Some(ref tx) = self.tui_tx {
@@ -287,6 +363,11 @@ mod tests {
             DoneReason::SingleIterationComplete.to_string(),
             "Single iteration complete (--once)"
         );
+        assert_eq!(DoneReason::Stalled.to_string(), "Build stalled");
+        assert_eq!(
+            DoneReason::CompleteWithBlocked.to_string(),
+            "All unblocked tasks complete"
+        );
     }
 
     #[test]
@@ -0,0 +1,389 @@
+//! Summary of a progress file (`rslph status`).
+//!
+//! Reads the file with `ProgressFile::load` and reports where the build is
+//! without spawning an agent: per-phase completion, the next ready task,
+//! blocked tasks, recent attempts, the tail of the iteration log and the
+//! total time logged.
+
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::error::RslphError;
+use crate::progress::{Attempt, IterationEntry, ProgressFile};
+
+/// Width of the per-phase completion bars, in characters.
+const BAR_WIDTH: usize = 20;
+
+/// Where a build stands, as printed by `rslph status`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusReport {
+    pub name: String,
+    pub status: String,
+    pub done: bool,
+    pub total_tasks: usize,
+    pub completed_tasks: usize,
+    pub blocked_tasks: usize,
+    pub remaining_tasks: usize,
+    pub phases: Vec<PhaseStatus>,
+    pub next_task: Option<TaskRef>,
+    pub blocked: Vec<TaskRef>,
+    pub recent_attempts: Vec<Attempt>,
+    /// The last rows of the iteration log, oldest first
+    pub iteration_log: Vec<IterationEntry>,
+    /// Iterations in the whole log
+    pub iterations: usize,
+    /// Sum of the logged iteration durations that could be read
+    pub total_duration_secs: u64,
+}
+
+/// Task counts of one phase.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseStatus {
+    pub name: String,
+    pub total: usize,
+    pub completed: usize,
+    pub blocked: usize,
+}
+
+/// A task and the phase it belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskRef {
+    pub phase: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub description: String,
+    /// Blocked reason, for blocked tasks that gave one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl StatusReport {
+    /// Summarize `progress`, keeping the last `log_rows` iteration log rows.
+    pub fn new(progress: &ProgressFile, log_rows: usize) -> Self {
+        let phases = progress
+            .tasks
+            .iter()
+            .map(|phase| PhaseStatus {
+                name: phase.name.clone(),
+                total: phase.tasks.len(),
+                completed: phase.tasks.iter().filter(|t| t.completed).count(),
+                blocked: phase.tasks.iter().filter(|t| t.is_blocked()).count(),
+            })
+            .collect();
+        let blocked = progress
+            .tasks
+            .iter()
+            .flat_map(|phase| phase.tasks.iter().map(move |task| (phase, task)))
+            .filter(|(_, task)| task.is_blocked())
+            .map(|(phase, task)| TaskRef {
+                phase: phase.name.clone(),
+                id: task.id.clone(),
+                description: task.description.clone(),
+                reason: task.blocked.clone().filter(|r| !r.is_empty()),
+            })
+            .collect();
+        let next_task = progress.next_task().map(|(phase, task)| TaskRef {
+            phase: phase.to_string(),
+            id: task.id.clone(),
+            description: task.description.clone(),
+            reason: None,
+        });
+        let log = &progress.iteration_log;
+
+        Self {
+            name: progress.name.clone(),
+            status: progress.status.clone(),
+            done: progress.is_done(),
+            total_tasks: progress.total_tasks(),
+            completed_tasks: progress.completed_tasks(),
+            blocked_tasks: progress.blocked_tasks(),
+            remaining_tasks: progress.remaining_tasks(),
+            phases,
+            next_task,
+            blocked,
+            recent_attempts: progress.recent_attempts.clone(),
+            iteration_log: log[log.len().saturating_sub(log_rows)..].to_vec(),
+            iterations: log.len(),
+            total_duration_secs: log
+                .iter()
+                .filter_map(|entry| parse_duration(&entry.duration))
+                .map(|d| d.as_secs())
+                .sum(),
+        }
+    }
+
+    /// Human-readable report.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("Plan: {}\nStatus: {}\n", self.name, self.status);
+        out.push_str(&format!(
+            "Tasks: {}/{} complete",
+            self.completed_tasks, self.total_tasks
+        ));
+        if self.blocked_tasks > 0 {
+            out.push_str(&format!(" ({} blocked)", self.blocked_tasks));
+        }
+        out.push('\n');
+
+        if !self.phases.is_empty() {
+            out.push_str("\nPhases:\n");
+            let width = self.phases.iter().map(|p| p.name.len()).max().unwrap_or(0);
+            for phase in &self.phases {
+                out.push_str(&format!(
+                    "  {:<width$}  {} {}/{}",
+                    phase.name,
+                    bar(phase.completed, phase.total),
+                    phase.completed,
+                    phase.total,
+                    width = width
+                ));
+                if phase.blocked > 0 {
+                    out.push_str(&format!(" ({} blocked)", phase.blocked));
+                }
+                out.push('\n');
+            }
+        }
+
+        out.push('\n');
+        match &self.next_task {
+            Some(task) => out.push_str(&format!("Next task: {}\n", task_label(task))),
+            None if self.done => out.push_str("Next task: none (plan is done)\n"),
+            None => out.push_str("Next task: none ready\n"),
+        }
+
+        if !self.blocked.is_empty() {
+            out.push_str("\nBlocked:\n");
+            for task in &self.blocked {
+                out.push_str(&format!("  - {}", task_label(task)));
+                if let Some(reason) = &task.reason {
+                    out.push_str(&format!(" ({})", reason));
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.recent_attempts.is_empty() {
+            out.push_str("\nRecent attempts:\n");
+            for attempt in &self.recent_attempts {
+                out.push_str(&format!(
+                    "  - Iteration {}: {} -> {}\n",
+                    attempt.iteration, attempt.tried, attempt.result
+                ));
+            }
+        }
+
+        if !self.iteration_log.is_empty() {
+            out.push_str(&format!(
+                "\nIteration log (last {} of {}):\n",
+                self.iteration_log.len(),
+                self.iterations
+            ));
+            for entry in &self.iteration_log {
+                out.push_str(&format!(
+                    "  {:>3}  {}  {}  {} task(s)  {}\n",
+                    entry.iteration,
+                    entry.started,
+                    entry.duration,
+                    entry.tasks_completed,
+                    entry.notes
+                ));
+            }
+        }
+
+        out.push_str(&format!(
+            "\nTotal duration: {} over {} iteration(s)\n",
+            format_duration(self.total_duration_secs),
+            self.iterations
+        ));
+        out
+    }
+}
+
+/// Print the status of the progress file at `path`, as text or JSON.
+pub fn run_status_command(path: &Path, log_rows: usize, json: bool) -> Result<(), RslphError> {
+    let progress = ProgressFile::load(path)?;
+    let report = StatusReport::new(&progress, log_rows);
+    if json {
+        let text = serde_json::to_string_pretty(&report)
+            .map_err(|e| RslphError::ProgressParse(format!("Cannot serialize status: {}", e)))?;
+        println!("{}", text);
+    } else {
+        print!("{}", report.to_text());
+    }
+    Ok(())
+}
+
+/// `[####----]` bar for `done` of `total`.
+fn bar(done: usize, total: usize) -> String {
+    let filled = (done * BAR_WIDTH).checked_div(total).unwrap_or(0);
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(BAR_WIDTH - filled))
+}
+
+/// "Phase: T3 description" label for a task.
+fn task_label(task: &TaskRef) -> String {
+    match &task.id {
+        Some(id) => format!("{}: {}: {}", task.phase, id, task.description),
+        None => format!("{}: {}", task.phase, task.description),
+    }
+}
+
+/// Format seconds the way the iteration log does ("3m 5s", "1h 2m 0s").
+fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {}m {}s", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    }
+}
+
+/// Read an iteration log duration such as "2m 5s", "45s" or "1h 3m".
+///
+/// Returns `None` for placeholders like "~" and anything else unrecognized.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut secs = 0;
+    let mut parts = 0;
+    for part in text.split_whitespace() {
+        let unit_at = part.find(|c: char| !c.is_ascii_digit())?;
+        let value: u64 = part[..unit_at].parse().ok()?;
+        secs += value
+            * match &part[unit_at..] {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+        parts += 1;
+    }
+    (parts > 0).then(|| Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRESS: &str = r#"# Progress: Todo App
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1: Setup
+
+- [x] T1: Create project
+- [x] T2: Add dependencies (after T1)
+
+### Phase 2: Core
+
+- [x] T3: Add model (after T2)
+- [ ] T4: Add storage (after T3)
+- [-] T5: Deploy (blocked: no credentials)
+- [ ] T6: Write docs
+
+## Recent Attempts
+
+### Iteration 3
+
+- Tried: Run tests
+- Result: Two failures
+- Next: Fix storage
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+| 1 | 2026-01-01 10:00 | 2m 5s | 2 | Setup |
+| 2 | 2026-01-01 10:03 | 1m 0s | 1 | Model |
+| 3 | 2026-01-01 10:05 | ~ | 0 | No tasks completed |
+"#;
+
+    #[test]
+    fn test_report_counts_phases_and_tasks() {
+        let progress = ProgressFile::parse(PROGRESS).expect("parse");
+        let report = StatusReport::new(&progress, 2);
+
+        assert_eq!(report.name, "Todo App");
+        assert!(!report.done);
+        assert_eq!(
+            (
+                report.total_tasks,
+                report.completed_tasks,
+                report.blocked_tasks,
+                report.remaining_tasks
+            ),
+            (6, 3, 1, 2)
+        );
+        assert_eq!(report.phases[1].completed, 1);
+        assert_eq!(report.phases[1].blocked, 1);
+        let next = report.next_task.as_ref().expect("next task");
+        assert_eq!(next.id.as_deref(), Some("T4"));
+        assert_eq!(report.blocked[0].reason.as_deref(), Some("no credentials"));
+        assert_eq!(report.recent_attempts.len(), 1);
+        assert_eq!(
+            report
+                .iteration_log
+                .iter()
+                .map(|e| e.iteration)
+                .collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(report.iterations, 3);
+        assert_eq!(report.total_duration_secs, 185);
+    }
+
+    #[test]
+    fn test_text_report() {
+        let progress = ProgressFile::parse(PROGRESS).expect("parse");
+        let text = StatusReport::new(&progress, 1).to_text();
+
+        assert!(
+            text.contains("Tasks: 3/6 complete (1 blocked)\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("  Phase 1: Setup  [####################] 2/2\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("  Phase 2: Core   [#####---------------] 1/4 (1 blocked)\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("Next task: Phase 2: Core: T4: Add storage\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("  - Phase 2: Core: T5: Deploy (no credentials)\n"),
+            "{}",
+            text
+        );
+        assert!(
+            text.contains("  - Iteration 3: Run tests -> Two failures\n"),
+            "{}",
+            text
+        );
+        assert!(text.contains("Iteration log (last 1 of 3):\n"), "{}", text);
+        assert!(
+            text.contains("Total duration: 3m 5s over 3 iteration(s)\n"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2m 5s"), Some(Duration::from_secs(125)));
+        assert_eq!(parse_duration("1h 3m"), Some(Duration::from_secs(3780)));
+        assert_eq!(parse_duration("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("~"), None);
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("5 minutes"), None);
+        assert_eq!(format_duration(3725), "1h 2m 5s");
+    }
+}
@@ -0,0 +1,673 @@
+//! Editing a plan's tasks from the command line (`rslph task`).
+//!
+//! Tasks are addressed by position, `PHASE.INDEX` counted from 1 (`2.1` is
+//! the first task of the second phase), or by ID (`T3`). Every edit loads
+//! the progress file, checks the result with the same dependency rules as
+//! parsing, and writes it back atomically with `ProgressFile::write`.
+//!
+//! The build reloads the progress file at the start of each iteration, so
+//! edits made between iterations are picked up. An edit made while an
+//! iteration runs is kept: the build applies the iteration's changes on top
+//! of the file on disk with `ProgressFile::rebase`.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::RslphError;
+use crate::progress::{is_task_id, ProgressFile, Task, TaskPhase};
+
+/// A task, by position or by ID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskAddress {
+    /// Phase and task number, both counted from 1
+    Position {
+        phase: usize,
+        index: usize,
+    },
+    Id(String),
+}
+
+impl FromStr for TaskAddress {
+    type Err = RslphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((phase, index)) = s.split_once('.') {
+            if let (Ok(phase), Ok(index)) = (phase.parse(), index.parse()) {
+                if phase > 0 && index > 0 {
+                    return Ok(TaskAddress::Position { phase, index });
+                }
+            }
+        }
+        if is_task_id(s) {
+            return Ok(TaskAddress::Id(s.to_string()));
+        }
+        Err(RslphError::Task(format!(
+            "Invalid task reference \"{}\": use PHASE.INDEX (e.g. 2.1) or a task ID (e.g. T3)",
+            s
+        )))
+    }
+}
+
+impl fmt::Display for TaskAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskAddress::Position { phase, index } => write!(f, "{}.{}", phase, index),
+            TaskAddress::Id(id) => write!(f, "{}", id),
+        }
+    }
+}
+
+/// A change to a plan's tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskEdit {
+    /// Append a task, written like a checklist line without the checkbox
+    /// (`T7: Add login (after T3; priority: high)`), to a phase given by
+    /// number or name; the last phase when `None`. A name that matches no
+    /// phase adds a new one.
+    Add {
+        text: String,
+        phase: Option<String>,
+    },
+    Done(TaskAddress),
+    Undo(TaskAddress),
+    /// Move a task to `PHASE.INDEX`, or to the end of a phase given by
+    /// number or name
+    Move {
+        task: TaskAddress,
+        to: String,
+    },
+    /// Remove a task; `force` also drops it from other tasks' dependencies
+    Remove {
+        task: TaskAddress,
+        force: bool,
+    },
+}
+
+/// Apply `edit` to the progress file at `path` and return what changed.
+pub fn run_task_edit(path: &Path, edit: TaskEdit) -> Result<String, RslphError> {
+    let original = ProgressFile::load(path)?;
+    let mut progress = original.clone();
+    let message = apply_edit(&mut progress, edit)?;
+    if progress == original {
+        return Ok(message);
+    }
+    progress.validate_dependencies().map_err(|e| match e {
+        RslphError::ProgressParse(msg) => RslphError::Task(msg),
+        e => e,
+    })?;
+    progress.write(path)?;
+    Ok(message)
+}
+
+/// Apply `edit` in memory and describe the result.
+pub fn apply_edit(progress: &mut ProgressFile, edit: TaskEdit) -> Result<String, RslphError> {
+    let message = match edit {
+        TaskEdit::Add { text, phase } => {
+            let task = Task::from_markdown(&text, false, false, None);
+            if task.description.is_empty() {
+                return Err(RslphError::Task("Task description is empty".to_string()));
+            }
+            let (phase, created) = match phase {
+                Some(selector) => match find_phase(progress, &selector) {
+                    Ok(phase) => (phase, false),
+                    Err(_)
+                        if selector.parse::<usize>().is_err()
+                            && phase_matches(progress, &selector).is_empty() =>
+                    {
+                        progress.tasks.push(TaskPhase {
+                            name: selector,
+                            ..Default::default()
+                        });
+                        (progress.tasks.len() - 1, true)
+                    }
+                    Err(e) => return Err(e),
+                },
+                None if progress.tasks.is_empty() => {
+                    return Err(RslphError::Task(
+                        "The plan has no phases; name one with --phase".to_string(),
+                    ));
+                }
+                None => (progress.tasks.len() - 1, false),
+            };
+            let tasks = &mut progress.tasks[phase].tasks;
+            tasks.push(task);
+            let mut message = format!(
+                "Added {}",
+                label(phase, tasks.len() - 1, &tasks[tasks.len() - 1])
+            );
+            if created {
+                message.push_str(&format!(" in new phase \"{}\"", progress.tasks[phase].name));
+            }
+            message.push_str(&reopen(progress));
+            message
+        }
+        TaskEdit::Done(address) => {
+            let (phase, index) = resolve(progress, &address)?;
+            let task = &mut progress.tasks[phase].tasks[index];
+            if task.completed {
+                return Ok(format!("{} is already complete", label(phase, index, task)));
+            }
+            task.completed = true;
+            task.blocked = None;
+            format!("Completed {}", label(phase, index, task))
+        }
+        TaskEdit::Undo(address) => {
+            let (phase, index) = resolve(progress, &address)?;
+            let task = &mut progress.tasks[phase].tasks[index];
+            if !task.completed {
+                return Ok(format!("{} is not complete", label(phase, index, task)));
+            }
+            task.completed = false;
+            let mut message = format!("Reopened {}", label(phase, index, task));
+            message.push_str(&reopen(progress));
+            message
+        }
+        TaskEdit::Move { task, to } => {
+            let (from_phase, from_index) = resolve(progress, &task)?;
+            let (to_phase, to_index) = destination(progress, &to, from_phase)?;
+            if (to_phase, to_index) == (from_phase, from_index) {
+                return Ok(format!(
+                    "{} is already at {}.{}",
+                    task,
+                    to_phase + 1,
+                    to_index + 1
+                ));
+            }
+            let moved = progress.tasks[from_phase].tasks.remove(from_index);
+            let tasks = &mut progress.tasks[to_phase].tasks;
+            let to_index = to_index.min(tasks.len());
+            tasks.insert(to_index, moved);
+            format!("Moved {} to {}.{}", task, to_phase + 1, to_index + 1)
+        }
+        TaskEdit::Remove { task, force } => {
+            let (phase, index) = resolve(progress, &task)?;
+            if let Some(id) = progress.tasks[phase].tasks[index].id.clone() {
+                let dependents: Vec<&mut Task> = progress
+                    .tasks
+                    .iter_mut()
+                    .flat_map(|p| &mut p.tasks)
+                    .filter(|t| t.depends_on.contains(&id))
+                    .collect();
+                if !dependents.is_empty() && !force {
+                    let names: Vec<String> = dependents
+                        .iter()
+                        .map(|t| {
+                            t.id.clone()
+                                .unwrap_or_else(|| format!("\"{}\"", t.description))
+                        })
+                        .collect();
+                    return Err(RslphError::Task(format!(
+                        "{} is a dependency of {}; use --force to remove it and drop those dependencies",
+                        id,
+                        names.join(", ")
+                    )));
+                }
+                for dependent in dependents {
+                    dependent.depends_on.retain(|dep| dep != &id);
+                }
+            }
+            let removed = progress.tasks[phase].tasks.remove(index);
+            format!("Removed {}", label(phase, index, &removed))
+        }
+    };
+    Ok(message)
+}
+
+/// The plan's tasks, grouped by phase, each with its `PHASE.INDEX`.
+pub fn format_task_list(progress: &ProgressFile) -> String {
+    if progress.tasks.is_empty() {
+        return "No tasks\n".to_string();
+    }
+    let mut out = String::new();
+    for (p, phase) in progress.tasks.iter().enumerate() {
+        if p > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("{}. {}\n", p + 1, phase.name));
+        for (i, task) in phase.tasks.iter().enumerate() {
+            out.push_str(&format!(
+                "  {:<6}{}\n",
+                format!("{}.{}", p + 1, i + 1),
+                task.to_markdown()
+            ));
+        }
+    }
+    out
+}
+
+/// Zero-based phase and task index of `address`.
+fn resolve(progress: &ProgressFile, address: &TaskAddress) -> Result<(usize, usize), RslphError> {
+    match address {
+        TaskAddress::Position { phase, index } => {
+            let tasks = &phase_at(progress, *phase)?.tasks;
+            if *index > tasks.len() {
+                return Err(RslphError::Task(format!(
+                    "No task {}: phase {} \"{}\" has {} task(s)",
+                    address,
+                    phase,
+                    progress.tasks[phase - 1].name,
+                    tasks.len()
+                )));
+            }
+            Ok((phase - 1, index - 1))
+        }
+        TaskAddress::Id(id) => {
+            for (p, phase) in progress.tasks.iter().enumerate() {
+                if let Some(i) = phase.tasks.iter().position(|t| t.id.as_ref() == Some(id)) {
+                    return Ok((p, i));
+                }
+            }
+            let ids: Vec<&str> = progress
+                .tasks
+                .iter()
+                .flat_map(|p| &p.tasks)
+                .filter_map(|t| t.id.as_deref())
+                .collect();
+            Err(RslphError::Task(if ids.is_empty() {
+                format!("No task with ID {}: no task has an ID, use PHASE.INDEX", id)
+            } else {
+                format!("No task with ID {} (IDs: {})", id, ids.join(", "))
+            }))
+        }
+    }
+}
+
+/// Phase `number`, counted from 1.
+fn phase_at(progress: &ProgressFile, number: usize) -> Result<&TaskPhase, RslphError> {
+    number
+        .checked_sub(1)
+        .and_then(|i| progress.tasks.get(i))
+        .ok_or_else(|| {
+            RslphError::Task(format!(
+                "No phase {}: the plan has {} phase(s)",
+                number,
+                progress.tasks.len()
+            ))
+        })
+}
+
+/// Zero-based index of the phase given by number or name.
+///
+/// A name matches case-insensitively, either the whole phase name or, when
+/// that is unique, part of it ("core" for "Phase 2: Core").
+fn find_phase(progress: &ProgressFile, selector: &str) -> Result<usize, RslphError> {
+    if let Ok(number) = selector.parse::<usize>() {
+        phase_at(progress, number)?;
+        return Ok(number - 1);
+    }
+    let describe = |phases: &mut dyn Iterator<Item = usize>| {
+        phases
+            .map(|i| format!("{} \"{}\"", i + 1, progress.tasks[i].name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match phase_matches(progress, selector).as_slice() {
+        [phase] => Ok(*phase),
+        [] => Err(RslphError::Task(format!(
+            "No phase named \"{}\" (phases: {})",
+            selector,
+            describe(&mut (0..progress.tasks.len()))
+        ))),
+        several => Err(RslphError::Task(format!(
+            "Phase \"{}\" is ambiguous: it matches {}",
+            selector,
+            describe(&mut several.iter().copied())
+        ))),
+    }
+}
+
+/// Phases whose name is `selector`, or else contains it.
+fn phase_matches(progress: &ProgressFile, selector: &str) -> Vec<usize> {
+    let selector = selector.trim().to_lowercase();
+    let names: Vec<String> = progress
+        .tasks
+        .iter()
+        .map(|p| p.name.to_lowercase())
+        .collect();
+    if let Some(exact) = names.iter().position(|name| *name == selector) {
+        return vec![exact];
+    }
+    (0..names.len())
+        .filter(|&i| names[i].contains(&selector))
+        .collect()
+}
+
+/// Zero-based phase and insert index for a move to `to`, once the task has
+/// been taken out of `from_phase`.
+fn destination(
+    progress: &ProgressFile,
+    to: &str,
+    from_phase: usize,
+) -> Result<(usize, usize), RslphError> {
+    let len_after =
+        |phase: usize| progress.tasks[phase].tasks.len() - usize::from(phase == from_phase);
+    if let Ok(TaskAddress::Position { phase, index }) = to.parse() {
+        phase_at(progress, phase)?;
+        let max = len_after(phase - 1) + 1;
+        if index > max {
+            return Err(RslphError::Task(format!(
+                "Cannot move to {}: positions in phase {} go up to {}",
+                to, phase, max
+            )));
+        }
+        return Ok((phase - 1, index - 1));
+    }
+    let phase = find_phase(progress, to)?;
+    Ok((phase, len_after(phase)))
+}
+
+/// `PHASE.INDEX` and checklist text of a task, for messages.
+fn label(phase: usize, index: usize, task: &Task) -> String {
+    match &task.id {
+        Some(id) => format!("{}.{} {}: {}", phase + 1, index + 1, id, task.description),
+        None => format!("{}.{} {}", phase + 1, index + 1, task.description),
+    }
+}
+
+/// Set a finished plan back in progress now that it has open work.
+fn reopen(progress: &mut ProgressFile) -> String {
+    if progress.is_done() {
+        progress.status = "In Progress".to_string();
+        " (plan status set back to In Progress)".to_string()
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRESS: &str = r#"# Progress: Todo App
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1: Setup
+
+- [x] T1: Create project
+- [ ] T2: Add dependencies (after T1)
+
+### Phase 2: Core
+
+- [ ] T3: Add model (after T2)
+- [ ] Write docs
+"#;
+
+    fn progress() -> ProgressFile {
+        ProgressFile::parse(PROGRESS).expect("parse")
+    }
+
+    fn edit(progress: &mut ProgressFile, edit: TaskEdit) -> String {
+        apply_edit(progress, edit).expect("edit")
+    }
+
+    fn address(s: &str) -> TaskAddress {
+        s.parse().expect("address")
+    }
+
+    fn descriptions(progress: &ProgressFile, phase: usize) -> Vec<&str> {
+        progress.tasks[phase]
+            .tasks
+            .iter()
+            .map(|t| t.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_task_address() {
+        assert_eq!(address("2.1"), TaskAddress::Position { phase: 2, index: 1 });
+        assert_eq!(address("T3"), TaskAddress::Id("T3".to_string()));
+        assert_eq!(address("API-2"), TaskAddress::Id("API-2".to_string()));
+        for bad in ["0.1", "2.0", "2", "docs", ""] {
+            let err = bad.parse::<TaskAddress>().unwrap_err().to_string();
+            assert!(err.contains("PHASE.INDEX"), "{}: {}", bad, err);
+        }
+    }
+
+    #[test]
+    fn test_add_task_with_annotations() {
+        let mut pf = progress();
+        let message = edit(
+            &mut pf,
+            TaskEdit::Add {
+                text: "T4: Add storage (after T3; priority: high)".to_string(),
+                phase: Some("core".to_string()),
+            },
+        );
+        assert_eq!(message, "Added 2.3 T4: Add storage");
+        let task = &pf.tasks[1].tasks[2];
+        assert_eq!(task.depends_on, vec!["T3"]);
+        assert!(!task.completed);
+
+        edit(
+            &mut pf,
+            TaskEdit::Add {
+                text: "Polish".to_string(),
+                phase: None,
+            },
+        );
+        assert_eq!(descriptions(&pf, 1).last(), Some(&"Polish"));
+
+        let message = edit(
+            &mut pf,
+            TaskEdit::Add {
+                text: "Deploy".to_string(),
+                phase: Some("Phase 3: Ship".to_string()),
+            },
+        );
+        assert_eq!(message, "Added 3.1 Deploy in new phase \"Phase 3: Ship\"");
+
+        let err = apply_edit(
+            &mut pf,
+            TaskEdit::Add {
+                text: "Nope".to_string(),
+                phase: Some("7".to_string()),
+            },
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No phase 7: the plan has 3 phase(s)"));
+
+        let err = apply_edit(
+            &mut pf,
+            TaskEdit::Add {
+                text: "Nope".to_string(),
+                phase: Some("phase".to_string()),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Task error: Phase \"phase\" is ambiguous: it matches 1 \"Phase 1: Setup\", \
+             2 \"Phase 2: Core\", 3 \"Phase 3: Ship\""
+        );
+    }
+
+    #[test]
+    fn test_done_and_undo() {
+        let mut pf = progress();
+        assert_eq!(
+            edit(&mut pf, TaskEdit::Done(address("T2"))),
+            "Completed 1.2 T2: Add dependencies"
+        );
+        assert!(pf.tasks[0].tasks[1].completed);
+        assert_eq!(
+            edit(&mut pf, TaskEdit::Done(address("1.2"))),
+            "1.2 T2: Add dependencies is already complete"
+        );
+
+        pf.mark_done("All tasks complete");
+        assert_eq!(
+            edit(&mut pf, TaskEdit::Undo(address("1.1"))),
+            "Reopened 1.1 T1: Create project (plan status set back to In Progress)"
+        );
+        assert!(!pf.is_done());
+        assert_eq!(
+            edit(&mut pf, TaskEdit::Undo(address("2.2"))),
+            "2.2 Write docs is not complete"
+        );
+    }
+
+    #[test]
+    fn test_move_task() {
+        let mut pf = progress();
+        assert_eq!(
+            edit(
+                &mut pf,
+                TaskEdit::Move {
+                    task: address("2.2"),
+                    to: "1.1".to_string(),
+                }
+            ),
+            "Moved 2.2 to 1.1"
+        );
+        assert_eq!(
+            descriptions(&pf, 0),
+            vec!["Write docs", "Create project", "Add dependencies"]
+        );
+
+        edit(
+            &mut pf,
+            TaskEdit::Move {
+                task: address("T1"),
+                to: "2".to_string(),
+            },
+        );
+        assert_eq!(descriptions(&pf, 1), vec!["Add model", "Create project"]);
+
+        edit(
+            &mut pf,
+            TaskEdit::Move {
+                task: address("1.1"),
+                to: "1.2".to_string(),
+            },
+        );
+        assert_eq!(descriptions(&pf, 0), vec!["Add dependencies", "Write docs"]);
+
+        let err = apply_edit(
+            &mut pf,
+            TaskEdit::Move {
+                task: address("1.1"),
+                to: "2.4".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("positions in phase 2 go up to 3"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_remove_task_checks_dependents() {
+        let mut pf = progress();
+        let err = apply_edit(
+            &mut pf,
+            TaskEdit::Remove {
+                task: address("T2"),
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("T2 is a dependency of T3"),
+            "{}",
+            err
+        );
+
+        assert_eq!(
+            edit(
+                &mut pf,
+                TaskEdit::Remove {
+                    task: address("T2"),
+                    force: true,
+                }
+            ),
+            "Removed 1.2 T2: Add dependencies"
+        );
+        assert!(pf.tasks[1].tasks[0].depends_on.is_empty());
+        pf.validate_dependencies()
+            .expect("dependencies still valid");
+    }
+
+    #[test]
+    fn test_unknown_references() {
+        let mut pf = progress();
+        let err = apply_edit(&mut pf, TaskEdit::Done(address("T9"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Task error: No task with ID T9 (IDs: T1, T2, T3)"
+        );
+        let err = apply_edit(&mut pf, TaskEdit::Done(address("2.3"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Task error: No task 2.3: phase 2 \"Phase 2: Core\" has 2 task(s)"
+        );
+        let err = apply_edit(
+            &mut pf,
+            TaskEdit::Move {
+                task: address("1.1"),
+                to: "Phase 9".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "No phase named \"Phase 9\" (phases: 1 \"Phase 1: Setup\", 2 \"Phase 2: Core\")"
+            ),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_run_task_edit_writes_and_validates() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("progress.json");
+        progress().write(&path).expect("write");
+
+        let message = run_task_edit(&path, TaskEdit::Done(address("1.2"))).expect("edit");
+        assert_eq!(message, "Completed 1.2 T2: Add dependencies");
+        assert!(ProgressFile::load(&path).expect("load").tasks[0].tasks[1].completed);
+
+        // Nothing changes, so the file isn't rewritten
+        std::fs::write(
+            &path,
+            std::fs::read_to_string(&path).expect("read").trim_end(),
+        )
+        .expect("write");
+        let before = std::fs::read_to_string(&path).expect("read");
+        run_task_edit(&path, TaskEdit::Done(address("1.2"))).expect("edit");
+        assert_eq!(std::fs::read_to_string(&path).expect("read"), before);
+
+        let err = run_task_edit(
+            &path,
+            TaskEdit::Add {
+                text: "T5: Ship (after T8)".to_string(),
+                phase: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Task error: Task \"Ship\" depends on unknown task T8"
+        );
+        assert_eq!(ProgressFile::load(&path).expect("load").total_tasks(), 4);
+    }
+
+    #[test]
+    fn test_format_task_list() {
+        let list = format_task_list(&progress());
+        assert_eq!(
+            list,
+            "1. Phase 1: Setup\n  1.1   [x] T1: Create project\n  1.2   [ ] T2: Add dependencies (after T1)\n\n2. Phase 2: Core\n  2.1   [ ] T3: Add model (after T2)\n  2.2   [ ] Write docs\n"
+        );
+    }
+}
@@ -12,6 +12,9 @@ pub struct TokenUsage {
     pub output_tokens: u64,
     pub cache_creation_input_tokens: u64,
     pub cache_read_input_tokens: u64,
+    /// Cost in USD, priced per model as the usage was recorded.
+    #[serde(default)]
+    pub cost_usd: f64,
 }
 
 impl TokenUsage {
@@ -22,6 +25,14 @@ impl TokenUsage {
         self.cache_creation_input_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
         self.cache_read_input_tokens += usage.cache_read_input_tokens.unwrap_or(0);
     }
+
+    /// Total tokens of all kinds, including cache writes and reads.
+    pub fn total(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_creation_input_tokens
+            + self.cache_read_input_tokens
+    }
 }
 
 /// Token usage for a single iteration.
@@ -32,6 +43,22 @@ pub struct IterationTokens {
     pub output_tokens: u64,
     pub cache_creation_input_tokens: u64,
     pub cache_read_input_tokens: u64,
+    /// Cost of the iteration in USD.
+    #[serde(default)]
+    pub cost_usd: f64,
+    /// Model the iteration ran on (comma-separated for parallel rounds).
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl IterationTokens {
+    /// Total tokens of all kinds, including cache writes and reads.
+    pub fn total(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_creation_input_tokens
+            + self.cache_read_input_tokens
+    }
 }
 
 /// Format token count for display (e.g., 5.2k, 1.2M).
@@ -0,0 +1,464 @@
+//! Per-iteration transcripts of agent sessions.
+//!
+//! With `archive_transcripts` enabled, each build iteration is saved to
+//! `.rslph/runs/<run-id>/iter-NNN.jsonl` (`iter-NNN-wK.jsonl` for parallel
+//! worker K). The file starts with an `rslph_input` record holding the agent
+//! backend, model, system prompt and user input that were sent, followed by
+//! the agent's raw stdout lines verbatim. Stderr lines are wrapped in
+//! `rslph_stderr` records. A timeout retry appends another `rslph_input`
+//! record to the same file.
+//!
+//! Output past `transcript_max_bytes` (counted per file, so retries share the
+//! limit) is replaced by a single `rslph_truncated` record, and transcripts of
+//! all but the `transcript_keep_runs` most recent runs are deleted when a
+//! build starts.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::state::BuildContext;
+use crate::config::Config;
+use crate::subprocess::AgentKind;
+
+/// File name prefix of iteration transcripts in a run directory.
+pub const TRANSCRIPT_PREFIX: &str = "iter-";
+
+/// A line added to a transcript by rslph (the agent's own lines are stored as-is).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TranscriptRecord {
+    /// What was sent to the agent when the iteration (or a retry) started.
+    #[serde(rename = "rslph_input")]
+    Input {
+        iteration: u32,
+        /// When the agent was spawned (RFC 3339).
+        started_at: String,
+        /// Backend that produced the output (Claude in older transcripts).
+        #[serde(default)]
+        backend: AgentKind,
+        model: Option<String>,
+        system_prompt: String,
+        user_input: String,
+    },
+    /// A line the agent wrote to stderr.
+    #[serde(rename = "rslph_stderr")]
+    Stderr { line: String },
+    /// Further output was dropped because the size limit was reached.
+    #[serde(rename = "rslph_truncated")]
+    Truncated { max_bytes: u64 },
+}
+
+impl TranscriptRecord {
+    /// Parse a transcript line written by rslph (None for the agent's lines).
+    pub fn parse(line: &str) -> Option<Self> {
+        serde_json::from_str(line).ok()
+    }
+}
+
+/// Where a build writes its transcripts.
+#[derive(Debug, Clone)]
+pub struct TranscriptArchive {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Parallel worker number, added to file names.
+    worker: Option<usize>,
+}
+
+impl TranscriptArchive {
+    /// Archive into `run_dir`, or None if `archive_transcripts` is off.
+    pub fn new(run_dir: &Path, config: &Config) -> Option<Self> {
+        config.archive_transcripts.then(|| Self {
+            dir: run_dir.to_path_buf(),
+            max_bytes: config.transcript_max_bytes,
+            worker: None,
+        })
+    }
+
+    /// The same archive with file names for parallel worker `worker`.
+    pub fn for_worker(&self, worker: usize) -> Self {
+        Self {
+            worker: Some(worker),
+            ..self.clone()
+        }
+    }
+
+    /// Transcript path for `iteration`.
+    pub fn path(&self, iteration: u32) -> PathBuf {
+        let name = match self.worker {
+            Some(worker) => format!("{}{:03}-w{}.jsonl", TRANSCRIPT_PREFIX, iteration, worker),
+            None => format!("{}{:03}.jsonl", TRANSCRIPT_PREFIX, iteration),
+        };
+        self.dir.join(name)
+    }
+
+    /// Open the transcript for `iteration` and record what is sent to the agent.
+    pub fn start(
+        &self,
+        iteration: u32,
+        backend: AgentKind,
+        model: Option<&str>,
+        system_prompt: &str,
+        user_input: &str,
+    ) -> io::Result<Transcript> {
+        let path = self.path(iteration);
+        let (written, truncated) = existing_output(&path)?;
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        let mut transcript = Transcript {
+            path,
+            file: BufWriter::new(file),
+            written,
+            max_bytes: self.max_bytes,
+            truncated,
+            error: None,
+        };
+        transcript.write_raw(&record_line(&TranscriptRecord::Input {
+            iteration,
+            started_at: chrono::Utc::now().to_rfc3339(),
+            backend,
+            model: model.map(str::to_string),
+            system_prompt: system_prompt.to_string(),
+            user_input: user_input.to_string(),
+        }));
+        Ok(transcript)
+    }
+}
+
+/// An open iteration transcript.
+///
+/// Write errors are kept and reported by `finish`; a transcript must never
+/// fail the iteration it records.
+pub struct Transcript {
+    path: PathBuf,
+    file: BufWriter<File>,
+    /// Output bytes in the file, including earlier sessions of a retry.
+    written: u64,
+    max_bytes: u64,
+    truncated: bool,
+    error: Option<io::Error>,
+}
+
+impl Transcript {
+    /// Record a raw stdout line from the agent.
+    pub fn stdout(&mut self, line: &str) {
+        self.write_output(line);
+    }
+
+    /// Record a stderr line from the agent.
+    pub fn stderr(&mut self, line: &str) {
+        self.write_output(&record_line(&TranscriptRecord::Stderr {
+            line: line.to_string(),
+        }));
+    }
+
+    /// Flush the transcript and return its path.
+    pub fn finish(mut self) -> io::Result<PathBuf> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.file.flush()?;
+        Ok(self.path)
+    }
+
+    fn write_output(&mut self, line: &str) {
+        if self.truncated {
+            return;
+        }
+        let len = line.len() as u64 + 1;
+        if self.max_bytes > 0 && self.written + len > self.max_bytes {
+            self.truncated = true;
+            self.write_raw(&record_line(&TranscriptRecord::Truncated {
+                max_bytes: self.max_bytes,
+            }));
+            return;
+        }
+        self.written += len;
+        self.write_raw(line);
+    }
+
+    fn write_raw(&mut self, line: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = writeln!(self.file, "{}", line) {
+            self.error = Some(e);
+        }
+    }
+}
+
+/// Output bytes already in a transcript and whether it was truncated.
+fn existing_output(path: &Path) -> io::Result<(u64, bool)> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok((0, false)),
+        Err(e) => return Err(e),
+    };
+    let mut written = 0;
+    for line in content.lines() {
+        match TranscriptRecord::parse(line) {
+            Some(TranscriptRecord::Input { .. }) => {}
+            Some(TranscriptRecord::Truncated { .. }) => return Ok((written, true)),
+            _ => written += line.len() as u64 + 1,
+        }
+    }
+    Ok((written, false))
+}
+
+fn record_line(record: &TranscriptRecord) -> String {
+    serde_json::to_string(record).unwrap_or_default()
+}
+
+/// Archive transcripts for the run in `run_dir` and apply the retention policy.
+pub fn start_archive(ctx: &mut BuildContext, run_dir: &Path) {
+    ctx.transcripts = TranscriptArchive::new(run_dir, &ctx.config);
+    if ctx.transcripts.is_none() || ctx.config.transcript_keep_runs == 0 {
+        return;
+    }
+
+    let Some(runs_dir) = run_dir.parent() else {
+        return;
+    };
+    match prune(runs_dir, ctx.config.transcript_keep_runs as usize) {
+        Ok(0) => {}
+        Ok(removed) => ctx.log(&format!(
+            "[TRANSCRIPT] Removed {} transcript(s) of older runs",
+            removed
+        )),
+        Err(e) => ctx.log(&format!(
+            "[TRANSCRIPT] Warning: failed to prune old transcripts: {}",
+            e
+        )),
+    }
+}
+
+/// Delete the transcripts of all but the `keep` most recent runs.
+///
+/// Run directories are ordered by name (run IDs sort chronologically). Only
+/// transcript files are removed; run state is left alone.
+///
+/// # Returns
+///
+/// The number of transcript files removed.
+pub fn prune(runs_dir: &Path, keep: usize) -> io::Result<usize> {
+    let mut run_dirs: Vec<PathBuf> = std::fs::read_dir(runs_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    run_dirs.sort();
+
+    let mut removed = 0;
+    for dir in run_dirs.iter().rev().skip(keep) {
+        for path in transcripts_in(dir)? {
+            std::fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Transcript files in a run directory, in iteration order.
+///
+/// Files are ordered by iteration number, then worker (the sequential
+/// transcript of an iteration comes before its workers').
+pub fn transcripts_in(run_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths: Vec<((u32, Option<usize>), PathBuf)> = std::fs::read_dir(run_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|p| {
+            let key = p.file_stem().and_then(|n| n.to_str()).and_then(sort_key)?;
+            Some((key, p))
+        })
+        .collect();
+    paths.sort();
+    Ok(paths.into_iter().map(|(_, p)| p).collect())
+}
+
+/// Parse `iter-NNN` or `iter-NNN-wK` into (iteration, worker).
+fn sort_key(stem: &str) -> Option<(u32, Option<usize>)> {
+    let rest = stem.strip_prefix(TRANSCRIPT_PREFIX)?;
+    match rest.split_once("-w") {
+        Some((iteration, worker)) => Some((iteration.parse().ok()?, Some(worker.parse().ok()?))),
+        None => Some((rest.parse().ok()?, None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn start(archive: &TranscriptArchive, iteration: u32) -> Transcript {
+        archive
+            .start(
+                iteration,
+                AgentKind::Claude,
+                Some("claude-opus-4-5"),
+                "You are rslph",
+                "Go",
+            )
+            .expect("start")
+    }
+
+    fn archive(dir: &TempDir, max_bytes: u64) -> TranscriptArchive {
+        let config = Config {
+            transcript_max_bytes: max_bytes,
+            ..Default::default()
+        };
+        TranscriptArchive::new(dir.path(), &config).expect("enabled by default")
+    }
+
+    #[test]
+    fn test_transcript_keeps_input_and_raw_lines() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut transcript = start(&archive(&dir, 0), 3);
+        transcript.stdout(r#"{"type":"system","subtype":"init"}"#);
+        transcript.stderr("warning: slow network");
+        let path = transcript.finish().expect("finish");
+
+        assert_eq!(path, dir.path().join("iter-003.jsonl"));
+        let content = std::fs::read_to_string(&path).expect("read");
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        match TranscriptRecord::parse(lines[0]) {
+            Some(TranscriptRecord::Input {
+                iteration,
+                backend,
+                model,
+                system_prompt,
+                user_input,
+                ..
+            }) => {
+                assert_eq!(iteration, 3);
+                assert_eq!(backend, AgentKind::Claude);
+                assert_eq!(model.as_deref(), Some("claude-opus-4-5"));
+                assert_eq!(system_prompt, "You are rslph");
+                assert_eq!(user_input, "Go");
+            }
+            other => panic!("expected input record, got {:?}", other),
+        }
+        assert_eq!(lines[1], r#"{"type":"system","subtype":"init"}"#);
+        assert_eq!(TranscriptRecord::parse(lines[1]), None);
+        assert_eq!(
+            TranscriptRecord::parse(lines[2]),
+            Some(TranscriptRecord::Stderr {
+                line: "warning: slow network".to_string()
+            })
+        );
+
+        // A retry appends to the same file; workers get their own
+        start(&archive(&dir, 0), 3).finish().expect("finish");
+        assert_eq!(
+            std::fs::read_to_string(&path)
+                .expect("read")
+                .lines()
+                .count(),
+            4
+        );
+        assert_eq!(
+            archive(&dir, 0).for_worker(2).path(3),
+            dir.path().join("iter-003-w2.jsonl")
+        );
+    }
+
+    #[test]
+    fn test_transcript_truncates_output_past_limit() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut transcript = start(&archive(&dir, 25), 1);
+        transcript.stdout("0123456789");
+        transcript.stdout("0123456789");
+        transcript.stdout("0123456789");
+        transcript.stdout("0123456789");
+        let content = std::fs::read_to_string(transcript.finish().expect("finish")).expect("read");
+
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            TranscriptRecord::parse(lines[3]),
+            Some(TranscriptRecord::Truncated { max_bytes: 25 })
+        );
+    }
+
+    #[test]
+    fn test_transcript_limit_covers_retries() {
+        let dir = TempDir::new().expect("temp dir");
+        let mut transcript = start(&archive(&dir, 25), 1);
+        transcript.stdout("0123456789");
+        transcript.stdout("0123456789");
+        transcript.finish().expect("finish");
+
+        // The retry only has 3 bytes left, then stays truncated
+        let mut transcript = start(&archive(&dir, 25), 1);
+        transcript.stdout("0123456789");
+        let path = transcript.finish().expect("finish");
+        start(&archive(&dir, 25), 1).finish().expect("finish");
+
+        let content = std::fs::read_to_string(path).expect("read");
+        let records: Vec<Option<TranscriptRecord>> =
+            content.lines().map(TranscriptRecord::parse).collect();
+        assert_eq!(records.len(), 6);
+        assert_eq!(
+            records[4],
+            Some(TranscriptRecord::Truncated { max_bytes: 25 })
+        );
+        assert!(matches!(records[5], Some(TranscriptRecord::Input { .. })));
+    }
+
+    #[test]
+    fn test_transcripts_in_sorts_by_iteration_then_worker() {
+        let dir = TempDir::new().expect("temp dir");
+        for name in [
+            "iter-1000.jsonl",
+            "iter-101.jsonl",
+            "iter-101-w10.jsonl",
+            "iter-101-w2.jsonl",
+            "iter-099.jsonl",
+            "iter-notes.jsonl",
+        ] {
+            std::fs::write(dir.path().join(name), "{}\n").expect("write");
+        }
+
+        let names: Vec<String> = transcripts_in(dir.path())
+            .expect("list")
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "iter-099.jsonl",
+                "iter-101.jsonl",
+                "iter-101-w2.jsonl",
+                "iter-101-w10.jsonl",
+                "iter-1000.jsonl",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_keeps_most_recent_runs() {
+        let dir = TempDir::new().expect("temp dir");
+        for run in ["20260101-000000", "20260102-000000", "20260103-000000"] {
+            let run_dir = dir.path().join(run);
+            std::fs::create_dir_all(&run_dir).expect("mkdir");
+            std::fs::write(run_dir.join("iter-001.jsonl"), "{}\n").expect("write");
+            std::fs::write(run_dir.join("state.json"), "{}").expect("write");
+        }
+
+        assert_eq!(prune(dir.path(), 2).expect("prune"), 1);
+        let oldest = dir.path().join("20260101-000000");
+        assert!(transcripts_in(&oldest).expect("list").is_empty());
+        assert!(oldest.join("state.json").is_file());
+        assert_eq!(
+            transcripts_in(&dir.path().join("20260103-000000"))
+                .expect("list")
+                .len(),
+            1
+        );
+        assert_eq!(prune(dir.path(), 2).expect("prune"), 0);
+    }
+}
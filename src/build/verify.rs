@@ -0,0 +1,338 @@
+//! Per-iteration verification gates.
+//!
+//! After an iteration checks off tasks, the configured gates (type-check, lint,
+//! test) run in the working directory. If any gate fails, the newly checked
+//! tasks are reverted so the next iteration sees them as incomplete.
+
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::process::Command;
+
+use crate::config::Config;
+use crate::planning::detect_stack;
+use crate::progress::ProgressFile;
+
+/// Maximum characters of gate output kept in a Recent Attempts entry.
+const MAX_SUMMARY_CHARS: usize = 400;
+
+/// Number of trailing output lines kept in a Recent Attempts entry.
+const SUMMARY_LINES: usize = 8;
+
+/// A verification command run after an iteration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gate {
+    /// Gate name ("type-check", "lint", "test").
+    pub name: &'static str,
+    /// Shell command to run.
+    pub command: String,
+}
+
+/// Details of a failed gate.
+#[derive(Debug, Clone)]
+pub struct GateFailure {
+    /// Name of the gate that failed.
+    pub gate: &'static str,
+    /// Command that was run.
+    pub command: String,
+    /// One-line summary of the failure (exit code and trailing output).
+    pub summary: String,
+}
+
+/// Resolve the gates to run for a working directory.
+///
+/// Explicit config commands take precedence; otherwise commands come from the
+/// detected project stack. An empty configured command disables that gate.
+pub fn resolve_gates(config: &Config, working_dir: &Path) -> Vec<Gate> {
+    let stack = detect_stack(working_dir);
+
+    let candidates = [
+        (
+            "type-check",
+            config.verify_typecheck_cmd.clone(),
+            stack.type_check_command(),
+        ),
+        ("lint", config.verify_lint_cmd.clone(), stack.lint_command()),
+        ("test", config.verify_test_cmd.clone(), stack.test_command()),
+    ];
+
+    candidates
+        .into_iter()
+        .filter_map(|(name, configured, detected)| {
+            configured
+                .or(detected)
+                .filter(|cmd| !cmd.trim().is_empty())
+                .map(|command| Gate { name, command })
+        })
+        .collect()
+}
+
+/// Run gates in order, stopping at the first failure.
+pub async fn run_gates(
+    gates: &[Gate],
+    working_dir: &Path,
+    timeout: Duration,
+) -> Result<(), GateFailure> {
+    for gate in gates {
+        run_gate(gate, working_dir, timeout).await?;
+    }
+    Ok(())
+}
+
+/// Run a single gate through `sh -c`.
+async fn run_gate(gate: &Gate, working_dir: &Path, timeout: Duration) -> Result<(), GateFailure> {
+    let failure = |summary: String| GateFailure {
+        gate: gate.name,
+        command: gate.command.clone(),
+        summary,
+    };
+
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(&gate.command)
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| failure(format!("failed to start: {}", e)))?;
+
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(failure(format!("failed to run: {}", e))),
+        Err(_) => return Err(failure(format!("timed out after {}s", timeout.as_secs()))),
+    };
+
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let mut combined = String::from_utf8_lossy(&output.stdout).into_owned();
+    combined.push('\n');
+    combined.push_str(&String::from_utf8_lossy(&output.stderr));
+
+    let exit = output
+        .status
+        .code()
+        .map(|c| format!("exit code {}", c))
+        .unwrap_or_else(|| "terminated by signal".to_string());
+
+    Err(failure(format!("{}: {}", exit, summarize_output(&combined))))
+}
+
+/// Condense command output to a single line for Recent Attempts.
+///
+/// Keeps the last few non-empty lines (where test runners and compilers put
+/// their verdicts) joined with " | ", truncated to a bounded length.
+pub fn summarize_output(output: &str) -> String {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let tail = &lines[lines.len().saturating_sub(SUMMARY_LINES)..];
+    let summary = tail.join(" | ");
+
+    if summary.chars().count() > MAX_SUMMARY_CHARS {
+        let start = summary.chars().count() - MAX_SUMMARY_CHARS;
+        format!("...{}", summary.chars().skip(start).collect::<String>())
+    } else if summary.is_empty() {
+        "(no output)".to_string()
+    } else {
+        summary
+    }
+}
+
+/// Tasks checked in `after` that were not checked in `before`.
+///
+/// Tasks are matched by phase name and description. Returns (phase, description) pairs.
+pub fn newly_completed(before: &ProgressFile, after: &ProgressFile) -> Vec<(String, String)> {
+    let was_completed = |phase: &str, description: &str| {
+        before.tasks.iter().any(|p| {
+            p.name == phase
+                && p.tasks
+                    .iter()
+                    .any(|t| t.description == description && t.completed)
+        })
+    };
+
+    after
+        .tasks
+        .iter()
+        .flat_map(|phase| {
+            phase
+                .tasks
+                .iter()
+                .filter(|t| t.completed && !was_completed(&phase.name, &t.description))
+                .map(|t| (phase.name.clone(), t.description.clone()))
+        })
+        .collect()
+}
+
+/// Mark the given (phase, description) tasks as incomplete.
+pub fn revert_tasks(progress: &mut ProgressFile, tasks: &[(String, String)]) {
+    for phase in &mut progress.tasks {
+        for task in &mut phase.tasks {
+            if tasks
+                .iter()
+                .any(|(p, d)| *p == phase.name && *d == task.description)
+            {
+                task.completed = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::progress::{Task, TaskPhase};
+    use tempfile::TempDir;
+
+    fn progress_with(tasks: &[(&str, bool)]) -> ProgressFile {
+        ProgressFile {
+            name: "Test".to_string(),
+            tasks: vec![TaskPhase {
+                name: "Phase 1".to_string(),
+                tasks: tasks
+                    .iter()
+                    .map(|(d, c)| Task {
+                        description: d.to_string(),
+                        completed: *c,
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_gates_from_stack() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"",
+        )
+        .expect("write Cargo.toml");
+
+        let gates = resolve_gates(&Config::default(), dir.path());
+        let names: Vec<&str> = gates.iter().map(|g| g.name).collect();
+        assert_eq!(names, vec!["type-check", "lint", "test"]);
+        assert_eq!(gates[2].command, "cargo test");
+    }
+
+    #[test]
+    fn test_resolve_gates_config_overrides_and_disables() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"",
+        )
+        .expect("write Cargo.toml");
+
+        let config = Config {
+            verify_test_cmd: Some("make test".to_string()),
+            verify_lint_cmd: Some(String::new()),
+            ..Default::default()
+        };
+
+        let gates = resolve_gates(&config, dir.path());
+        let names: Vec<&str> = gates.iter().map(|g| g.name).collect();
+        assert_eq!(names, vec!["type-check", "test"]);
+        assert_eq!(gates[1].command, "make test");
+    }
+
+    #[test]
+    fn test_resolve_gates_unknown_stack_is_empty() {
+        let dir = TempDir::new().expect("temp dir");
+        assert!(resolve_gates(&Config::default(), dir.path()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_gates_passes() {
+        let dir = TempDir::new().expect("temp dir");
+        let gates = vec![Gate {
+            name: "test",
+            command: "true".to_string(),
+        }];
+
+        assert!(run_gates(&gates, dir.path(), Duration::from_secs(10))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_gates_stops_at_first_failure() {
+        let dir = TempDir::new().expect("temp dir");
+        let gates = vec![
+            Gate {
+                name: "lint",
+                command: "echo 'warning: unused variable'; exit 3".to_string(),
+            },
+            Gate {
+                name: "test",
+                command: "touch ran-tests".to_string(),
+            },
+        ];
+
+        let failure = run_gates(&gates, dir.path(), Duration::from_secs(10))
+            .await
+            .expect_err("lint gate should fail");
+        assert_eq!(failure.gate, "lint");
+        assert!(failure.summary.contains("exit code 3"));
+        assert!(failure.summary.contains("unused variable"));
+        assert!(!dir.path().join("ran-tests").exists());
+    }
+
+    #[tokio::test]
+    async fn test_run_gate_timeout() {
+        let dir = TempDir::new().expect("temp dir");
+        let gates = vec![Gate {
+            name: "test",
+            command: "sleep 5".to_string(),
+        }];
+
+        let failure = run_gates(&gates, dir.path(), Duration::from_millis(100))
+            .await
+            .expect_err("gate should time out");
+        assert!(failure.summary.contains("timed out"));
+    }
+
+    #[test]
+    fn test_summarize_output_keeps_tail_on_one_line() {
+        let output = (1..=20)
+            .map(|i| format!("line {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let summary = summarize_output(&output);
+        assert!(!summary.contains('\n'));
+        assert!(summary.starts_with("line 13"));
+        assert!(summary.ends_with("line 20"));
+
+        assert_eq!(summarize_output("\n\n"), "(no output)");
+        assert!(summarize_output(&"x".repeat(1000)).len() <= MAX_SUMMARY_CHARS + 3);
+    }
+
+    #[test]
+    fn test_newly_completed_and_revert() {
+        let before = progress_with(&[("A", true), ("B", false), ("C", false)]);
+        let mut after = progress_with(&[("A", true), ("B", true), ("C", true)]);
+
+        let newly = newly_completed(&before, &after);
+        assert_eq!(
+            newly,
+            vec![
+                ("Phase 1".to_string(), "B".to_string()),
+                ("Phase 1".to_string(), "C".to_string())
+            ]
+        );
+
+        revert_tasks(&mut after, &newly);
+        assert_eq!(after.completed_tasks(), 1);
+        assert!(after.tasks[0].tasks[0].completed, "A stays checked");
+    }
+}
@@ -2,8 +2,10 @@ use clap::parser::ValueSource;
 use clap::{CommandFactory, Parser, Subcommand};
 use std::path::PathBuf;
 
+use crate::build::TaskAddress;
 use crate::config::{Config, PartialConfig};
 use crate::prompts::PromptMode;
+use crate::subprocess::{AgentKind, HttpApi};
 
 #[derive(Parser, Debug)]
 #[command(name = "rslph")]
@@ -26,6 +28,10 @@ pub struct Cli {
     #[arg(long, global = true, value_parser = clap::value_parser!(PromptMode))]
     pub mode: Option<PromptMode>,
 
+    /// Agent backend for build iterations and planning (claude, command, http)
+    #[arg(long, global = true, value_parser = clap::value_parser!(AgentKind))]
+    pub backend: Option<AgentKind>,
+
     /// Append --dangerously-skip-permissions to all Claude invocations
     #[arg(long, global = true)]
     pub no_dsp: bool,
@@ -58,6 +64,38 @@ pub enum Commands {
         /// Preview without executing
         #[arg(long)]
         dry_run: bool,
+
+        /// Resume the most recent interrupted run from its build journal
+        #[arg(long)]
+        resume: bool,
+
+        /// Run up to N tasks at once, each in its own git worktree
+        #[arg(
+            long,
+            value_name = "N",
+            default_value = "1",
+            value_parser = clap::value_parser!(u32).range(1..),
+            conflicts_with_all = ["once", "dry_run", "resume"]
+        )]
+        parallel: u32,
+
+        /// Write build events as JSON lines to PATH (`-` for stdout)
+        #[arg(long, value_name = "PATH", conflicts_with = "dry_run")]
+        events: Option<PathBuf>,
+    },
+
+    /// Play back a recorded build run in the TUI
+    Replay {
+        /// Run directory holding iteration transcripts (.rslph/runs/<run-id>)
+        run_dir: PathBuf,
+
+        /// Playback speed multiplier
+        #[arg(long, default_value = "1", value_parser = parse_speed)]
+        speed: f64,
+
+        /// Fast-forward to iteration N, then play at the chosen speed
+        #[arg(long, value_name = "N")]
+        from: Option<u32>,
     },
 
     /// Run evaluation in isolated environment (EVAL-01)
@@ -97,6 +135,145 @@ pub enum Commands {
         /// Second result file (comparison)
         file2: PathBuf,
     },
+
+    /// Show where a build stands, read from its progress file
+    Status {
+        /// Path to the progress file
+        #[arg(default_value = "progress.md")]
+        plan: PathBuf,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Number of iteration log rows to show
+        #[arg(long, value_name = "N", default_value = "5")]
+        log: usize,
+    },
+
+    /// Check a progress file for content the parser drops or misreads
+    Lint {
+        /// Path to the progress file
+        #[arg(default_value = "progress.md")]
+        plan: PathBuf,
+
+        /// Apply mechanical fixes in place
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// List, add, check off, reorder or remove tasks in a progress file
+    Task {
+        /// Progress file to edit
+        #[arg(long, short = 'f', global = true, default_value = "progress.md")]
+        file: PathBuf,
+
+        #[command(subcommand)]
+        command: TaskCommands,
+    },
+
+    /// Work with progress files
+    Progress {
+        #[command(subcommand)]
+        command: ProgressCommands,
+    },
+
+    /// Run one agent session against an HTTP model API (used by the http backend)
+    #[command(hide = true)]
+    HttpAgent {
+        /// Wire protocol (anthropic, openai)
+        #[arg(long, value_parser = clap::value_parser!(HttpApi))]
+        api: HttpApi,
+
+        /// API base URL (e.g. http://localhost:8080/v1)
+        #[arg(long)]
+        base_url: String,
+
+        /// Environment variable holding the API key (unset sends no key)
+        #[arg(long)]
+        api_key_env: String,
+
+        /// Model to request
+        #[arg(long)]
+        model: String,
+
+        /// Maximum tokens per response
+        #[arg(long)]
+        max_tokens: u32,
+
+        /// Maximum model round trips
+        #[arg(long)]
+        max_turns: u32,
+
+        /// System prompt
+        #[arg(long)]
+        system_prompt: String,
+
+        /// User message
+        user_input: String,
+    },
+}
+
+/// Subcommands of `rslph progress`.
+#[derive(Subcommand, Debug)]
+pub enum ProgressCommands {
+    /// Convert a progress file between markdown, JSON, YAML and TOML (by extension)
+    Convert {
+        /// Progress file to read
+        input: PathBuf,
+
+        /// File to write (.md, .json, .yaml, .yml or .toml)
+        output: PathBuf,
+
+        /// Overwrite the output file if it exists
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Subcommands of `rslph task`.
+///
+/// TASK is a position, PHASE.INDEX counted from 1 (e.g. 2.1), or a task ID (e.g. T3).
+#[derive(Subcommand, Debug)]
+pub enum TaskCommands {
+    /// List tasks with their PHASE.INDEX positions
+    List,
+
+    /// Add a task to the end of a phase
+    Add {
+        /// Task as written in the progress file, e.g. "T7: Add login (after T3; priority: high)"
+        text: String,
+
+        /// Phase number or name (default: the last phase); a new name adds a phase
+        #[arg(long)]
+        phase: Option<String>,
+    },
+
+    /// Mark a task complete
+    Done { task: TaskAddress },
+
+    /// Mark a completed task incomplete again
+    Undo { task: TaskAddress },
+
+    /// Move a task to PHASE.INDEX, or to the end of a phase given by number or name
+    Move { task: TaskAddress, to: String },
+
+    /// Remove a task
+    Rm {
+        task: TaskAddress,
+
+        /// Also remove the task from other tasks' dependencies
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Parse a positive playback speed.
+fn parse_speed(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("'{}' is not a positive number", s)),
+    }
 }
 
 impl Cli {
@@ -112,6 +289,7 @@ impl Cli {
                 &self.max_iterations,
             ),
             prompt_mode: self.extract_if_explicit(matches, "mode", &self.mode),
+            agent_backend: self.extract_if_explicit(matches, "backend", &self.backend),
             ..Default::default()
         }
     }
@@ -166,10 +344,16 @@ mod tests {
                 plan,
                 once,
                 dry_run,
+                resume,
+                parallel,
+                events,
             } => {
                 assert_eq!(plan, PathBuf::from("progress.md"));
                 assert!(once);
                 assert!(!dry_run);
+                assert!(!resume);
+                assert_eq!(parallel, 1);
+                assert!(events.is_none());
             }
             _ => panic!("Expected Build command"),
         }
@@ -217,15 +401,91 @@ mod tests {
                 plan,
                 once,
                 dry_run,
+                resume,
+                ..
             } => {
                 assert_eq!(plan, PathBuf::from("progress.md"));
                 assert!(!once);
                 assert!(dry_run);
+                assert!(!resume);
+            }
+            _ => panic!("Expected Build command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_build_with_resume() {
+        let cli = Cli::try_parse_from(["rslph", "build", "progress.md", "--resume"])
+            .expect("Should parse");
+        match cli.command {
+            Commands::Build { plan, resume, .. } => {
+                assert_eq!(plan, PathBuf::from("progress.md"));
+                assert!(resume);
             }
             _ => panic!("Expected Build command"),
         }
     }
 
+    #[test]
+    fn test_parse_build_with_parallel() {
+        let cli = Cli::try_parse_from(["rslph", "build", "progress.md", "--parallel", "3"])
+            .expect("Should parse");
+        match cli.command {
+            Commands::Build { parallel, .. } => assert_eq!(parallel, 3),
+            _ => panic!("Expected Build command"),
+        }
+
+        assert!(Cli::try_parse_from(["rslph", "build", "progress.md", "--parallel", "0"]).is_err());
+        assert!(Cli::try_parse_from([
+            "rslph",
+            "build",
+            "progress.md",
+            "--parallel",
+            "2",
+            "--once"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_build_with_events() {
+        let cli = Cli::try_parse_from(["rslph", "build", "progress.md", "--events", "-"])
+            .expect("Should parse");
+        match cli.command {
+            Commands::Build { events, .. } => assert_eq!(events, Some(PathBuf::from("-"))),
+            _ => panic!("Expected Build command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_replay_command() {
+        let cli = Cli::try_parse_from([
+            "rslph",
+            "replay",
+            ".rslph/runs/20260101-000000",
+            "--speed",
+            "4",
+            "--from",
+            "3",
+        ])
+        .expect("Should parse");
+        match cli.command {
+            Commands::Replay {
+                run_dir,
+                speed,
+                from,
+            } => {
+                assert_eq!(run_dir, PathBuf::from(".rslph/runs/20260101-000000"));
+                assert_eq!(speed, 4.0);
+                assert_eq!(from, Some(3));
+            }
+            _ => panic!("Expected Replay command"),
+        }
+
+        assert!(Cli::try_parse_from(["rslph", "replay", "run", "--speed", "0"]).is_err());
+        assert!(Cli::try_parse_from(["rslph", "replay", "run", "--speed", "fast"]).is_err());
+    }
+
     #[test]
     fn test_config_override_flag() {
         let cli = Cli::try_parse_from(["rslph", "-c", "/custom/config.toml", "plan", "idea.txt"])
@@ -417,6 +677,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_backend_flag() {
+        let cli = Cli::try_parse_from(["rslph", "eval", "calculator", "--backend", "command"])
+            .expect("Should parse");
+        assert_eq!(cli.backend, Some(AgentKind::Command));
+
+        let cli = Cli::try_parse_from(["rslph", "build", "PROGRESS.md"]).expect("Should parse");
+        assert!(cli.backend.is_none());
+    }
+
+    #[test]
+    fn test_parse_http_agent_command() {
+        let cli = Cli::try_parse_from([
+            "rslph",
+            "http-agent",
+            "--api",
+            "openai",
+            "--base-url",
+            "http://localhost:8080/v1",
+            "--api-key-env",
+            "OPENAI_API_KEY",
+            "--model",
+            "local",
+            "--max-tokens",
+            "1024",
+            "--max-turns",
+            "5",
+            "--system-prompt",
+            "sys",
+            "go",
+        ])
+        .expect("Should parse");
+        match cli.command {
+            Commands::HttpAgent {
+                api,
+                max_turns,
+                user_input,
+                ..
+            } => {
+                assert_eq!(api, HttpApi::OpenAi);
+                assert_eq!(max_turns, 5);
+                assert_eq!(user_input, "go");
+            }
+            _ => panic!("Expected HttpAgent command"),
+        }
+    }
+
     #[test]
     fn test_parse_compare_command() {
         let cli = Cli::try_parse_from([
@@ -435,6 +742,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_status_command() {
+        let cli = Cli::try_parse_from(["rslph", "status"]).expect("Should parse");
+        match cli.command {
+            Commands::Status { plan, json, log } => {
+                assert_eq!(plan, PathBuf::from("progress.md"));
+                assert!(!json);
+                assert_eq!(log, 5);
+            }
+            _ => panic!("Expected Status command"),
+        }
+
+        let cli = Cli::try_parse_from(["rslph", "status", "plan.json", "--json", "--log", "2"])
+            .expect("Should parse");
+        match cli.command {
+            Commands::Status { plan, json, log } => {
+                assert_eq!(plan, PathBuf::from("plan.json"));
+                assert!(json);
+                assert_eq!(log, 2);
+            }
+            _ => panic!("Expected Status command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_lint_command() {
+        let cli = Cli::try_parse_from(["rslph", "lint", "plan.md", "--fix"]).expect("Should parse");
+        match cli.command {
+            Commands::Lint { plan, fix } => {
+                assert_eq!(plan, PathBuf::from("plan.md"));
+                assert!(fix);
+            }
+            _ => panic!("Expected Lint command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_task_commands() {
+        let cli = Cli::try_parse_from(["rslph", "task", "done", "2.1"]).expect("Should parse");
+        match cli.command {
+            Commands::Task {
+                file,
+                command: TaskCommands::Done { task },
+            } => {
+                assert_eq!(file, PathBuf::from("progress.md"));
+                assert_eq!(task, TaskAddress::Position { phase: 2, index: 1 });
+            }
+            _ => panic!("Expected Task Done command"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "rslph",
+            "task",
+            "add",
+            "T7: Add login",
+            "--phase",
+            "2",
+            "-f",
+            "plan.json",
+        ])
+        .expect("Should parse");
+        match cli.command {
+            Commands::Task {
+                file,
+                command: TaskCommands::Add { text, phase },
+            } => {
+                assert_eq!(file, PathBuf::from("plan.json"));
+                assert_eq!(text, "T7: Add login");
+                assert_eq!(phase.as_deref(), Some("2"));
+            }
+            _ => panic!("Expected Task Add command"),
+        }
+
+        assert!(Cli::try_parse_from(["rslph", "task", "rm", "not a task"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_progress_convert_command() {
+        let cli = Cli::try_parse_from([
+            "rslph",
+            "progress",
+            "convert",
+            "progress.md",
+            "progress.json",
+        ])
+        .expect("Should parse");
+        match cli.command {
+            Commands::Progress {
+                command:
+                    ProgressCommands::Convert {
+                        input,
+                        output,
+                        force,
+                    },
+            } => {
+                assert_eq!(input, PathBuf::from("progress.md"));
+                assert_eq!(output, PathBuf::from("progress.json"));
+                assert!(!force);
+            }
+            _ => panic!("Expected Progress Convert command"),
+        }
+    }
+
     #[test]
     fn test_parse_no_dsp_flag() {
         let cli =
@@ -1,10 +1,15 @@
+use crate::build::guard::default_rules;
+use crate::build::{GuardrailAction, GuardrailRule, HookConfig, StallPolicy};
+use crate::pricing::ModelPricing;
 use crate::prompts::PromptMode;
+use crate::subprocess::{AgentKind, HttpApi};
 use directories::{BaseDirs, ProjectDirs};
 use figment::{
     providers::{Env, Format, Serialized, Toml},
     Figment,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
@@ -72,6 +77,29 @@ pub struct Config {
     #[serde(skip)]
     pub claude_cmd: ClaudeCommand,
 
+    /// Agent that runs build iterations (claude, command)
+    pub agent_backend: AgentKind,
+
+    /// Command line for the `command` backend, run through `sh -c` with the
+    /// prompt appended as its last argument; stdout is the agent's response
+    pub agent_cmd: Option<String>,
+
+    /// Wire protocol for the `http` backend (anthropic, openai)
+    pub http_api: HttpApi,
+
+    /// Base URL for the `http` backend. None uses the provider's public API.
+    pub http_base_url: Option<String>,
+
+    /// Environment variable holding the `http` backend's API key.
+    /// None uses ANTHROPIC_API_KEY or OPENAI_API_KEY to match `http_api`.
+    pub http_api_key_env: Option<String>,
+
+    /// Maximum tokens per `http` backend response
+    pub http_max_tokens: u32,
+
+    /// Maximum model round trips per iteration for the `http` backend
+    pub http_max_turns: u32,
+
     /// Maximum iterations before stopping (CFG-06)
     pub max_iterations: u32,
 
@@ -87,9 +115,22 @@ pub struct Config {
     /// Path to build prompt file override (CFG-04)
     pub build_prompt: Option<PathBuf>,
 
+    /// Path to the build iteration user-message template override
+    pub build_message_prompt: Option<PathBuf>,
+
+    /// Path to the eval test discovery prompt override
+    pub test_discovery_prompt: Option<PathBuf>,
+
     /// Shell for notify script execution (CFG-05)
     pub notify_shell: String,
 
+    /// Notification command run through `notify_shell` every `notify_interval`
+    /// iterations and when the build stops. None disables notifications.
+    pub notify_script: Option<String>,
+
+    /// Seconds a notify script may run before it is killed
+    pub notify_timeout: u64,
+
     /// Enable TUI mode for build command (TUI-09)
     pub tui_enabled: bool,
 
@@ -108,6 +149,93 @@ pub struct Config {
 
     /// Prompt mode selection (basic, gsd)
     pub prompt_mode: PromptMode,
+
+    /// Suspend the running Claude subprocess and its process group (SIGSTOP) while the TUI is paused.
+    /// When false, pausing lets the current iteration finish before holding.
+    pub pause_suspends_subprocess: bool,
+
+    /// Run verification gates (test, lint, type-check) after each iteration
+    /// that checks off tasks. Failing gates revert the newly checked tasks.
+    pub verify_gates: bool,
+
+    /// Test gate command (default: from detected stack, "" disables)
+    pub verify_test_cmd: Option<String>,
+
+    /// Lint gate command (default: from detected stack, "" disables)
+    pub verify_lint_cmd: Option<String>,
+
+    /// Type-check gate command (default: from detected stack, "" disables)
+    pub verify_typecheck_cmd: Option<String>,
+
+    /// Discard a failed iteration's working tree changes (timeout, unparseable
+    /// response, failed verification) by restoring a VCS snapshot.
+    pub rollback_failed_iterations: bool,
+
+    /// Archive each build iteration's prompts and raw Claude output in
+    /// `.rslph/runs/<run-id>/iter-NNN.jsonl`
+    pub archive_transcripts: bool,
+
+    /// Size limit of one iteration's transcript in bytes, timeout retries
+    /// included; output past it is dropped. 0 disables the limit.
+    pub transcript_max_bytes: u64,
+
+    /// Runs whose transcripts are kept; older transcripts are deleted when a
+    /// build starts. 0 keeps all of them.
+    pub transcript_keep_runs: u32,
+
+    /// Stop the build once total tokens (all kinds) reach this ceiling
+    pub max_total_tokens: Option<u64>,
+
+    /// Stop the build once estimated spend in USD reaches this ceiling
+    pub max_cost_usd: Option<f64>,
+
+    /// Stop the build after an iteration that used this many tokens or more.
+    /// Checked when the iteration ends; the iteration itself is not cut short.
+    pub max_iteration_tokens: Option<u64>,
+
+    /// Iterations without progress (or repeats of the same failed attempt)
+    /// before the build counts as stalled. 0 (the default) disables stall
+    /// detection.
+    pub stall_threshold: u32,
+
+    /// What to do when the build stalls (stop, skip, escalate)
+    pub stall_policy: StallPolicy,
+
+    /// Model passed to Claude (`--model`) after an escalation
+    pub stall_model: Option<String>,
+
+    /// What to do when Claude's progress file breaks a guardrail rule
+    /// (off, warn, reject, repair)
+    pub guardrails: GuardrailAction,
+
+    /// Structural rules checked against the previous progress file
+    pub guardrail_rules: Vec<GuardrailRule>,
+
+    /// Model for planning (`rslph plan`). None uses the Claude CLI default.
+    pub plan_model: Option<String>,
+
+    /// Model for adaptive planning personas and project naming
+    pub persona_model: Option<String>,
+
+    /// Model for build iterations
+    pub build_model: Option<String>,
+
+    /// Stronger models to escalate a build task to, in order
+    pub build_model_escalation: Vec<String>,
+
+    /// Unfinished iterations on the same task before moving one step up
+    /// `build_model_escalation`. 0 disables escalation.
+    pub escalate_after: u32,
+
+    /// Model for test discovery (`discover_run_script` in evals)
+    pub discovery_model: Option<String>,
+
+    /// Lifecycle hook commands (`[hooks]` table)
+    pub hooks: HookConfig,
+
+    /// Per-model token prices (USD per million tokens), keyed by model ID prefix.
+    /// Entries override or extend the built-in pricing table.
+    pub pricing: BTreeMap<String, ModelPricing>,
 }
 
 impl Default for Config {
@@ -120,18 +248,54 @@ impl Default for Config {
         Self {
             claude_path: None, // Deprecated - use claude_cmd
             claude_cmd: ClaudeCommand::default(),
+            agent_backend: AgentKind::default(),
+            agent_cmd: None,
+            http_api: HttpApi::default(),
+            http_base_url: None,
+            http_api_key_env: None,
+            http_max_tokens: 8192,
+            http_max_turns: 50,
             max_iterations: 20,
             recent_threads: 5,
             notify_interval: 10,
             plan_prompt: None,
             build_prompt: None,
+            build_message_prompt: None,
+            test_discovery_prompt: None,
             notify_shell: "/bin/sh".to_string(),
+            notify_script: None,
+            notify_timeout: 30,
             tui_enabled: true,
             tui_recent_messages: 10,
             eval_dir,
             iteration_timeout: 600,
             timeout_retries: 3,
             prompt_mode: PromptMode::default(),
+            pause_suspends_subprocess: false,
+            verify_gates: false,
+            verify_test_cmd: None,
+            verify_lint_cmd: None,
+            verify_typecheck_cmd: None,
+            rollback_failed_iterations: false,
+            archive_transcripts: true,
+            transcript_max_bytes: 10 * 1024 * 1024,
+            transcript_keep_runs: 20,
+            max_total_tokens: None,
+            max_cost_usd: None,
+            max_iteration_tokens: None,
+            stall_threshold: 0,
+            stall_policy: StallPolicy::default(),
+            stall_model: None,
+            guardrails: GuardrailAction::default(),
+            guardrail_rules: default_rules(),
+            plan_model: None,
+            persona_model: None,
+            build_model: None,
+            build_model_escalation: Vec::new(),
+            escalate_after: 2,
+            discovery_model: None,
+            hooks: HookConfig::default(),
+            pricing: BTreeMap::new(),
         }
     }
 }
@@ -237,6 +401,20 @@ pub struct PartialConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub claude_path: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_backend: Option<AgentKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_cmd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_api: Option<HttpApi>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_api_key_env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_max_turns: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_iterations: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recent_threads: Option<u32>,
@@ -247,8 +425,16 @@ pub struct PartialConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub build_prompt: Option<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_message_prompt: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_discovery_prompt: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub notify_shell: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_script: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notify_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tui_enabled: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tui_recent_messages: Option<usize>,
@@ -260,6 +446,54 @@ pub struct PartialConfig {
     pub timeout_retries: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prompt_mode: Option<PromptMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause_suspends_subprocess: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_gates: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_test_cmd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_lint_cmd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_typecheck_cmd: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback_failed_iterations: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_transcripts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_max_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_keep_runs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_total_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_iteration_tokens: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stall_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stall_policy: Option<StallPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stall_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<GuardrailAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrail_rules: Option<Vec<GuardrailRule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persona_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_model_escalation: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalate_after: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discovery_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<HookConfig>,
 }
 
 #[cfg(test)]
@@ -275,17 +509,47 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.claude_cmd.command, "claude");
         assert_eq!(config.claude_cmd.base_args.len(), 0);
+        assert_eq!(config.agent_backend, AgentKind::Claude);
+        assert!(config.agent_cmd.is_none());
+        assert_eq!(config.http_api, HttpApi::Anthropic);
+        assert_eq!(config.http_max_tokens, 8192);
+        assert_eq!(config.http_max_turns, 50);
         assert_eq!(config.max_iterations, 20);
         assert_eq!(config.recent_threads, 5);
         assert_eq!(config.notify_interval, 10);
         assert!(config.plan_prompt.is_none());
         assert!(config.build_prompt.is_none());
+        assert!(config.build_message_prompt.is_none());
+        assert!(config.test_discovery_prompt.is_none());
         assert_eq!(config.notify_shell, "/bin/sh");
+        assert!(config.notify_script.is_none());
+        assert_eq!(config.notify_timeout, 30);
         assert!(config.tui_enabled);
         assert_eq!(config.tui_recent_messages, 10);
         assert_eq!(config.iteration_timeout, 600);
         assert_eq!(config.timeout_retries, 3);
         assert_eq!(config.prompt_mode, PromptMode::Basic);
+        assert!(!config.pause_suspends_subprocess);
+        assert!(!config.verify_gates);
+        assert!(config.verify_test_cmd.is_none());
+        assert!(!config.rollback_failed_iterations);
+        assert!(config.archive_transcripts);
+        assert_eq!(config.transcript_max_bytes, 10 * 1024 * 1024);
+        assert_eq!(config.transcript_keep_runs, 20);
+        assert!(config.max_total_tokens.is_none());
+        assert!(config.max_cost_usd.is_none());
+        assert_eq!(config.stall_threshold, 0);
+        assert_eq!(config.stall_policy, StallPolicy::Stop);
+        assert!(config.stall_model.is_none());
+        assert_eq!(config.guardrails, GuardrailAction::Warn);
+        assert_eq!(config.guardrail_rules, default_rules());
+        assert!(config.plan_model.is_none());
+        assert!(config.build_model.is_none());
+        assert!(config.build_model_escalation.is_empty());
+        assert_eq!(config.escalate_after, 2);
+        assert!(config.discovery_model.is_none());
+        assert_eq!(config.hooks, HookConfig::default());
+        assert!(config.pricing.is_empty());
         // eval_dir should end with .rslph/evals
         assert!(
             config.eval_dir.ends_with(".rslph/evals"),
@@ -313,6 +577,26 @@ mod tests {
         std::env::remove_var("RSLPH_MAX_ITERATIONS");
     }
 
+    #[test]
+    fn test_load_pricing_overrides_from_file() {
+        let _guard = ENV_MUTEX.lock().unwrap();
+        let dir = tempfile::TempDir::new().expect("temp dir");
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "max_iterations = 5\n\n[pricing.claude-sonnet-4]\ninput = 2.0\noutput = 10.0\ncache_write = 2.5\ncache_read = 0.2\n",
+        )
+        .expect("write config");
+
+        let config = Config::load(Some(&path)).expect("Should load");
+        let sonnet = config
+            .pricing
+            .get("claude-sonnet-4")
+            .expect("pricing entry");
+        assert_eq!(sonnet.input, 2.0);
+        assert_eq!(sonnet.cache_read, 0.2);
+    }
+
     #[test]
     fn test_default_path_is_xdg_compliant() {
         let path = Config::default_path();
@@ -12,6 +12,8 @@ pub enum VcsError {
     CommitFailed(String),
     /// VCS detection failed.
     Detection(String),
+    /// A merge stopped on conflicting paths and was aborted.
+    MergeConflict(Vec<String>),
 }
 
 impl fmt::Display for VcsError {
@@ -23,6 +25,9 @@ impl fmt::Display for VcsError {
             VcsError::NothingToCommit => write!(f, "Nothing to commit"),
             VcsError::CommitFailed(msg) => write!(f, "Commit failed: {}", msg),
             VcsError::Detection(msg) => write!(f, "VCS detection failed: {}", msg),
+            VcsError::MergeConflict(paths) => {
+                write!(f, "Merge conflict in {}", paths.join(", "))
+            }
         }
     }
 }
@@ -51,6 +56,15 @@ pub enum RslphError {
 
     #[error("VCS error: {0}")]
     Vcs(#[from] VcsError),
+
+    #[error("Build journal error: {0}")]
+    Journal(String),
+
+    #[error("Prompt error: {0}")]
+    Prompt(String),
+
+    #[error("Task error: {0}")]
+    Task(String),
 }
 
 impl From<figment::Error> for RslphError {
@@ -15,14 +15,17 @@ use tokio_util::sync::CancellationToken;
 /// Parameters: (current_iteration, max_iterations)
 pub type ProgressCallback = Arc<dyn Fn(u32, u32) + Send + Sync>;
 
-use crate::build::run_build_command;
+use crate::build::{run_build_command, Budget};
 use crate::build::tokens::{format_tokens, TokenUsage};
 use crate::config::Config;
+use crate::pricing::format_cost;
 use crate::eval::{load_test_cases, TestResults, TestRunner};
-use crate::planning::run_plan_command;
+use crate::planning::{detect_stack, run_plan_command};
 use crate::progress::ProgressFile;
-use crate::prompts::{test_discovery_prompt, PromptMode};
-use crate::subprocess::{build_claude_args, ClaudeRunner, OutputLine, StreamResponse};
+use crate::prompts::{load_test_discovery_prompt, render, PromptMode, PromptVars};
+use crate::subprocess::{
+    build_claude_args, model_args, AgentKind, ClaudeRunner, OutputLine, StreamResponse,
+};
 use crate::tui::run_dashboard_tui;
 
 use super::parallel::{run_parallel_evals, TrialEvent, TrialResult as ParallelTrialResult};
@@ -99,8 +102,13 @@ pub async fn run_eval_command(
         print_statistics(&statistics, trials);
 
         // Save multi-trial results to JSON file (EVAL-08)
-        let result_path =
-            save_multi_trial_result(&config.eval_dir, &project, &trial_results, &statistics)?;
+        let result_path = save_multi_trial_result(
+            &config.eval_dir,
+            &project,
+            config.agent_backend,
+            &trial_results,
+            &statistics,
+        )?;
         println!("\nResults saved to: {}", result_path.display());
     }
 
@@ -251,7 +259,14 @@ async fn run_parallel_eval_mode(
     // Save multi-mode results to JSON
     let all_eval_results: Vec<EvalResult> = results.iter().map(|r| r.eval_result.clone()).collect();
     let _combined_stats = compute_statistics(&all_eval_results);
-    let result_path = save_multi_mode_result(&config.eval_dir, project, modes, &results, &by_mode)?;
+    let result_path = save_multi_mode_result(
+        &config.eval_dir,
+        project,
+        config.agent_backend,
+        modes,
+        &results,
+        &by_mode,
+    )?;
     println!("Results saved to: {}", result_path.display());
 
     // Return last result for backward compatibility
@@ -266,6 +281,7 @@ async fn run_parallel_eval_mode(
 fn save_multi_mode_result(
     eval_dir: &Path,
     project: &str,
+    backend: AgentKind,
     modes: &[PromptMode],
     results: &[ParallelTrialResult],
     by_mode: &std::collections::HashMap<PromptMode, Vec<&ParallelTrialResult>>,
@@ -302,6 +318,7 @@ fn save_multi_mode_result(
 
     let result = SerializableMultiModeResult {
         project: project.to_string(),
+        backend,
         timestamp: Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
         modes: modes.iter().map(|m| m.to_string()).collect(),
         total_trials: results.len() as u32,
@@ -319,6 +336,9 @@ fn save_multi_mode_result(
 #[derive(Debug, Serialize, Deserialize)]
 struct SerializableMultiModeResult {
     project: String,
+    /// Agent backend that ran the builds (Claude in older results).
+    #[serde(default)]
+    backend: AgentKind,
     timestamp: String,
     modes: Vec<String>,
     total_trials: u32,
@@ -440,33 +460,42 @@ async fn run_single_trial(
     .await?;
 
     println!(
-        "Planning tokens: In: {} | Out: {} | CacheW: {} | CacheR: {}",
+        "Planning tokens: In: {} | Out: {} | CacheW: {} | CacheR: {} | Cost: {}",
         format_tokens(plan_tokens.input_tokens),
         format_tokens(plan_tokens.output_tokens),
         format_tokens(plan_tokens.cache_creation_input_tokens),
         format_tokens(plan_tokens.cache_read_input_tokens),
+        format_cost(plan_tokens.cost_usd),
     );
 
     // Step 7: Run build command and capture tokens
+    // Budget limits apply per trial, so the build gets what planning left over
     println!("\n=== BUILD PHASE ===\n");
+    let mut build_config = config.clone();
+    Budget::from_config(config)
+        .remaining_after(&plan_tokens)
+        .apply_to(&mut build_config);
     let build_tokens = run_build_command(
         progress_path.clone(),
         false, // not once
         false, // not dry-run
+        false, // not resuming
         mode,
         false, // no_dsp
-        config,
+        &build_config,
         cancel_token.clone(),
         progress_callback,
+        None,
     )
     .await?;
 
     println!(
-        "Build tokens: In: {} | Out: {} | CacheW: {} | CacheR: {}",
+        "Build tokens: In: {} | Out: {} | CacheW: {} | CacheR: {} | Cost: {}",
         format_tokens(build_tokens.input_tokens),
         format_tokens(build_tokens.output_tokens),
         format_tokens(build_tokens.cache_creation_input_tokens),
         format_tokens(build_tokens.cache_read_input_tokens),
+        format_cost(build_tokens.cost_usd),
     );
 
     // Step 8: Aggregate tokens from plan + build
@@ -477,6 +506,7 @@ async fn run_single_trial(
             + build_tokens.cache_creation_input_tokens,
         cache_read_input_tokens: plan_tokens.cache_read_input_tokens
             + build_tokens.cache_read_input_tokens,
+        cost_usd: plan_tokens.cost_usd + build_tokens.cost_usd,
     };
 
     // Step 9: Collect metrics from progress file
@@ -623,6 +653,7 @@ pub async fn run_retest_command(
             output_tokens: existing_result.tokens.output,
             cache_creation_input_tokens: existing_result.tokens.cache_creation,
             cache_read_input_tokens: existing_result.tokens.cache_read,
+            cost_usd: existing_result.tokens.cost_usd,
         },
         iterations: existing_result.iterations,
         workspace_path: Some(workspace.clone()),
@@ -664,6 +695,8 @@ struct StoredTokens {
     output: u64,
     cache_creation: u64,
     cache_read: u64,
+    #[serde(default)]
+    cost_usd: f64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -691,6 +724,9 @@ struct SerializableTokens {
     output: u64,
     cache_creation: u64,
     cache_read: u64,
+    /// Cost in USD (absent in results written before pricing was tracked).
+    #[serde(default)]
+    cost_usd: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -704,6 +740,9 @@ struct SerializableTestResults {
 #[derive(Debug, Serialize, Deserialize)]
 struct SerializableMultiTrialResult {
     project: String,
+    /// Agent backend that ran the builds (Claude in older results).
+    #[serde(default)]
+    backend: AgentKind,
     timestamp: String,
     trial_count: u32,
     trials: Vec<SerializableTrialSummary>,
@@ -728,11 +767,13 @@ struct SerializableStatistics {
     elapsed_secs: SerializableStatSummary,
     total_input_tokens: SerializableStatSummary,
     total_output_tokens: SerializableStatSummary,
+    #[serde(default)]
+    total_cost_usd: SerializableStatSummary,
     iterations: SerializableStatSummary,
 }
 
 /// Serializable stat summary for JSON output.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct SerializableStatSummary {
     mean: f64,
     variance: f64,
@@ -754,6 +795,7 @@ fn save_result_json(working_dir: &Path, result: &EvalResult) -> color_eyre::Resu
             output: result.total_tokens.output_tokens,
             cache_creation: result.total_tokens.cache_creation_input_tokens,
             cache_read: result.total_tokens.cache_read_input_tokens,
+            cost_usd: result.total_tokens.cost_usd,
         },
         test_results: result
             .test_results
@@ -777,6 +819,7 @@ fn save_result_json(working_dir: &Path, result: &EvalResult) -> color_eyre::Resu
 /// - Elapsed time in seconds
 /// - Total input tokens
 /// - Total output tokens
+/// - Total cost in USD
 /// - Number of build iterations
 fn compute_statistics(trials: &[EvalResult]) -> TrialStatistics {
     // Extract pass rates (only from trials with test results)
@@ -800,6 +843,8 @@ fn compute_statistics(trials: &[EvalResult]) -> TrialStatistics {
         .map(|t| t.total_tokens.output_tokens as f64)
         .collect();
 
+    let costs: Vec<f64> = trials.iter().map(|t| t.total_tokens.cost_usd).collect();
+
     // Extract iteration counts
     let iterations: Vec<f64> = trials.iter().map(|t| t.iterations as f64).collect();
 
@@ -808,6 +853,7 @@ fn compute_statistics(trials: &[EvalResult]) -> TrialStatistics {
         elapsed_secs: StatSummary::from_values(&elapsed_secs),
         total_input_tokens: StatSummary::from_values(&input_tokens),
         total_output_tokens: StatSummary::from_values(&output_tokens),
+        total_cost_usd: StatSummary::from_values(&costs),
         iterations: StatSummary::from_values(&iterations),
     }
 }
@@ -853,6 +899,13 @@ fn print_statistics(stats: &TrialStatistics, trial_count: u32) {
         format_tokens(stats.total_output_tokens.min as u64),
         format_tokens(stats.total_output_tokens.max as u64),
     );
+    println!(
+        "Cost:           Mean: {}  Std Dev: {}  Min: {}  Max: {}",
+        format_cost(stats.total_cost_usd.mean),
+        format_cost(stats.total_cost_usd.std_dev()),
+        format_cost(stats.total_cost_usd.min),
+        format_cost(stats.total_cost_usd.max),
+    );
 
     // Iterations
     println!(
@@ -873,6 +926,7 @@ fn print_statistics(stats: &TrialStatistics, trial_count: u32) {
 ///
 /// * `eval_dir` - Directory to save the results file
 /// * `project` - Project name
+/// * `backend` - Agent backend that ran the builds
 /// * `trials` - Trial results to save
 /// * `statistics` - Computed statistics across trials
 ///
@@ -883,6 +937,7 @@ fn print_statistics(stats: &TrialStatistics, trial_count: u32) {
 fn save_multi_trial_result(
     eval_dir: &Path,
     project: &str,
+    backend: AgentKind,
     trials: &[EvalResult],
     statistics: &TrialStatistics,
 ) -> color_eyre::Result<PathBuf> {
@@ -895,7 +950,7 @@ fn save_multi_trial_result(
     let path = eval_dir.join(&filename);
 
     // Convert to serializable format
-    let serializable = convert_to_serializable(project, trials, statistics);
+    let serializable = convert_to_serializable(project, backend, trials, statistics);
 
     // Write pretty-printed JSON
     let json = serde_json::to_string_pretty(&serializable)?;
@@ -907,11 +962,13 @@ fn save_multi_trial_result(
 /// Convert multi-trial results to serializable format.
 fn convert_to_serializable(
     project: &str,
+    backend: AgentKind,
     trials: &[EvalResult],
     statistics: &TrialStatistics,
 ) -> SerializableMultiTrialResult {
     SerializableMultiTrialResult {
         project: project.to_string(),
+        backend,
         timestamp: Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
         trial_count: trials.len() as u32,
         trials: trials.iter().map(convert_trial_to_serializable).collect(),
@@ -930,6 +987,7 @@ fn convert_trial_to_serializable(trial: &EvalResult) -> SerializableTrialSummary
             output: trial.total_tokens.output_tokens,
             cache_creation: trial.total_tokens.cache_creation_input_tokens,
             cache_read: trial.total_tokens.cache_read_input_tokens,
+            cost_usd: trial.total_tokens.cost_usd,
         },
         test_results: trial
             .test_results
@@ -954,6 +1012,7 @@ fn convert_statistics_to_serializable(stats: &TrialStatistics) -> SerializableSt
         elapsed_secs: convert_stat_summary(&stats.elapsed_secs),
         total_input_tokens: convert_stat_summary(&stats.total_input_tokens),
         total_output_tokens: convert_stat_summary(&stats.total_output_tokens),
+        total_cost_usd: convert_stat_summary(&stats.total_cost_usd),
         iterations: convert_stat_summary(&stats.iterations),
     }
 }
@@ -997,6 +1056,7 @@ fn load_multi_trial_result(path: &Path) -> color_eyre::Result<SerializableMultiT
 /// - Execution time (lower is better)
 /// - Input tokens (lower is better)
 /// - Output tokens (lower is better)
+/// - Cost (lower is better)
 ///
 /// # Arguments
 ///
@@ -1013,14 +1073,16 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
 
     println!("Comparing results:");
     println!(
-        "  Baseline:   {} ({} trials)",
+        "  Baseline:   {} ({} trials, {})",
         file1.display(),
-        result1.trial_count
+        result1.trial_count,
+        result1.backend
     );
     println!(
-        "  Comparison: {} ({} trials)",
+        "  Comparison: {} ({} trials, {})",
         file2.display(),
-        result2.trial_count
+        result2.trial_count,
+        result2.backend
     );
     println!();
 
@@ -1031,6 +1093,7 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
         result1.statistics.pass_rate.mean * 100.0,
         result2.statistics.pass_rate.mean * 100.0,
         "%",
+        1,
         true, // higher is better
     );
 
@@ -1040,6 +1103,7 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
         result1.statistics.elapsed_secs.mean,
         result2.statistics.elapsed_secs.mean,
         "s",
+        1,
         false, // lower is better
     );
 
@@ -1049,6 +1113,7 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
         result1.statistics.total_input_tokens.mean,
         result2.statistics.total_input_tokens.mean,
         "",
+        1,
         false, // lower is better
     );
 
@@ -1058,6 +1123,17 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
         result1.statistics.total_output_tokens.mean,
         result2.statistics.total_output_tokens.mean,
         "",
+        1,
+        false, // lower is better
+    );
+
+    // Cost: lower is better
+    print_delta(
+        "Cost (USD)",
+        result1.statistics.total_cost_usd.mean,
+        result2.statistics.total_cost_usd.mean,
+        "",
+        2,
         false, // lower is better
     );
 
@@ -1074,8 +1150,16 @@ pub fn run_compare_command(file1: PathBuf, file2: PathBuf) -> color_eyre::Result
 /// * `baseline` - Baseline value
 /// * `comparison` - Comparison value
 /// * `unit` - Unit suffix (e.g., "%", "s", "")
+/// * `decimals` - Decimal places shown for values and delta
 /// * `higher_is_better` - If true, positive delta shows ^, else shows v
-fn print_delta(name: &str, baseline: f64, comparison: f64, unit: &str, higher_is_better: bool) {
+fn print_delta(
+    name: &str,
+    baseline: f64,
+    comparison: f64,
+    unit: &str,
+    decimals: usize,
+    higher_is_better: bool,
+) {
     let delta = comparison - baseline;
     let percent = if baseline.abs() > 0.0001 {
         (delta / baseline) * 100.0
@@ -1097,13 +1181,16 @@ fn print_delta(name: &str, baseline: f64, comparison: f64, unit: &str, higher_is
     let sign = if delta >= 0.0 { "+" } else { "" };
 
     println!(
-        "{}: {:.1}{} -> {:.1}{} ({}{:.1}{}, {}{}%)",
+        "{}: {:.*}{} -> {:.*}{} ({}{:.*}{}, {}{}%)",
         name,
+        decimals,
         baseline,
         unit,
+        decimals,
         comparison,
         unit,
         arrow,
+        decimals,
         delta.abs(),
         unit,
         sign,
@@ -1340,16 +1427,18 @@ async fn discover_run_script(
     let context = build_workspace_context(working_dir)?;
 
     // Prepare Claude args
-    let system_prompt = test_discovery_prompt();
-    let args = vec![
+    let system_prompt = render(
+        &load_test_discovery_prompt(config)?,
+        &PromptVars::new().with("stack", detect_stack(working_dir).to_summary()),
+    );
+    let mut args = vec![
         "-p".to_string(),
         "--verbose".to_string(),
         "--output-format".to_string(),
         "stream-json".to_string(),
-        "--system-prompt".to_string(),
-        system_prompt.to_string(),
-        context,
     ];
+    args.extend(model_args(config.discovery_model.as_deref()));
+    args.extend(["--system-prompt".to_string(), system_prompt, context]);
 
     // Spawn Claude
     let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, false);
@@ -1789,6 +1878,7 @@ version = "0.1.0"
                 output_tokens: 500,
                 cache_creation_input_tokens: 100,
                 cache_read_input_tokens: 50,
+                cost_usd: 0.25,
             },
             iterations: 5,
             workspace_path: Some(dir.path().to_path_buf()),
@@ -1814,6 +1904,7 @@ version = "0.1.0"
         assert_eq!(json["iterations"], 5);
         assert_eq!(json["tokens"]["input"], 1000);
         assert_eq!(json["tokens"]["output"], 500);
+        assert_eq!(json["tokens"]["cost_usd"], 0.25);
         assert_eq!(json["test_results"]["passed"], 3);
         assert_eq!(json["test_results"]["total"], 5);
         assert_eq!(json["test_results"]["pass_rate"], 60.0);
@@ -1834,6 +1925,7 @@ version = "0.1.0"
                 output_tokens: 100,
                 cache_creation_input_tokens: 0,
                 cache_read_input_tokens: 0,
+                cost_usd: 0.05,
             },
             iterations: 3,
             workspace_path: Some(dir.path().to_path_buf()),
@@ -1969,6 +2061,7 @@ version = "0.1.0"
                     output_tokens: 500,
                     cache_creation_input_tokens: 0,
                     cache_read_input_tokens: 0,
+                    cost_usd: 0.25,
                 },
                 iterations: 3,
                 workspace_path: None,
@@ -1988,6 +2081,7 @@ version = "0.1.0"
                     output_tokens: 600,
                     cache_creation_input_tokens: 0,
                     cache_read_input_tokens: 0,
+                    cost_usd: 0.30,
                 },
                 iterations: 4,
                 workspace_path: None,
@@ -2007,6 +2101,7 @@ version = "0.1.0"
                     output_tokens: 400,
                     cache_creation_input_tokens: 0,
                     cache_read_input_tokens: 0,
+                    cost_usd: 0.20,
                 },
                 iterations: 2,
                 workspace_path: None,
@@ -2032,6 +2127,10 @@ version = "0.1.0"
         assert!((stats.elapsed_secs.min - 10.0).abs() < 0.001);
         assert!((stats.elapsed_secs.max - 15.0).abs() < 0.001);
 
+        // Verify cost ($0.25, $0.30, $0.20) -> mean = $0.25
+        assert!((stats.total_cost_usd.mean - 0.25).abs() < 0.001);
+        assert!((stats.total_cost_usd.max - 0.30).abs() < 0.001);
+
         // Verify iterations (3, 4, 2) -> mean = 3.0
         assert_eq!(stats.iterations.count, 3);
         assert!((stats.iterations.mean - 3.0).abs() < 0.001);
@@ -2064,6 +2163,7 @@ version = "0.1.0"
                 output_tokens: 500,
                 cache_creation_input_tokens: 0,
                 cache_read_input_tokens: 0,
+                cost_usd: 0.25,
             },
             iterations: 3,
             workspace_path: None,
@@ -2098,6 +2198,7 @@ version = "0.1.0"
                     output_tokens: 500,
                     cache_creation_input_tokens: 100,
                     cache_read_input_tokens: 50,
+                    cost_usd: 0.25,
                 },
                 iterations: 3,
                 workspace_path: Some(PathBuf::from("/tmp/workspace1")),
@@ -2117,6 +2218,7 @@ version = "0.1.0"
                     output_tokens: 600,
                     cache_creation_input_tokens: 120,
                     cache_read_input_tokens: 60,
+                    cost_usd: 0.30,
                 },
                 iterations: 4,
                 workspace_path: Some(PathBuf::from("/tmp/workspace2")),
@@ -2132,8 +2234,14 @@ version = "0.1.0"
         let statistics = compute_statistics(&trials);
 
         // Save to temp directory
-        let result_path = save_multi_trial_result(dir.path(), "calculator", &trials, &statistics)
-            .expect("save multi-trial result");
+        let result_path = save_multi_trial_result(
+            dir.path(),
+            "calculator",
+            AgentKind::Command,
+            &trials,
+            &statistics,
+        )
+        .expect("save multi-trial result");
 
         // Verify file exists
         assert!(result_path.exists(), "JSON file should exist");
@@ -2157,6 +2265,7 @@ version = "0.1.0"
 
         // Check top-level fields
         assert_eq!(json["project"], "calculator");
+        assert_eq!(json["backend"], "command");
         assert_eq!(json["trial_count"], 2);
         assert!(json["timestamp"].as_str().is_some());
 
@@ -2177,6 +2286,7 @@ version = "0.1.0"
         assert!(stats["elapsed_secs"]["mean"].as_f64().is_some());
         assert!(stats["total_input_tokens"]["mean"].as_f64().is_some());
         assert!(stats["total_output_tokens"]["mean"].as_f64().is_some());
+        assert!(stats["total_cost_usd"]["mean"].as_f64().is_some());
         assert!(stats["iterations"]["mean"].as_f64().is_some());
     }
 
@@ -2220,12 +2330,17 @@ version = "0.1.0"
 
         let loaded = load_multi_trial_result(&result_path).expect("load");
         assert_eq!(loaded.project, "calculator");
+        // Results saved before agent backends were configurable ran on Claude
+        assert_eq!(loaded.backend, AgentKind::Claude);
         assert_eq!(loaded.trial_count, 2);
         assert_eq!(loaded.trials.len(), 2);
         assert_eq!(loaded.trials[0].trial_num, 1);
         assert_eq!(loaded.trials[1].trial_num, 2);
         assert!((loaded.statistics.pass_rate.mean - 0.9).abs() < 0.001);
         assert!((loaded.statistics.elapsed_secs.mean - 11.0).abs() < 0.001);
+        // Results saved before cost tracking load with zero cost
+        assert_eq!(loaded.statistics.total_cost_usd.count, 0);
+        assert_eq!(loaded.trials[0].tokens.cost_usd, 0.0);
     }
 
     #[test]
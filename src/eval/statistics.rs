@@ -78,6 +78,8 @@ pub struct TrialStatistics {
     pub total_input_tokens: StatSummary,
     /// Total output tokens consumed
     pub total_output_tokens: StatSummary,
+    /// Total cost in USD
+    pub total_cost_usd: StatSummary,
     /// Number of build iterations
     pub iterations: StatSummary,
 }
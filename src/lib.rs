@@ -4,6 +4,7 @@ pub mod config;
 pub mod error;
 pub mod eval;
 pub mod planning;
+pub mod pricing;
 pub mod progress;
 pub mod prompts;
 pub mod subprocess;
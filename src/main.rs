@@ -3,12 +3,16 @@ use std::path::Path;
 use std::time::Duration;
 
 use clap::Parser;
-use rslph::build::run_build_command;
 use rslph::build::tokens::format_tokens;
-use rslph::cli::{Cli, Commands};
+use rslph::build::{
+    format_task_list, run_build_command, run_lint_command, run_parallel_build, run_replay_command,
+    run_status_command, run_task_edit, EventSink, TaskEdit,
+};
+use rslph::cli::{Cli, Commands, ProgressCommands, TaskCommands};
 use rslph::eval::{run_compare_command, run_eval_command, run_retest_command};
 use rslph::planning::run_plan_command;
-use rslph::subprocess::setup_ctrl_c_handler;
+use rslph::progress::ProgressFile;
+use rslph::subprocess::{setup_ctrl_c_handler, HttpAgent, Workspace};
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
@@ -102,12 +106,30 @@ async fn main() -> color_eyre::Result<()> {
             plan,
             once,
             dry_run,
+            resume,
+            parallel,
+            events,
         } => {
             // Set up Ctrl+C handling
             let cancel_token = setup_ctrl_c_handler();
 
+            let events = match events {
+                Some(path) => Some(EventSink::open(&path).map_err(|e| {
+                    color_eyre::eyre::eyre!(
+                        "Failed to open event stream '{}': {}",
+                        path.display(),
+                        e
+                    )
+                })?),
+                None => None,
+            };
+
             // Determine if TUI will be used - if so, suppress startup messages
-            let use_tui = config.tui_enabled && !dry_run;
+            // (parallel builds and `--events -` always run headless)
+            let use_tui = config.tui_enabled
+                && !dry_run
+                && parallel == 1
+                && !events.as_ref().is_some_and(EventSink::is_stdout);
 
             if !use_tui {
                 println!("Building: {}", plan.display());
@@ -117,20 +139,42 @@ async fn main() -> color_eyre::Result<()> {
                 if dry_run {
                     println!("Mode: dry run (--dry-run)");
                 }
+                if resume {
+                    println!("Mode: resume last run (--resume)");
+                }
+                if parallel > 1 {
+                    println!("Mode: up to {} tasks in parallel (--parallel)", parallel);
+                }
             }
 
-            match run_build_command(
-                plan,
-                once,
-                dry_run,
-                config.prompt_mode,
-                cli.no_dsp,
-                &config,
-                cancel_token,
-                None,
-            )
-            .await
-            {
+            let result = if parallel > 1 {
+                run_parallel_build(
+                    plan,
+                    parallel as usize,
+                    config.prompt_mode,
+                    cli.no_dsp,
+                    &config,
+                    cancel_token,
+                    events,
+                )
+                .await
+            } else {
+                run_build_command(
+                    plan,
+                    once,
+                    dry_run,
+                    resume,
+                    config.prompt_mode,
+                    cli.no_dsp,
+                    &config,
+                    cancel_token,
+                    None,
+                    events,
+                )
+                .await
+            };
+
+            match result {
                 Ok(_tokens) => {
                     // Tokens already printed by build command
                     if !use_tui {
@@ -262,6 +306,16 @@ async fn main() -> color_eyre::Result<()> {
                 }
             }
         }
+        Commands::Replay {
+            run_dir,
+            speed,
+            from,
+        } => {
+            if let Err(e) = run_replay_command(run_dir, speed, from, &config).await {
+                eprintln!("Replay failed: {}", e);
+                std::process::exit(1);
+            }
+        }
         Commands::Compare { file1, file2 } => match run_compare_command(file1, file2) {
             Ok(()) => {}
             Err(e) => {
@@ -269,6 +323,93 @@ async fn main() -> color_eyre::Result<()> {
                 std::process::exit(1);
             }
         },
+        Commands::Status { plan, json, log } => {
+            if let Err(e) = run_status_command(&plan, log, json) {
+                eprintln!("Status failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Commands::Lint { plan, fix } => match run_lint_command(&plan, fix) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => {
+                eprintln!("Lint failed: {}", e);
+                std::process::exit(1);
+            }
+        },
+        Commands::Task { file, command } => {
+            let edit = |edit| run_task_edit(&file, edit).map(|message| format!("{}\n", message));
+            let result = match command {
+                TaskCommands::List => ProgressFile::load(&file).map(|p| format_task_list(&p)),
+                TaskCommands::Add { text, phase } => edit(TaskEdit::Add { text, phase }),
+                TaskCommands::Done { task } => edit(TaskEdit::Done(task)),
+                TaskCommands::Undo { task } => edit(TaskEdit::Undo(task)),
+                TaskCommands::Move { task, to } => edit(TaskEdit::Move { task, to }),
+                TaskCommands::Rm { task, force } => edit(TaskEdit::Remove { task, force }),
+            };
+            match result {
+                Ok(output) => print!("{}", output),
+                Err(e) => {
+                    eprintln!("Task command failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Progress {
+            command:
+                ProgressCommands::Convert {
+                    input,
+                    output,
+                    force,
+                },
+        } => {
+            if output.exists() && !force {
+                eprintln!(
+                    "Convert failed: {} already exists (use --force to overwrite)",
+                    output.display()
+                );
+                std::process::exit(1);
+            }
+            match ProgressFile::load(&input).and_then(|progress| progress.write(&output)) {
+                Ok(()) => println!("Converted {} to {}", input.display(), output.display()),
+                Err(e) => {
+                    eprintln!("Convert failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::HttpAgent {
+            api,
+            base_url,
+            api_key_env,
+            model,
+            max_tokens,
+            max_turns,
+            system_prompt,
+            user_input,
+        } => {
+            let agent = HttpAgent {
+                api,
+                base_url,
+                api_key: std::env::var(&api_key_env).ok().filter(|k| !k.is_empty()),
+                model,
+                max_tokens,
+                max_turns,
+            };
+            let workspace = Workspace::new(&std::env::current_dir()?)?;
+            if let Err(e) = agent
+                .run(
+                    &system_prompt,
+                    &user_input,
+                    &workspace,
+                    &mut std::io::stdout(),
+                )
+                .await
+            {
+                eprintln!("HTTP agent failed: {}", e);
+                std::process::exit(1);
+            }
+        }
     }
 
     Ok(())
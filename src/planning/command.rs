@@ -1,6 +1,9 @@
 //! Planning command handler.
 //!
 //! Executes Claude in headless mode to transform user ideas into structured progress files.
+//!
+//! With another `agent_backend`, planning runs headless through that backend;
+//! the TUI and adaptive flows rely on Claude CLI sessions.
 
 use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
@@ -15,9 +18,13 @@ use crate::error::RslphError;
 use crate::planning::{
     assess_vagueness, detect_stack, REQUIREMENTS_CLARIFIER_PERSONA, TESTING_STRATEGIST_PERSONA,
 };
+use crate::pricing::{format_cost, PricingTable};
 use crate::progress::ProgressFile;
-use crate::prompts::{get_plan_prompt_for_mode, PromptMode};
-use crate::subprocess::{build_claude_args, ClaudeRunner, OutputLine, StreamEvent, StreamResponse};
+use crate::prompts::{load_plan_prompt, render, PromptMode, PromptVars};
+use crate::subprocess::{
+    agent_backend, build_claude_args, model_args, AgentKind, AgentRequest, AgentResponse,
+    ClaudeRunner, OutputLine, StreamEvent, StreamResponse,
+};
 use crate::tui::plan_tui::{run_plan_tui, PlanTuiEvent};
 
 /// Run the planning command.
@@ -50,6 +57,15 @@ pub async fn run_plan_command(
     cancel_token: CancellationToken,
     timeout: Duration,
 ) -> color_eyre::Result<(PathBuf, TokenUsage)> {
+    // Adaptive Q&A resumes Claude sessions, which other backends don't have
+    if adaptive && config.agent_backend != AgentKind::Claude {
+        return Err(RslphError::Subprocess(format!(
+            "Adaptive planning needs the Claude CLI; agent_backend is \"{}\"",
+            config.agent_backend
+        ))
+        .into());
+    }
+
     // Adaptive mode has its own flow
     if adaptive {
         return run_adaptive_planning(
@@ -64,8 +80,8 @@ pub async fn run_plan_command(
         .await;
     }
 
-    // Check if TUI is enabled
-    if config.tui_enabled {
+    // Check if TUI is enabled (it streams Claude's questions, so Claude only)
+    if config.tui_enabled && config.agent_backend == AgentKind::Claude {
         return run_tui_planning(
             input,
             adaptive,
@@ -94,9 +110,9 @@ pub async fn run_plan_command(
 
 /// Run headless planning mode without TUI display.
 ///
-/// This mode is used when TUI is disabled (config.tui_enabled = false).
-/// It processes Claude's output without any terminal UI, making it suitable
-/// for CI environments and automated testing.
+/// This mode is used when TUI is disabled (config.tui_enabled = false) or the
+/// agent backend isn't Claude. It processes the agent's output without any
+/// terminal UI, making it suitable for CI environments and automated testing.
 async fn run_headless_planning(
     input: &str,
     mode: PromptMode,
@@ -112,7 +128,10 @@ async fn run_headless_planning(
     let stack = detect_stack(working_dir);
 
     // Step 2: Get the planning prompt for the specified mode
-    let system_prompt = get_plan_prompt_for_mode(mode);
+    let system_prompt = render(
+        &load_plan_prompt(config, mode)?,
+        &PromptVars::new().with("stack", stack.to_summary()),
+    );
 
     // Step 3: Build user input with stack context
     let full_input = format!(
@@ -121,31 +140,30 @@ async fn run_headless_planning(
         input
     );
 
-    // Step 4: Build Claude CLI args for streaming mode
-    let args = vec![
-        "-p".to_string(),
-        "--verbose".to_string(),
-        "--output-format".to_string(),
-        "stream-json".to_string(),
-        "--system-prompt".to_string(),
-        system_prompt,
-        full_input.clone(),
-    ];
-
-    // Step 5: Spawn Claude
-    let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, no_dsp);
-    let mut runner =
-        ClaudeRunner::spawn_interactive(&config.claude_cmd.command, &combined_args, working_dir)
-            .await
-            .map_err(|e| RslphError::Subprocess(format!("Failed to spawn claude: {}", e)))?;
-
-    debug!(pid = ?runner.id(), "Spawned Claude for headless planning");
+    // Step 4: Build the agent command line
+    let backend = agent_backend(config, no_dsp)?;
+    let invocation = backend.invocation(&AgentRequest {
+        system_prompt: &system_prompt,
+        user_input: &full_input,
+        model: config.plan_model.as_deref(),
+    });
 
-    // Close stdin immediately for headless mode
-    runner.close_stdin();
-
-    // Step 6: Stream and process events without TUI
-    let mut stream_response = StreamResponse::new();
+    // Step 5: Spawn the agent
+    let mut runner = ClaudeRunner::spawn(&invocation.command, &invocation.args, working_dir)
+        .await
+        .map_err(|e| {
+            RslphError::Subprocess(format!(
+                "Failed to spawn '{}': {}. {}",
+                invocation.command,
+                e,
+                backend.spawn_hint()
+            ))
+        })?;
+
+    debug!(pid = ?runner.id(), backend = %backend.kind(), "Spawned agent for headless planning");
+
+    // Step 6: Stream and process output without TUI
+    let mut response = AgentResponse::new();
     let stream_cancel = cancel_token.clone();
 
     let stream_result = tokio_timeout(timeout, async {
@@ -163,8 +181,8 @@ async fn run_headless_planning(
                     match line {
                         Some(OutputLine::Stdout(s)) => {
                             trace!(line_len = %s.len(), "Processing stdout line (headless)");
-                            if let Ok(event) = StreamEvent::parse(&s) {
-                                stream_response.process_event(&event);
+                            if let Some(output) = backend.parse_line(&s) {
+                                response.process(&output);
                             }
                         }
                         Some(OutputLine::Stderr(_)) => {
@@ -194,13 +212,12 @@ async fn run_headless_planning(
     }
 
     debug!(
-        has_questions = %stream_response.has_questions(),
-        text_len = %stream_response.text.len(),
+        text_len = %response.text.len(),
         "Headless planning stream complete"
     );
 
     // Step 7: Parse response into ProgressFile
-    let mut progress_file = ProgressFile::parse(&stream_response.text)?;
+    let mut progress_file = ProgressFile::parse(&response.text)?;
 
     // Step 8: Generate project name if empty
     if progress_file.name.is_empty() {
@@ -220,17 +237,43 @@ async fn run_headless_planning(
     let output_path = working_dir.join("progress.md");
     progress_file.write(&output_path)?;
 
-    // Step 10: Create TokenUsage from stream_response
-    let tokens = TokenUsage {
-        input_tokens: stream_response.input_tokens,
-        output_tokens: stream_response.output_tokens,
-        cache_creation_input_tokens: stream_response.cache_creation_input_tokens,
-        cache_read_input_tokens: stream_response.cache_read_input_tokens,
-    };
+    // Step 10: Create TokenUsage from the response
+    let tokens = priced_usage(
+        TokenUsage {
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+            cache_creation_input_tokens: response.cache_creation_input_tokens,
+            cache_read_input_tokens: response.cache_read_input_tokens,
+            ..Default::default()
+        },
+        response.model.as_deref(),
+        config,
+    );
 
     Ok((output_path, tokens))
 }
 
+/// Token usage of a Claude response, priced for the model that produced it.
+fn response_usage(response: &StreamResponse, config: &Config) -> TokenUsage {
+    priced_usage(
+        TokenUsage {
+            input_tokens: response.input_tokens,
+            output_tokens: response.output_tokens,
+            cache_creation_input_tokens: response.cache_creation_input_tokens,
+            cache_read_input_tokens: response.cache_read_input_tokens,
+            ..Default::default()
+        },
+        response.model.as_deref(),
+        config,
+    )
+}
+
+/// Add the cost of `tokens` for `model`.
+fn priced_usage(mut tokens: TokenUsage, model: Option<&str>, config: &Config) -> TokenUsage {
+    tokens.cost_usd = PricingTable::from_config(config).cost_usd(model, &tokens);
+    tokens
+}
+
 /// Run TUI planning mode with streaming output display.
 ///
 /// TUI mode:
@@ -239,6 +282,7 @@ async fn run_headless_planning(
 /// 3. Streams events to TUI for real-time display
 /// 4. If Claude asks questions, enters Q&A mode in TUI
 /// 5. Parses output and writes progress file
+#[allow(clippy::too_many_arguments)]
 async fn run_tui_planning(
     input: &str,
     adaptive: bool,
@@ -255,7 +299,10 @@ async fn run_tui_planning(
     let stack = detect_stack(working_dir);
 
     // Step 2: Get the planning prompt for the specified mode
-    let base_prompt = get_plan_prompt_for_mode(mode);
+    let base_prompt = render(
+        &load_plan_prompt(config, mode)?,
+        &PromptVars::new().with("stack", stack.to_summary()),
+    );
 
     // Step 2.5: Append mode indicator for adaptive mode
     let system_prompt = if adaptive {
@@ -275,15 +322,18 @@ async fn run_tui_planning(
     );
 
     // Step 4: Build Claude CLI args for streaming mode
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         "--verbose".to_string(),
         "--output-format".to_string(),
         "stream-json".to_string(),
+    ];
+    args.extend(model_args(config.plan_model.as_deref()));
+    args.extend([
         "--system-prompt".to_string(),
         system_prompt,
         full_input.clone(),
-    ];
+    ]);
 
     // Step 5: Spawn Claude with interactive stdin for potential questions
     let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, no_dsp);
@@ -483,12 +533,7 @@ async fn run_tui_planning(
             progress_file.write(&output_path)?;
 
             // Create TokenUsage from stream_response
-            let tokens = TokenUsage {
-                input_tokens: stream_response.input_tokens,
-                output_tokens: stream_response.output_tokens,
-                cache_creation_input_tokens: stream_response.cache_creation_input_tokens,
-                cache_read_input_tokens: stream_response.cache_read_input_tokens,
-            };
+            let tokens = response_usage(&stream_response, config);
 
             return Ok((output_path, tokens));
         }
@@ -531,12 +576,7 @@ async fn run_tui_planning(
     progress_file.write(&output_path)?;
 
     // Step 14: Create TokenUsage from stream_response
-    let tokens = TokenUsage {
-        input_tokens: stream_response.input_tokens,
-        output_tokens: stream_response.output_tokens,
-        cache_creation_input_tokens: stream_response.cache_creation_input_tokens,
-        cache_read_input_tokens: stream_response.cache_read_input_tokens,
-    };
+    let tokens = response_usage(&stream_response, config);
 
     Ok((output_path, tokens))
 }
@@ -582,7 +622,7 @@ pub async fn run_adaptive_planning(
             input
         );
 
-        let questions = run_claude_headless(
+        let questions = run_agent_headless(
             REQUIREMENTS_CLARIFIER_PERSONA,
             &clarifier_input,
             no_dsp,
@@ -619,7 +659,7 @@ pub async fn run_adaptive_planning(
         }
     );
 
-    let testing_strategy = run_claude_headless(
+    let testing_strategy = run_agent_headless(
         TESTING_STRATEGIST_PERSONA,
         &testing_input,
         no_dsp,
@@ -635,7 +675,10 @@ pub async fn run_adaptive_planning(
     // Step 6: Run final planning with all context
     println!("Generating final plan...\n");
 
-    let base_prompt = get_plan_prompt_for_mode(mode);
+    let base_prompt = render(
+        &load_plan_prompt(config, mode)?,
+        &PromptVars::new().with("stack", stack.to_summary()),
+    );
     // Append adaptive mode indicator
     let plan_prompt = format!(
         "{}\n\n---\n\n**ACTIVE MODE: ADAPTIVE**\n\nYou are running in adaptive mode. You SHOULD use the `AskUserQuestion` tool to ask 2-5 clarifying questions before generating the plan. Focus on ambiguous technology choices, critical scope decisions, and project-specific context.",
@@ -650,15 +693,14 @@ pub async fn run_adaptive_planning(
     );
 
     // Build Claude CLI args for headless mode
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),
         "--verbose".to_string(), // Required for stream-json with -p
         "--output-format".to_string(),
         "stream-json".to_string(), // JSONL for structured parsing
-        "--system-prompt".to_string(),
-        plan_prompt,
-        final_input,
     ];
+    args.extend(model_args(config.plan_model.as_deref()));
+    args.extend(["--system-prompt".to_string(), plan_prompt, final_input]);
 
     // Spawn Claude
     let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, no_dsp);
@@ -693,6 +735,7 @@ pub async fn run_adaptive_planning(
     let mut total_output_tokens = stream_response.output_tokens;
     let mut total_cache_creation = stream_response.cache_creation_input_tokens;
     let mut total_cache_read = stream_response.cache_read_input_tokens;
+    let mut total_cost_usd = response_usage(&stream_response, config).cost_usd;
 
     // Interactive loop: handle questions with session resume
     const MAX_QUESTION_ROUNDS: u32 = 5;
@@ -761,6 +804,7 @@ pub async fn run_adaptive_planning(
                 total_output_tokens += new_response.output_tokens;
                 total_cache_creation += new_response.cache_creation_input_tokens;
                 total_cache_read += new_response.cache_read_input_tokens;
+                total_cost_usd += response_usage(&new_response, config).cost_usd;
 
                 // Update stream_response for next iteration or final output
                 stream_response = new_response;
@@ -784,11 +828,12 @@ pub async fn run_adaptive_planning(
 
     // Display total token summary for user
     println!(
-        "Total tokens used: In: {} | Out: {} | CacheW: {} | CacheR: {}",
+        "Total tokens used: In: {} | Out: {} | CacheW: {} | CacheR: {} | Cost: {}",
         format_tokens(total_input_tokens),
         format_tokens(total_output_tokens),
         format_tokens(total_cache_creation),
         format_tokens(total_cache_read),
+        format_cost(total_cost_usd),
     );
     if round > 0 {
         println!("(Accumulated across {} round(s) of Q&A)", round);
@@ -823,13 +868,14 @@ pub async fn run_adaptive_planning(
         output_tokens: total_output_tokens,
         cache_creation_input_tokens: total_cache_creation,
         cache_read_input_tokens: total_cache_read,
+        cost_usd: total_cost_usd,
     };
 
     Ok((output_path, tokens))
 }
 
-/// Run Claude CLI in headless mode with a system prompt and return the response.
-async fn run_claude_headless(
+/// Run the agent in headless mode with a system prompt and return the response.
+async fn run_agent_headless(
     system_prompt: &str,
     user_input: &str,
     no_dsp: bool,
@@ -838,32 +884,36 @@ async fn run_claude_headless(
     cancel_token: CancellationToken,
     timeout: Duration,
 ) -> color_eyre::Result<String> {
-    let args = vec![
-        "-p".to_string(),
-        "--verbose".to_string(), // Required for stream-json with -p
-        "--output-format".to_string(),
-        "stream-json".to_string(), // JSONL for structured parsing
-        "--system-prompt".to_string(),
-        system_prompt.to_string(),
-        user_input.to_string(),
-    ];
+    let backend = agent_backend(config, no_dsp)?;
+    let invocation = backend.invocation(&AgentRequest {
+        system_prompt,
+        user_input,
+        model: config.persona_model.as_deref(),
+    });
 
-    let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, no_dsp);
-    let mut runner = ClaudeRunner::spawn(&config.claude_cmd.command, &combined_args, working_dir)
+    let mut runner = ClaudeRunner::spawn(&invocation.command, &invocation.args, working_dir)
         .await
-        .map_err(|e| RslphError::Subprocess(format!("Failed to spawn claude: {}", e)))?;
+        .map_err(|e| {
+            RslphError::Subprocess(format!(
+                "Failed to spawn '{}': {}. {}",
+                invocation.command,
+                e,
+                backend.spawn_hint()
+            ))
+        })?;
 
     let output = runner.run_with_timeout(timeout, cancel_token).await?;
 
-    // Parse JSONL output using StreamResponse
-    let mut stream_response = StreamResponse::new();
+    let mut response = AgentResponse::new();
     for line in &output {
         if let OutputLine::Stdout(s) = line {
-            stream_response.process_line(s);
+            if let Some(output) = backend.parse_line(s) {
+                response.process(&output);
+            }
         }
     }
 
-    Ok(stream_response.text)
+    Ok(response.text)
 }
 
 /// Resume a Claude session with user-provided answers.
@@ -893,15 +943,18 @@ async fn resume_session(
     cancel_token: CancellationToken,
     timeout: Duration,
 ) -> color_eyre::Result<StreamResponse> {
-    let args = vec![
+    let mut args = vec![
         "-p".to_string(),              // Print mode (headless)
         "--verbose".to_string(),       // Required for stream-json with -p
         "--output-format".to_string(), // Output format
         "stream-json".to_string(),     // JSONL for structured parsing
-        "--resume".to_string(),        // Resume the session
-        session_id.to_string(),        // Session ID to resume
-        message.to_string(),           // User's message (answers)
     ];
+    args.extend(model_args(config.plan_model.as_deref()));
+    args.extend([
+        "--resume".to_string(), // Resume the session
+        session_id.to_string(), // Session ID to resume
+        message.to_string(),    // User's message (answers)
+    ]);
 
     let combined_args = build_claude_args(&config.claude_cmd.base_args, &args, no_dsp);
 
@@ -956,7 +1009,7 @@ Example outputs:
 - weather-api
 - chat-bot"#;
 
-    let response = run_claude_headless(
+    let response = run_agent_headless(
         NAME_GENERATOR_PROMPT,
         user_input,
         no_dsp,
@@ -1237,6 +1290,60 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_run_plan_command_uses_agent_backend() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("plan.md"),
+            "# Progress: Demo\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### Phase 1\n\n- [ ] Task 1\n",
+        )
+        .expect("write plan");
+
+        // `#` comments out the prompt the command backend appends
+        let config = Config {
+            agent_backend: AgentKind::Command,
+            agent_cmd: Some("cat plan.md #".to_string()),
+            claude_cmd: crate::config::ClaudeCommand {
+                command: "/nonexistent/claude".to_string(),
+                base_args: vec![],
+            },
+            ..Default::default()
+        };
+
+        let (path, tokens) = run_plan_command(
+            "anything",
+            false, // basic mode
+            PromptMode::Basic,
+            false, // no_dsp
+            &config,
+            dir.path(),
+            CancellationToken::new(),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect("planned through the command backend");
+
+        let progress = ProgressFile::load(&path).expect("load");
+        assert_eq!(progress.name, "Demo");
+        assert_eq!(progress.total_tasks(), 1);
+        assert_eq!(tokens.input_tokens, 0);
+
+        // Adaptive planning can't run without Claude
+        let err = run_plan_command(
+            "anything",
+            true, // adaptive
+            PromptMode::Basic,
+            false,
+            &config,
+            dir.path(),
+            CancellationToken::new(),
+            Duration::from_secs(5),
+        )
+        .await
+        .expect_err("adaptive needs Claude");
+        assert!(err.to_string().contains("Adaptive planning needs the Claude CLI"));
+    }
+
     #[test]
     fn test_display_questions_formats_correctly() {
         // Test that display_questions doesn't panic with various inputs
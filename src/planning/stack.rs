@@ -65,6 +65,48 @@ impl DetectedStack {
 
         parts.join("\n")
     }
+
+    /// Shell command that runs the project's tests, if a test runner was detected.
+    pub fn test_command(&self) -> Option<String> {
+        self.test_runner.as_deref().map(|tool| tool_command(tool).to_string())
+    }
+
+    /// Shell command that runs the project's linter, if one was detected.
+    pub fn lint_command(&self) -> Option<String> {
+        self.linter.as_deref().map(|tool| tool_command(tool).to_string())
+    }
+
+    /// Shell command that type-checks the project, if a type checker was detected.
+    pub fn type_check_command(&self) -> Option<String> {
+        self.type_checker
+            .as_deref()
+            .map(|tool| tool_command(tool).to_string())
+    }
+}
+
+/// Map a detected tool name to a runnable shell command.
+///
+/// Tool names that are already commands (e.g., "cargo test", "pytest") are
+/// returned unchanged.
+fn tool_command(tool: &str) -> &str {
+    match tool {
+        "rustc" => "cargo check --all-targets",
+        "clippy" => "cargo clippy --all-targets -- -D warnings",
+        "tsc" => "npx tsc --noEmit",
+        "vitest" => "npx vitest run",
+        "jest" => "npx jest",
+        "mocha" => "npx mocha",
+        "eslint" => "npx eslint .",
+        "biome" => "npx biome check .",
+        "mypy" => "mypy .",
+        "ruff" => "ruff check .",
+        "flake8" => "flake8 .",
+        "pylint" => "pylint .",
+        "go test" => "go test ./...",
+        "go build" => "go build ./...",
+        "golangci-lint" => "golangci-lint run",
+        other => other,
+    }
 }
 
 /// Detect the project stack from manifest files.
@@ -331,6 +373,35 @@ dependencies = ["fastapi"]
         assert!(summary.contains("Linter: clippy"));
     }
 
+    #[test]
+    fn test_gate_commands_for_rust() {
+        let dir = TempDir::new().expect("temp dir");
+        std::fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"test\"\nversion = \"0.1.0\"",
+        )
+        .expect("write Cargo.toml");
+
+        let stack = detect_stack(dir.path());
+        assert_eq!(stack.test_command(), Some("cargo test".to_string()));
+        assert_eq!(
+            stack.type_check_command(),
+            Some("cargo check --all-targets".to_string())
+        );
+        assert_eq!(
+            stack.lint_command(),
+            Some("cargo clippy --all-targets -- -D warnings".to_string())
+        );
+    }
+
+    #[test]
+    fn test_gate_commands_unknown_stack() {
+        let stack = DetectedStack::default();
+        assert!(stack.test_command().is_none());
+        assert!(stack.lint_command().is_none());
+        assert!(stack.type_check_command().is_none());
+    }
+
     #[test]
     fn test_to_summary_minimal() {
         let stack = DetectedStack::default();
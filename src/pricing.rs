@@ -0,0 +1,201 @@
+//! Model pricing for cost reporting.
+//!
+//! Converts token usage into USD using per-model rates. A built-in table
+//! covers current Claude models; entries in the config file's `[pricing]`
+//! table override or extend it.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::build::tokens::TokenUsage;
+use crate::config::Config;
+
+/// Key used for models that match no other entry (or are unknown).
+pub const DEFAULT_MODEL_KEY: &str = "default";
+
+/// Token prices for one model, in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPricing {
+    /// Uncached input tokens.
+    pub input: f64,
+    /// Output tokens.
+    pub output: f64,
+    /// Cache write (cache creation) input tokens.
+    pub cache_write: f64,
+    /// Cache read input tokens.
+    pub cache_read: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64, cache_write: f64, cache_read: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_write,
+            cache_read,
+        }
+    }
+
+    /// Cost of `usage` in USD at these rates.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input
+            + usage.output_tokens as f64 * self.output
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.cache_read_input_tokens as f64 * self.cache_read)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in rates, keyed by model ID prefix.
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-5", ModelPricing::new(5.0, 25.0, 6.25, 0.50)),
+    ("claude-opus-4", ModelPricing::new(15.0, 75.0, 18.75, 1.50)),
+    ("claude-sonnet-4", ModelPricing::new(3.0, 15.0, 3.75, 0.30)),
+    ("claude-haiku-4-5", ModelPricing::new(1.0, 5.0, 1.25, 0.10)),
+    (
+        "claude-3-7-sonnet",
+        ModelPricing::new(3.0, 15.0, 3.75, 0.30),
+    ),
+    (
+        "claude-3-5-sonnet",
+        ModelPricing::new(3.0, 15.0, 3.75, 0.30),
+    ),
+    ("claude-3-5-haiku", ModelPricing::new(0.80, 4.0, 1.0, 0.08)),
+    ("claude-3-opus", ModelPricing::new(15.0, 75.0, 18.75, 1.50)),
+    ("claude-3-haiku", ModelPricing::new(0.25, 1.25, 0.30, 0.03)),
+    (DEFAULT_MODEL_KEY, ModelPricing::new(3.0, 15.0, 3.75, 0.30)),
+];
+
+/// Per-model pricing lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PricingTable {
+    models: BTreeMap<String, ModelPricing>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self {
+            models: BUILTIN_PRICING
+                .iter()
+                .map(|(key, pricing)| (key.to_string(), *pricing))
+                .collect(),
+        }
+    }
+}
+
+impl PricingTable {
+    /// Built-in rates with the config's `[pricing]` entries applied on top.
+    pub fn from_config(config: &Config) -> Self {
+        let mut table = Self::default();
+        table.models.extend(
+            config
+                .pricing
+                .iter()
+                .map(|(key, pricing)| (key.clone(), *pricing)),
+        );
+        table
+    }
+
+    /// Rates for a model ID.
+    ///
+    /// The longest table key that appears in the model ID wins, so
+    /// "claude-opus-4-5-20251101" matches "claude-opus-4-5" before
+    /// "claude-opus-4". Unknown or missing models use the "default" entry.
+    pub fn rates_for(&self, model: Option<&str>) -> ModelPricing {
+        let matched = model.and_then(|model| {
+            self.models
+                .iter()
+                .filter(|(key, _)| {
+                    key.as_str() != DEFAULT_MODEL_KEY && model.contains(key.as_str())
+                })
+                .max_by_key(|(key, _)| key.len())
+                .map(|(_, pricing)| *pricing)
+        });
+
+        matched
+            .or_else(|| self.models.get(DEFAULT_MODEL_KEY).copied())
+            .unwrap_or(ModelPricing::new(0.0, 0.0, 0.0, 0.0))
+    }
+
+    /// Cost of `usage` in USD for the given model.
+    pub fn cost_usd(&self, model: Option<&str>, usage: &TokenUsage) -> f64 {
+        self.rates_for(model).cost_usd(usage)
+    }
+}
+
+/// Format a USD amount for display (e.g., "$1.23").
+pub fn format_cost(usd: f64) -> String {
+    format!("${:.2}", usd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn million_of_each() -> TokenUsage {
+        TokenUsage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            cache_creation_input_tokens: 1_000_000,
+            cache_read_input_tokens: 1_000_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rates_for_longest_match_wins() {
+        let table = PricingTable::default();
+        assert_eq!(table.rates_for(Some("claude-opus-4-5-20251101")).input, 5.0);
+        assert_eq!(
+            table.rates_for(Some("claude-opus-4-1-20250805")).input,
+            15.0
+        );
+        assert_eq!(
+            table.rates_for(Some("claude-sonnet-4-5-20250929")).output,
+            15.0
+        );
+    }
+
+    #[test]
+    fn test_rates_for_unknown_model_uses_default() {
+        let table = PricingTable::default();
+        let default = table.rates_for(None);
+        assert_eq!(table.rates_for(Some("some-other-model")), default);
+        assert_eq!(default.input, 3.0);
+    }
+
+    #[test]
+    fn test_cost_usd() {
+        let table = PricingTable::default();
+        let cost = table.cost_usd(Some("claude-sonnet-4-20250514"), &million_of_each());
+        assert!((cost - 22.05).abs() < 1e-9, "cost was {}", cost);
+    }
+
+    #[test]
+    fn test_config_overrides_and_extends() {
+        let mut config = Config::default();
+        config.pricing.insert(
+            "claude-sonnet-4".to_string(),
+            ModelPricing::new(1.0, 2.0, 0.0, 0.0),
+        );
+        config.pricing.insert(
+            "my-local-model".to_string(),
+            ModelPricing::new(0.0, 0.0, 0.0, 0.0),
+        );
+
+        let table = PricingTable::from_config(&config);
+        let usage = million_of_each();
+        assert!((table.cost_usd(Some("claude-sonnet-4-20250514"), &usage) - 3.0).abs() < 1e-9);
+        assert_eq!(table.cost_usd(Some("my-local-model-7b"), &usage), 0.0);
+        // Untouched entries keep their built-in rates
+        assert_eq!(table.rates_for(Some("claude-3-haiku-20240307")).input, 0.25);
+    }
+
+    #[test]
+    fn test_format_cost() {
+        assert_eq!(format_cost(0.0), "$0.00");
+        assert_eq!(format_cost(1.234), "$1.23");
+    }
+}
@@ -1,10 +1,54 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
 use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
 
 use crate::error::RslphError;
 
+/// On-disk format of a progress file, chosen by its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ProgressFormat {
+    /// `.md` or any other extension, read by `ProgressFile::parse`
+    #[default]
+    Markdown,
+    /// `.json`
+    Json,
+    /// `.yaml` or `.yml`
+    Yaml,
+    /// `.toml`
+    Toml,
+}
+
+impl ProgressFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("json") => ProgressFormat::Json,
+            Some("yaml") | Some("yml") => ProgressFormat::Yaml,
+            Some("toml") => ProgressFormat::Toml,
+            _ => ProgressFormat::Markdown,
+        }
+    }
+
+    /// Check for a serde format rather than markdown
+    pub fn is_structured(self) -> bool {
+        self != ProgressFormat::Markdown
+    }
+}
+
 /// Complete progress file structure (PROG-01 through PROG-07)
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// Markdown is the primary format; the same structure is read and written as
+/// JSON, YAML or TOML for `progress.json`, `progress.yaml` and `progress.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProgressFile {
     /// Plan name/title
     pub name: String,
@@ -15,6 +59,10 @@ pub struct ProgressFile {
     /// Analysis/research section content (PROG-02)
     pub analysis: String,
 
+    /// Prose under `## Tasks` before the first phase, as markdown
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub tasks_preamble: String,
+
     /// Task list organized by phases (PROG-03)
     pub tasks: Vec<TaskPhase>,
 
@@ -24,38 +72,540 @@ pub struct ProgressFile {
     /// Tasks completed in current iteration (PROG-05)
     pub completed_this_iteration: Vec<String>,
 
+    /// Other markdown in Completed This Iteration, written after the items
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub completed_notes: String,
+
     /// Recent attempts for failure memory (PROG-06)
     pub recent_attempts: Vec<Attempt>,
 
+    /// Other markdown in Recent Attempts, written after the attempts
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub attempts_notes: String,
+
     /// Full iteration log history (PROG-07)
     pub iteration_log: Vec<IterationEntry>,
+
+    /// Other markdown in Iteration Log, written after the table
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub log_notes: String,
+
+    /// `##` sections rslph doesn't manage, kept verbatim
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_sections: Vec<ExtraSection>,
+}
+
+/// Sections `ProgressFile` models, in the order `to_markdown` writes them
+pub(crate) const KNOWN_SECTIONS: [&str; 7] = [
+    "Status",
+    "Analysis",
+    "Tasks",
+    "Testing Strategy",
+    "Completed This Iteration",
+    "Recent Attempts",
+    "Iteration Log",
+];
+
+/// Known sections whose entries are parsed; other text in them is kept as notes
+pub(crate) const NOTE_SECTIONS: [&str; 3] = ["Completed This Iteration", "Recent Attempts", "Iteration Log"];
+
+/// A `Tried:`, `Result:` or `Next:` line of an attempt
+enum AttemptEntry<'a> {
+    Tried(&'a str),
+    Result(&'a str),
+    Next(&'a str),
+}
+
+/// Read a list item of Recent Attempts as an entry of its `### Iteration N`
+fn attempt_entry<'a>(h2: &str, h3: &str, text: &'a str) -> Option<(u32, AttemptEntry<'a>)> {
+    if h2 != "Recent Attempts" {
+        return None;
+    }
+    let iteration = attempt_iteration(h3)?;
+    let entry = if let Some(tried) = text.strip_prefix("Tried:") {
+        AttemptEntry::Tried(tried.trim())
+    } else if let Some(result) = text.strip_prefix("Result:") {
+        AttemptEntry::Result(result.trim())
+    } else if let Some(next) = text.strip_prefix("Next:") {
+        AttemptEntry::Next(next.trim())
+    } else {
+        return None;
+    };
+    Some((iteration, entry))
+}
+
+/// Append a block of notes, followed by a blank line
+fn push_notes(md: &mut String, notes: &str) {
+    if !notes.is_empty() {
+        md.push_str(notes);
+        md.push_str("\n\n");
+    }
+}
+
+/// A user-added `##` section, written back after the known section it followed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtraSection {
+    pub title: String,
+    /// Markdown between this heading and the next `#`/`##` heading
+    #[serde(default)]
+    pub body: String,
+    /// Known section it came after; `None` for right after the title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
 }
 
 /// A phase containing related tasks
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TaskPhase {
     pub name: String,
+    /// Prose and non-task list items under the phase heading, as markdown
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default)]
     pub tasks: Vec<Task>,
 }
 
+/// Description prefix older progress files used for blocked tasks; parsed as
+/// the `[-]` state
+pub const BLOCKED_PREFIX: &str = "BLOCKED: ";
+
+/// How urgent a task is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumString, Display)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
 /// Individual task with completion state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// In the file a task may carry a stable ID and, in a trailing group of
+/// `;`-separated annotations, dependencies and metadata:
+/// `- [ ] T3: Wire up the API (after T1, T2; priority: high; tags: api, backend; estimate: 2)`.
+/// A blocked task is checked `[-]` and may give its reason:
+/// `- [-] Deploy (blocked: no credentials)`. The ID and annotations are kept
+/// out of `description`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub description: String,
+    #[serde(default)]
     pub completed: bool,
+    /// Stable ID other tasks can depend on (e.g. "T3")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// IDs of tasks that must be completed first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Reason the task was blocked (`[-]`); empty when none was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Expected number of iterations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<u32>,
+    /// Sub-bullets and nested subtasks under the task, as markdown without
+    /// the list indentation
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub details: String,
+}
+
+impl Task {
+    /// Check if the task was marked blocked and should be skipped
+    pub fn is_blocked(&self) -> bool {
+        !self.completed && self.blocked.is_some()
+    }
+
+    /// Build a task from its checklist text, splitting off the ID and annotations
+    ///
+    /// With `known_ids`, only those are read as IDs, in the prefix and in
+    /// `(after ...)`; anything else stays in the description.
+    pub(crate) fn from_markdown(
+        text: &str,
+        completed: bool,
+        blocked: bool,
+        known_ids: Option<&HashSet<String>>,
+    ) -> Self {
+        let is_id = |s: &str| is_task_id(s) && known_ids.is_none_or(|ids| ids.contains(s));
+        let mut rest = text.trim();
+        let mut task = Task {
+            completed,
+            blocked: blocked.then(String::new),
+            ..Default::default()
+        };
+
+        if let Some((head, tail)) = rest.split_once(':') {
+            if is_id(head) && tail.starts_with(char::is_whitespace) {
+                task.id = Some(head.to_string());
+                rest = tail.trim_start();
+            }
+        }
+
+        // A trailing group that isn't all annotations stays in the description
+        if let Some(open) = trailing_group(rest) {
+            let mut annotated = task.clone();
+            let group = &rest[open + 1..rest.len() - 1];
+            if group
+                .split(';')
+                .all(|entry| annotated.annotate(entry.trim()))
+                && annotated.depends_on.iter().all(|id| is_id(id))
+            {
+                task = annotated;
+                rest = rest[..open].trim_end();
+            }
+        }
+
+        if let Some(stripped) = rest.strip_prefix(BLOCKED_PREFIX) {
+            if !completed {
+                task.blocked.get_or_insert_with(String::new);
+                rest = stripped;
+            }
+        }
+
+        task.description = rest.to_string();
+        task
+    }
+
+    /// Apply one annotation (`after ...`, `priority: ...`, ...); false if it isn't one
+    pub(crate) fn annotate(&mut self, entry: &str) -> bool {
+        if let Some(ids) = entry.strip_prefix("after ") {
+            let ids: Vec<&str> = ids.split(',').map(str::trim).collect();
+            if !ids.iter().all(|id| is_task_id(id)) {
+                return false;
+            }
+            self.depends_on = ids.into_iter().map(str::to_string).collect();
+            return true;
+        }
+
+        let Some((key, value)) = entry.split_once(':') else {
+            return false;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "priority" => match value.parse() {
+                Ok(priority) => self.priority = Some(priority),
+                Err(_) => return false,
+            },
+            "tags" => {
+                self.tags = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            // Iterations only: "2 days" would be rewritten as a bare 2
+            "estimate" => match value.parse() {
+                Ok(estimate) => self.estimate = Some(estimate),
+                Err(_) => return false,
+            },
+            "blocked" => self.blocked = Some(value.to_string()),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Checklist line for the task, including its ID and annotations
+    pub(crate) fn to_markdown(&self) -> String {
+        let checkbox = if self.completed {
+            "[x]"
+        } else if self.blocked.is_some() {
+            "[-]"
+        } else {
+            "[ ]"
+        };
+        let mut text = match &self.id {
+            Some(id) => format!("{} {}: {}", checkbox, id, self.description),
+            None => format!("{} {}", checkbox, self.description),
+        };
+
+        let mut annotations = Vec::new();
+        if !self.depends_on.is_empty() {
+            annotations.push(format!("after {}", self.depends_on.join(", ")));
+        }
+        if let Some(priority) = self.priority {
+            annotations.push(format!("priority: {}", priority));
+        }
+        if !self.tags.is_empty() {
+            annotations.push(format!("tags: {}", self.tags.join(", ")));
+        }
+        if let Some(estimate) = self.estimate {
+            annotations.push(format!("estimate: {}", estimate));
+        }
+        match self.blocked.as_deref() {
+            Some(reason) if !self.completed && !reason.is_empty() => {
+                annotations.push(format!("blocked: {}", reason.replace(';', ",")));
+            }
+            _ => {}
+        }
+        if !annotations.is_empty() {
+            text.push_str(&format!(" ({})", annotations.join("; ")));
+        }
+        text
+    }
+}
+
+/// Text of a blocked (`[-]` or `[~]`) task list item in the Tasks section
+fn blocked_item<'a>(h2: &str, item: &'a str) -> Option<&'a str> {
+    if h2 != "Tasks" {
+        return None;
+    }
+    let item = item.trim_start();
+    item.strip_prefix("[-]")
+        .or_else(|| item.strip_prefix("[~]"))
+        .filter(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Body of the first code fence in `text`, or all of it when there is none
+fn unfence(text: &str) -> &str {
+    let Some(start) = text.find("```") else {
+        return text.trim();
+    };
+    // Skip the info string (e.g. "json") after the opening fence
+    let body = &text[start + 3..];
+    let body = body.split_once('\n').map_or("", |(_, rest)| rest);
+    match body.find("```") {
+        Some(end) => body[..end].trim(),
+        None => body.trim(),
+    }
+}
+
+/// Replace the file at `path` without leaving it half-written (temp file + rename)
+pub(crate) fn write_atomically(path: &Path, content: &str) -> Result<(), RslphError> {
+    use atomicwrites::{AllowOverwrite, AtomicFile};
+    use std::io::Write;
+
+    AtomicFile::new(path, AllowOverwrite)
+        .write(|f| f.write_all(content.as_bytes()))
+        .map_err(|e| RslphError::Io(e.into()))
+}
+
+/// Check for a `####` or deeper heading, which is prose rather than structure
+pub(crate) fn is_sub_heading(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Start(Tag::Heading {
+            level: HeadingLevel::H4 | HeadingLevel::H5 | HeadingLevel::H6,
+            ..
+        })
+    )
+}
+
+/// Iteration number of a `### Iteration N` heading in Recent Attempts
+pub(crate) fn attempt_iteration(h3: &str) -> Option<u32> {
+    h3.strip_prefix("Iteration ")?.trim().parse().ok()
+}
+
+/// Check for the start of a block-level element (paragraph, list, ...)
+pub(crate) fn is_block_start(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Start(
+            Tag::Paragraph
+                | Tag::List(_)
+                | Tag::CodeBlock(_)
+                | Tag::BlockQuote(_)
+                | Tag::HtmlBlock
+                | Tag::Table(_)
+        )
+    )
+}
+
+/// Check for an inline event, whose span is part of a block's text
+pub(crate) fn is_inline(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Text(_)
+            | Event::Code(_)
+            | Event::InlineHtml(_)
+            | Event::SoftBreak
+            | Event::HardBreak
+            | Event::Start(
+                Tag::Emphasis | Tag::Strong | Tag::Strikethrough | Tag::Link { .. } | Tag::Image { .. }
+            )
+            | Event::End(
+                TagEnd::Emphasis
+                    | TagEnd::Strong
+                    | TagEnd::Strikethrough
+                    | TagEnd::Link
+                    | TagEnd::Image
+            )
+    )
+}
+
+/// `item` without its list marker (`-`, `*`, `+`, `1.`) and the space after it
+pub(crate) fn strip_bullet(item: &str) -> &str {
+    let rest = item.trim_start();
+    let rest = match rest.strip_prefix(['-', '*', '+']) {
+        Some(rest) => rest,
+        None => {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            rest[digits..].strip_prefix(['.', ')']).unwrap_or(rest)
+        }
+    };
+    rest.trim_start_matches([' ', '\t'])
+}
+
+/// Source text of a heading, without the `#` markers or setext underline
+pub(crate) fn heading_source(raw: &str) -> &str {
+    let raw = raw.trim();
+    if !raw.starts_with('#') {
+        return raw.lines().next().unwrap_or_default().trim();
+    }
+    let text = raw.trim_start_matches('#').trim();
+    // A closing `#` sequence only counts after whitespace ("C#" keeps its `#`)
+    let closed = text.trim_end_matches('#');
+    if closed.is_empty() || closed.ends_with([' ', '\t']) {
+        closed.trim_end()
+    } else {
+        text
+    }
+}
+
+/// Item or paragraph source joined into lines without their indentation
+pub(crate) fn source_lines(text: &str) -> String {
+    text.trim()
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Table cell text for the markdown source of a cell
+fn unescape_cell(source: &str) -> String {
+    source.trim().replace("\\|", "|")
+}
+
+/// Markdown source of a table cell holding `text`
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Byte offset of the start of the line containing `pos`
+pub(crate) fn line_start(content: &str, pos: usize) -> usize {
+    content[..pos].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// `text` without leading blank lines and trailing whitespace
+fn trim_blank_lines(text: &str) -> &str {
+    let text = text.trim_end();
+    let first_line = text
+        .char_indices()
+        .take_while(|(_, c)| c.is_whitespace())
+        .filter(|(_, c)| *c == '\n')
+        .last()
+        .map_or(0, |(i, _)| i + 1);
+    &text[first_line..]
+}
+
+/// `text` with the indentation common to its lines removed
+fn dedent(text: &str) -> String {
+    let indent_of = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let indent = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(indent_of)
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| {
+            if line.trim().is_empty() {
+                ""
+            } else {
+                &line[indent..]
+            }
+        })
+        .collect();
+    trim_blank_lines(&lines.join("\n")).to_string()
+}
+
+/// Byte offset of the `(` opening a parenthesized group that ends `text`
+pub(crate) fn trailing_group(text: &str) -> Option<usize> {
+    if !text.ends_with(')') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices().rev() {
+        match c {
+            ')' => depth += 1,
+            '(' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Check for a task ID: a letter, then letters, digits, `-`, `_` or `.`,
+/// ending in a digit (e.g. "T3", "API-2")
+pub(crate) fn is_task_id(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && s.ends_with(|c: char| c.is_ascii_digit())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Dependency search state of a task in `find_cycle`
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    New,
+    OnPath,
+    Done,
+}
+
+/// Depth-first search from `index`; reaching a task that is still on the
+/// path closes a cycle, returned as the IDs along it
+fn find_cycle<'a>(
+    index: usize,
+    tasks: &[&'a Task],
+    ids: &HashMap<&str, usize>,
+    state: &mut [Visit],
+    path: &mut Vec<&'a str>,
+) -> Option<Vec<&'a str>> {
+    let id = tasks[index].id.as_deref().unwrap_or_default();
+    match state[index] {
+        Visit::Done => return None,
+        Visit::OnPath => {
+            let start = path.iter().position(|p| *p == id).unwrap_or(0);
+            let mut cycle = path[start..].to_vec();
+            cycle.push(id);
+            return Some(cycle);
+        }
+        Visit::New => {}
+    }
+    state[index] = Visit::OnPath;
+    path.push(id);
+    for dep in &tasks[index].depends_on {
+        if let Some(cycle) = find_cycle(ids[dep.as_str()], tasks, ids, state, path) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    state[index] = Visit::Done;
+    None
 }
 
 /// Record of an iteration attempt (PROG-06)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Attempt {
     pub iteration: u32,
     pub tried: String,
     pub result: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub next: Option<String>,
 }
 
 /// Log entry for iteration history (PROG-07)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IterationEntry {
     pub iteration: u32,
     pub started: String,
@@ -84,11 +634,32 @@ impl ProgressFile {
             .count()
     }
 
-    /// Get next incomplete task
+    /// Count incomplete tasks marked blocked
+    pub fn blocked_tasks(&self) -> usize {
+        self.tasks
+            .iter()
+            .flat_map(|p| &p.tasks)
+            .filter(|t| t.is_blocked())
+            .count()
+    }
+
+    /// Count tasks that are neither completed nor blocked
+    pub fn remaining_tasks(&self) -> usize {
+        self.total_tasks() - self.completed_tasks() - self.blocked_tasks()
+    }
+
+    /// Check if every task is completed or knowingly blocked, with at least
+    /// one blocked (a plan finished apart from skipped work)
+    pub fn is_finished_with_blocked(&self) -> bool {
+        self.blocked_tasks() > 0 && self.remaining_tasks() == 0
+    }
+
+    /// Get next incomplete task that is not blocked and whose dependencies
+    /// are all completed
     pub fn next_task(&self) -> Option<(&str, &Task)> {
         for phase in &self.tasks {
             for task in &phase.tasks {
-                if !task.completed {
+                if self.is_ready(task) {
                     return Some((&phase.name, task));
                 }
             }
@@ -96,45 +667,201 @@ impl ProgressFile {
         None
     }
 
+    /// Check if a task can be worked on: incomplete, not blocked, and every
+    /// task it depends on is completed
+    pub fn is_ready(&self, task: &Task) -> bool {
+        !task.completed
+            && !task.is_blocked()
+            && task.depends_on.iter().all(|dep| {
+                self.tasks
+                    .iter()
+                    .flat_map(|p| &p.tasks)
+                    .any(|t| t.completed && t.id.as_deref() == Some(dep.as_str()))
+            })
+    }
+
+    /// Check that task IDs are unique and dependencies name existing tasks
+    /// without forming a cycle
+    pub(crate) fn validate_dependencies(&self) -> Result<(), RslphError> {
+        let tasks: Vec<&Task> = self.tasks.iter().flat_map(|p| &p.tasks).collect();
+        let mut ids = HashMap::new();
+        for (index, task) in tasks.iter().enumerate() {
+            if let Some(id) = &task.id {
+                if ids.insert(id.as_str(), index).is_some() {
+                    return Err(RslphError::ProgressParse(format!(
+                        "Duplicate task ID {}",
+                        id
+                    )));
+                }
+            }
+        }
+        for task in &tasks {
+            if let Some(dep) = task
+                .depends_on
+                .iter()
+                .find(|d| !ids.contains_key(d.as_str()))
+            {
+                return Err(RslphError::ProgressParse(format!(
+                    "Task \"{}\" depends on unknown task {}",
+                    task.description, dep
+                )));
+            }
+        }
+
+        let mut state = vec![Visit::New; tasks.len()];
+        for index in 0..tasks.len() {
+            if let Some(cycle) = find_cycle(index, &tasks, &ids, &mut state, &mut Vec::new()) {
+                return Err(RslphError::ProgressParse(format!(
+                    "Task dependency cycle: {}",
+                    cycle.join(" -> ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Parse markdown content into ProgressFile
+    ///
+    /// Free-text and unknown sections, phase prose and task sub-bullets are
+    /// kept as written, so `to_markdown` gives them back unchanged. Headings,
+    /// task text, list items and table cells are read from their markdown
+    /// source too, so escapes, emphasis and links survive a rewrite.
+    ///
+    /// A task ID prefix that another task also uses, or an `(after ...)`
+    /// naming a task that doesn't exist, is ordinary text (`Step1: ...`,
+    /// `(after v2)`) and stays in the description.
     pub fn parse(content: &str) -> Result<Self, RslphError> {
+        let mut pf = Self::parse_markdown(content, None)?;
+        let ids = pf.unique_task_ids();
+        let resolved = pf.tasks.iter().flat_map(|p| &p.tasks).all(|task| {
+            task.id.as_ref().is_none_or(|id| ids.contains(id))
+                && task.depends_on.iter().all(|dep| ids.contains(dep))
+        });
+        if !resolved {
+            pf = Self::parse_markdown(content, Some(&ids))?;
+        }
+        pf.validate_dependencies()?;
+        Ok(pf)
+    }
+
+    /// Task IDs used by exactly one task
+    fn unique_task_ids(&self) -> HashSet<String> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for id in self.tasks.iter().flat_map(|p| &p.tasks).filter_map(|t| t.id.as_deref()) {
+            *counts.entry(id).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count == 1)
+            .map(|(id, _)| id.to_string())
+            .collect()
+    }
+
+    /// Parse markdown, reading only `known_ids` as task IDs when given
+    fn parse_markdown(
+        content: &str,
+        known_ids: Option<&HashSet<String>>,
+    ) -> Result<Self, RslphError> {
         let mut options = Options::empty();
         options.insert(Options::ENABLE_TASKLISTS);
         options.insert(Options::ENABLE_TABLES);
 
-        let parser = Parser::new_ext(content, options);
+        let parser = Parser::new_ext(content, options).into_offset_iter();
 
         let mut pf = ProgressFile::default();
         let mut current_h2 = String::new();
         let mut current_h3 = String::new();
         let mut heading_level: Option<HeadingLevel> = None;
-        let mut heading_text = String::new();
-        let mut section_text = String::new();
+        let mut heading_range = 0..0;
+        // Body of the current `##` section starts here; extra sections are
+        // anchored to the last known section before them
+        let mut section_start = 0;
+        let mut last_known: Option<String> = None;
         let mut task_is_checked: Option<bool> = None;
-        let mut task_description = String::new();
         let mut current_phase_tasks: Vec<Task> = Vec::new();
-        let mut in_table_cell = false;
+        let mut phase_description: Vec<&str> = Vec::new();
+        // Text of the current section that isn't one of its entries (the
+        // Tasks preamble, or notes in the Completed/Attempts/Log sections)
+        let mut section_notes: Vec<&str> = Vec::new();
         let mut table_row: Vec<String> = Vec::new();
-        let mut cell_text = String::new();
+        let mut cell_range = 0..0;
         let mut in_list_item = false;
-        let mut list_item_text = String::new();
+        // Source span of the current list item's text, after its marker
+        let mut item_text = 0..0;
+        // List item nesting and the span of the outermost item; its nested
+        // blocks (task details) are copied verbatim from `details_start`
+        let mut item_depth = 0;
+        let mut item_range = 0..0;
+        let mut item_has_text = false;
+        let mut details_start: Option<usize> = None;
+        // End of the phase-level block last copied into the phase description
+        let mut skip_until = 0;
+
+        for (event, range) in parser {
+            if range.start < skip_until {
+                continue;
+            }
+            match event {
+                Event::Start(Tag::Item) => item_depth += 1,
+                Event::End(TagEnd::Item) => item_depth -= 1,
+                _ => {}
+            }
+            if details_start.is_some() && item_depth > 0 {
+                continue;
+            }
+
+            if current_h2 == "Tasks" && item_depth == 1 && item_has_text && is_block_start(&event) {
+                details_start = Some(line_start(content, range.start));
+                continue;
+            }
+            let prose = item_depth == 0
+                && (is_block_start(&event) || is_sub_heading(&event) || matches!(event, Event::Rule))
+                && !matches!(event, Event::Start(Tag::List(_)));
+            if prose && current_h2 == "Tasks" {
+                let text = content[range.clone()].trim();
+                if current_h3.is_empty() {
+                    section_notes.push(text);
+                } else {
+                    phase_description.push(text);
+                }
+                skip_until = range.end;
+                continue;
+            }
+            if prose
+                && NOTE_SECTIONS.contains(&current_h2.as_str())
+                // A table too narrow for log rows is notes too
+                && !(current_h2 == "Iteration Log"
+                    && matches!(&event, Event::Start(Tag::Table(columns)) if columns.len() >= 5))
+            {
+                section_notes.push(content[range.clone()].trim());
+                skip_until = range.end;
+                continue;
+            }
 
-        for event in parser {
             match event {
                 Event::Start(Tag::Heading { level, .. }) => {
                     // Flush previous section
-                    Self::flush_section(
+                    Self::flush_phase(
                         &mut pf,
                         &current_h2,
-                        &section_text,
                         &current_h3,
                         &mut current_phase_tasks,
+                        &mut phase_description,
                     );
-                    section_text.clear();
+                    if matches!(level, HeadingLevel::H1 | HeadingLevel::H2) {
+                        Self::flush_section(
+                            &mut pf,
+                            &current_h2,
+                            &content[section_start..range.start],
+                            &last_known,
+                            &mut section_notes,
+                        );
+                    }
                     heading_level = Some(level);
-                    heading_text.clear();
+                    heading_range = range;
                 }
                 Event::End(TagEnd::Heading(_)) => {
+                    let heading_text = heading_source(&content[heading_range.clone()]);
                     match heading_level {
                         Some(HeadingLevel::H1) => {
                             pf.name = heading_text
@@ -142,123 +869,108 @@ impl ProgressFile {
                                 .trim_start_matches("Progress:")
                                 .trim()
                                 .to_string();
+                            current_h2.clear();
+                            current_h3.clear();
                         }
                         Some(HeadingLevel::H2) => {
                             current_h2 = heading_text.trim().to_string();
                             current_h3.clear();
+                            section_start = range.end;
+                            if KNOWN_SECTIONS.contains(&current_h2.as_str()) {
+                                last_known = Some(current_h2.clone());
+                            }
                         }
                         Some(HeadingLevel::H3) => {
-                            // Save previous phase if we're in Tasks section
-                            if current_h2 == "Tasks"
-                                && !current_h3.is_empty()
-                                && !current_phase_tasks.is_empty()
+                            current_h3 = heading_text.trim().to_string();
+                            // Only `### Iteration N` means something outside Tasks
+                            if NOTE_SECTIONS.contains(&current_h2.as_str())
+                                && (current_h2 != "Recent Attempts"
+                                    || attempt_iteration(&current_h3).is_none())
                             {
-                                pf.tasks.push(TaskPhase {
-                                    name: current_h3.clone(),
-                                    tasks: std::mem::take(&mut current_phase_tasks),
-                                });
+                                section_notes.push(content[heading_range.clone()].trim());
                             }
-                            current_h3 = heading_text.trim().to_string();
                         }
                         _ => {}
                     }
                     heading_level = None;
                 }
-                Event::Text(text) => {
-                    if heading_level.is_some() {
-                        heading_text.push_str(&text);
-                    } else if task_is_checked.is_some() {
-                        // Accumulate task description text
-                        task_description.push_str(&text);
-                    } else if in_list_item {
-                        list_item_text.push_str(&text);
-                    } else if in_table_cell {
-                        cell_text.push_str(&text);
-                    } else {
-                        section_text.push_str(&text);
-                        section_text.push('\n');
-                    }
-                }
-                Event::Code(code) => {
-                    if task_is_checked.is_some() {
-                        // Include inline code in task description
-                        task_description.push('`');
-                        task_description.push_str(&code);
-                        task_description.push('`');
-                    } else if in_list_item {
-                        list_item_text.push('`');
-                        list_item_text.push_str(&code);
-                        list_item_text.push('`');
-                    }
+                ref inline if is_inline(inline) && heading_level.is_none() && in_list_item => {
+                    // The item's text runs to the end of its last inline
+                    item_text.end = item_text.end.max(range.end);
+                    item_has_text = true;
                 }
                 Event::TaskListMarker(checked) => {
                     task_is_checked = Some(checked);
-                    task_description.clear();
+                    item_text = range.end..range.end;
+                    item_has_text = true;
                 }
                 Event::Start(Tag::Item) => {
                     in_list_item = true;
-                    list_item_text.clear();
+                    let raw = &content[range.clone()];
+                    let start = range.end - strip_bullet(raw).len();
+                    item_text = start..start;
+                    if item_depth == 1 {
+                        item_range = range;
+                        item_has_text = false;
+                    }
                 }
                 Event::End(TagEnd::Item) => {
                     in_list_item = false;
+                    let text = source_lines(&content[item_text.clone()]);
+                    item_text = 0..0;
+                    let details = details_start
+                        .take()
+                        .map(|start| dedent(&content[start..item_range.end]))
+                        .unwrap_or_default();
 
                     // If we have a task being built, finalize it
                     if let Some(checked) = task_is_checked.take() {
-                        let task = Task {
-                            description: task_description.trim().to_string(),
-                            completed: checked,
-                        };
+                        let mut task = Task::from_markdown(&text, checked, false, known_ids);
+                        task.details = details;
                         if current_h2 == "Tasks" {
                             current_phase_tasks.push(task);
                         } else if current_h2 == "Completed This Iteration" {
-                            pf.completed_this_iteration
-                                .push(task_description.trim().to_string());
+                            pf.completed_this_iteration.push(text);
+                        } else if NOTE_SECTIONS.contains(&current_h2.as_str()) && item_depth == 0 {
+                            section_notes.push(content[item_range.clone()].trim_end());
                         }
-                        task_description.clear();
-                    } else if current_h2 == "Recent Attempts" && !list_item_text.is_empty() {
-                        // Handle list items in Recent Attempts section
-                        let text = list_item_text.trim();
-                        if let Some(iteration_num) = current_h3.strip_prefix("Iteration ") {
-                            if let Ok(iteration) = iteration_num.trim().parse::<u32>() {
-                                // Find or create the attempt for this iteration
-                                let attempt = pf
-                                    .recent_attempts
-                                    .iter_mut()
-                                    .find(|a| a.iteration == iteration);
-                                if let Some(attempt) = attempt {
-                                    if let Some(tried) = text.strip_prefix("Tried:") {
-                                        attempt.tried = tried.trim().to_string();
-                                    } else if let Some(result) = text.strip_prefix("Result:") {
-                                        attempt.result = result.trim().to_string();
-                                    } else if let Some(next) = text.strip_prefix("Next:") {
-                                        attempt.next = Some(next.trim().to_string());
-                                    }
-                                } else {
-                                    // Create new attempt
-                                    let mut new_attempt = Attempt {
-                                        iteration,
-                                        tried: String::new(),
-                                        result: String::new(),
-                                        next: None,
-                                    };
-                                    if let Some(tried) = text.strip_prefix("Tried:") {
-                                        new_attempt.tried = tried.trim().to_string();
-                                    } else if let Some(result) = text.strip_prefix("Result:") {
-                                        new_attempt.result = result.trim().to_string();
-                                    } else if let Some(next) = text.strip_prefix("Next:") {
-                                        new_attempt.next = Some(next.trim().to_string());
-                                    }
-                                    pf.recent_attempts.push(new_attempt);
-                                }
+                    } else if let Some(text) = blocked_item(&current_h2, &text) {
+                        // `- [-]` isn't a task list marker, so blocked tasks arrive as list items
+                        let mut task = Task::from_markdown(text, false, true, known_ids);
+                        task.details = details;
+                        current_phase_tasks.push(task);
+                    } else if current_h2 == "Tasks" && !current_h3.is_empty() && item_depth == 0 {
+                        // Other list items stay with the phase's prose
+                        phase_description.push(content[item_range.clone()].trim_end());
+                    } else if current_h2 == "Tasks" && item_depth == 0 {
+                        // Non-task list items before the first phase
+                        section_notes.push(content[item_range.clone()].trim_end());
+                    } else if let Some((iteration, entry)) = attempt_entry(&current_h2, &current_h3, &text) {
+                        // Find or create the attempt for this iteration
+                        let attempt = match pf
+                            .recent_attempts
+                            .iter()
+                            .position(|a| a.iteration == iteration)
+                        {
+                            Some(index) => &mut pf.recent_attempts[index],
+                            None => {
+                                pf.recent_attempts.push(Attempt {
+                                    iteration,
+                                    tried: String::new(),
+                                    result: String::new(),
+                                    next: None,
+                                });
+                                pf.recent_attempts.last_mut().expect("just pushed")
                             }
+                        };
+                        match entry {
+                            AttemptEntry::Tried(tried) => attempt.tried = tried.to_string(),
+                            AttemptEntry::Result(result) => attempt.result = result.to_string(),
+                            AttemptEntry::Next(next) => attempt.next = Some(next.to_string()),
                         }
-                    } else if !list_item_text.is_empty() {
-                        // For other sections (like Testing Strategy), append list items to section text
-                        section_text.push_str("- ");
-                        section_text.push_str(&list_item_text);
-                        section_text.push('\n');
+                    } else if NOTE_SECTIONS.contains(&current_h2.as_str()) && item_depth == 0 {
+                        section_notes.push(content[item_range.clone()].trim_end());
                     }
-                    list_item_text.clear();
                 }
                 Event::Start(Tag::Table(_)) | Event::End(TagEnd::Table) => {
                     // Table boundaries handled via cell/row events
@@ -267,13 +979,9 @@ impl ProgressFile {
                     // Clear header row, we don't need it
                     table_row.clear();
                 }
-                Event::Start(Tag::TableCell) => {
-                    in_table_cell = true;
-                    cell_text.clear();
-                }
+                Event::Start(Tag::TableCell) => cell_range = range,
                 Event::End(TagEnd::TableCell) => {
-                    in_table_cell = false;
-                    table_row.push(cell_text.trim().to_string());
+                    table_row.push(unescape_cell(&content[cell_range.clone()]));
                 }
                 Event::End(TagEnd::TableRow) => {
                     // Parse iteration log row
@@ -293,24 +1001,24 @@ impl ProgressFile {
                     }
                     table_row.clear();
                 }
-                Event::SoftBreak | Event::HardBreak => {
-                    if in_list_item {
-                        list_item_text.push('\n');
-                    } else {
-                        section_text.push('\n');
-                    }
-                }
                 _ => {}
             }
         }
 
         // Flush final section
-        Self::flush_section(
+        Self::flush_phase(
             &mut pf,
             &current_h2,
-            &section_text,
             &current_h3,
             &mut current_phase_tasks,
+            &mut phase_description,
+        );
+        Self::flush_section(
+            &mut pf,
+            &current_h2,
+            &content[section_start..],
+            &last_known,
+            &mut section_notes,
         );
 
         // Validate that we parsed something meaningful
@@ -324,27 +1032,74 @@ impl ProgressFile {
         Ok(pf)
     }
 
+    /// Store the body of a `##` section: free-text sections keep their
+    /// markdown, unknown ones become extra sections, and text that isn't an
+    /// entry of a structured section becomes its notes
     fn flush_section(
         pf: &mut ProgressFile,
         h2: &str,
-        text: &str,
+        body: &str,
+        after: &Option<String>,
+        notes: &mut Vec<&str>,
+    ) {
+        let body = trim_blank_lines(body);
+        let notes = std::mem::take(notes).join("\n\n");
+        match h2 {
+            "" => {}
+            "Status" => pf.status = body.to_string(),
+            "Analysis" => pf.analysis = body.to_string(),
+            "Tasks" => pf.tasks_preamble = notes,
+            "Testing Strategy" => pf.testing_strategy = body.to_string(),
+            "Completed This Iteration" => pf.completed_notes = notes,
+            "Recent Attempts" => pf.attempts_notes = notes,
+            "Iteration Log" => pf.log_notes = notes,
+            _ if KNOWN_SECTIONS.contains(&h2) => {}
+            _ => pf.extra_sections.push(ExtraSection {
+                title: h2.to_string(),
+                body: body.to_string(),
+                after: after.clone(),
+            }),
+        }
+    }
+
+    /// Close the current `###` phase of the Tasks section
+    fn flush_phase(
+        pf: &mut ProgressFile,
+        h2: &str,
         h3: &str,
         phase_tasks: &mut Vec<Task>,
+        description: &mut Vec<&str>,
     ) {
-        let text = text.trim();
-        match h2 {
-            "Status" => pf.status = text.to_string(),
-            "Analysis" => pf.analysis = text.to_string(),
-            "Testing Strategy" => pf.testing_strategy = text.to_string(),
-            "Tasks" => {
-                if !h3.is_empty() && !phase_tasks.is_empty() {
-                    pf.tasks.push(TaskPhase {
-                        name: h3.to_string(),
-                        tasks: std::mem::take(phase_tasks),
-                    });
-                }
+        if h2 == "Tasks" && !h3.is_empty() && (!phase_tasks.is_empty() || !description.is_empty()) {
+            pf.tasks.push(TaskPhase {
+                name: h3.to_string(),
+                description: description.join("\n\n"),
+                tasks: std::mem::take(phase_tasks),
+            });
+        }
+        description.clear();
+    }
+
+    /// Write the extra sections that followed `after` in the original file
+    fn push_extra_sections(&self, md: &mut String, after: Option<&str>) {
+        for section in &self.extra_sections {
+            // Sections anchored to something unknown go last
+            let anchor = match section.after.as_deref() {
+                Some(known) if KNOWN_SECTIONS.contains(&known) => Some(known),
+                Some(_) => Some("Iteration Log"),
+                None => None,
+            };
+            if anchor != after {
+                continue;
+            }
+            if !md.ends_with("\n\n") {
+                md.push('\n');
+            }
+            md.push_str(&format!("## {}\n\n", section.title));
+            if !section.body.is_empty() {
+                md.push_str(&section.body);
+                md.push_str("\n\n");
             }
-            _ => {}
         }
     }
 
@@ -354,32 +1109,54 @@ impl ProgressFile {
 
         // Title
         md.push_str(&format!("# Progress: {}\n\n", self.name));
+        self.push_extra_sections(&mut md, None);
 
         // Status (PROG-01)
         md.push_str("## Status\n\n");
         md.push_str(&self.status);
         md.push_str("\n\n");
+        self.push_extra_sections(&mut md, Some("Status"));
 
         // Analysis (PROG-02)
         md.push_str("## Analysis\n\n");
         md.push_str(&self.analysis);
         md.push_str("\n\n");
+        self.push_extra_sections(&mut md, Some("Analysis"));
 
         // Tasks (PROG-03)
         md.push_str("## Tasks\n\n");
+        push_notes(&mut md, &self.tasks_preamble);
         for phase in &self.tasks {
             md.push_str(&format!("### {}\n\n", phase.name));
+            if !phase.description.is_empty() {
+                md.push_str(&phase.description);
+                md.push_str("\n\n");
+            }
             for task in &phase.tasks {
-                let checkbox = if task.completed { "[x]" } else { "[ ]" };
-                md.push_str(&format!("- {} {}\n", checkbox, task.description));
+                md.push_str(&format!("- {}\n", task.to_markdown()));
+                if !task.details.is_empty() {
+                    // A nested list continues the item; other blocks need a blank line
+                    if !task.details.starts_with(['-', '*', '+']) {
+                        md.push('\n');
+                    }
+                    for line in task.details.lines() {
+                        if !line.is_empty() {
+                            md.push_str("  ");
+                            md.push_str(line);
+                        }
+                        md.push('\n');
+                    }
+                }
             }
             md.push('\n');
         }
+        self.push_extra_sections(&mut md, Some("Tasks"));
 
         // Testing Strategy (PROG-04)
         md.push_str("## Testing Strategy\n\n");
         md.push_str(&self.testing_strategy);
         md.push_str("\n\n");
+        self.push_extra_sections(&mut md, Some("Testing Strategy"));
 
         // Completed This Iteration (PROG-05)
         md.push_str("## Completed This Iteration\n\n");
@@ -387,6 +1164,8 @@ impl ProgressFile {
             md.push_str(&format!("- [x] {}\n", item));
         }
         md.push('\n');
+        push_notes(&mut md, &self.completed_notes);
+        self.push_extra_sections(&mut md, Some("Completed This Iteration"));
 
         // Recent Attempts (PROG-06)
         md.push_str("## Recent Attempts\n\n");
@@ -399,6 +1178,8 @@ impl ProgressFile {
             }
             md.push('\n');
         }
+        push_notes(&mut md, &self.attempts_notes);
+        self.push_extra_sections(&mut md, Some("Recent Attempts"));
 
         // Iteration Log (PROG-07)
         md.push_str("## Iteration Log\n\n");
@@ -407,32 +1188,76 @@ impl ProgressFile {
         for entry in &self.iteration_log {
             md.push_str(&format!(
                 "| {} | {} | {} | {} | {} |\n",
-                entry.iteration, entry.started, entry.duration, entry.tasks_completed, entry.notes
+                entry.iteration,
+                escape_cell(&entry.started),
+                escape_cell(&entry.duration),
+                entry.tasks_completed,
+                escape_cell(&entry.notes)
             ));
         }
+        if !self.log_notes.is_empty() {
+            // A blank line ends the table
+            md.push('\n');
+            push_notes(&mut md, &self.log_notes);
+        }
+        self.push_extra_sections(&mut md, Some("Iteration Log"));
 
         md
     }
 
-    /// Write progress file atomically (crash-safe)
-    /// Uses temp file + rename pattern for durability
-    pub fn write(&self, path: &std::path::Path) -> Result<(), RslphError> {
-        use atomicwrites::{AllowOverwrite, AtomicFile};
-        use std::io::Write;
+    /// Parse content in the given format
+    ///
+    /// Structured formats go through serde and get the same dependency
+    /// checks as markdown.
+    pub fn parse_as(content: &str, format: ProgressFormat) -> Result<Self, RslphError> {
+        let pf: Self = match format {
+            ProgressFormat::Markdown => return Self::parse(content),
+            ProgressFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            ProgressFormat::Yaml => serde_norway::from_str(content).map_err(|e| e.to_string()),
+            ProgressFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| {
+            RslphError::ProgressParse(format!("Invalid {} progress file: {}", format, e))
+        })?;
+        pf.validate_dependencies()?;
+        Ok(pf)
+    }
 
-        let content = self.to_markdown();
-        let af = AtomicFile::new(path, AllowOverwrite);
+    /// Parse an agent response holding a progress file in the given format
+    ///
+    /// A structured response may be wrapped in a code fence.
+    pub fn parse_response(text: &str, format: ProgressFormat) -> Result<Self, RslphError> {
+        if !format.is_structured() {
+            return Self::parse(text);
+        }
+        Self::parse_as(unfence(text), format)
+    }
 
-        af.write(|f| f.write_all(content.as_bytes()))
-            .map_err(|e| RslphError::Io(e.into()))?;
+    /// Render the progress file in the given format
+    pub fn render(&self, format: ProgressFormat) -> Result<String, RslphError> {
+        match format {
+            ProgressFormat::Markdown => Ok(self.to_markdown()),
+            ProgressFormat::Json => serde_json::to_string_pretty(self)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string()),
+            ProgressFormat::Yaml => serde_norway::to_string(self).map_err(|e| e.to_string()),
+            ProgressFormat::Toml => toml::to_string(self).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| RslphError::ProgressParse(format!("Cannot render {}: {}", format, e)))
+    }
 
-        Ok(())
+    /// Write progress file atomically (crash-safe), in the format its
+    /// extension names
+    /// Uses temp file + rename pattern for durability
+    pub fn write(&self, path: &Path) -> Result<(), RslphError> {
+        let content = self.render(ProgressFormat::from_path(path))?;
+        write_atomically(path, &content)
     }
 
-    /// Load progress file from disk
-    pub fn load(path: &std::path::Path) -> Result<Self, RslphError> {
+    /// Load progress file from disk, in the format its extension names
+    pub fn load(path: &Path) -> Result<Self, RslphError> {
         let content = std::fs::read_to_string(path)?;
-        Self::parse(&content)
+        Self::parse_as(&content, ProgressFormat::from_path(path))
     }
 
     /// Mark a task as completed
@@ -442,6 +1267,7 @@ impl ProgressFile {
                 for task in &mut phase.tasks {
                     if task.description == task_description && !task.completed {
                         task.completed = true;
+                        task.blocked = None;
                         self.completed_this_iteration
                             .push(task_description.to_string());
                         return true;
@@ -452,6 +1278,21 @@ impl ProgressFile {
         false
     }
 
+    /// Mark an incomplete task blocked so the build skips it
+    pub fn block_task(&mut self, phase_name: &str, task_description: &str, reason: &str) -> bool {
+        for phase in &mut self.tasks {
+            if phase.name == phase_name {
+                for task in &mut phase.tasks {
+                    if task.description == task_description && !task.completed {
+                        task.blocked = Some(reason.to_string());
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
     /// Add an attempt record
     pub fn add_attempt(&mut self, iteration: u32, tried: &str, result: &str, next: Option<&str>) {
         self.recent_attempts.push(Attempt {
@@ -497,6 +1338,107 @@ impl ProgressFile {
     pub fn mark_done(&mut self, message: &str) {
         self.status = format!("RALPH_DONE - {}", message);
     }
+
+    /// Apply the changes made from `base` to this file on top of `onto`, a
+    /// newer copy of the same file edited by someone else (e.g. `rslph task`).
+    ///
+    /// Sections this file left alone keep their content from `onto`. Tasks
+    /// it changed, removed or added and the attempts and log entries it
+    /// appended are carried over; its version wins where both changed.
+    pub fn rebase(&self, base: &ProgressFile, onto: &ProgressFile) -> ProgressFile {
+        fn take<T: Clone + PartialEq>(ours: &T, base: &T, merged: &mut T) {
+            if ours != base {
+                *merged = ours.clone();
+            }
+        }
+
+        let mut merged = onto.clone();
+        take(&self.name, &base.name, &mut merged.name);
+        take(&self.status, &base.status, &mut merged.status);
+        take(&self.analysis, &base.analysis, &mut merged.analysis);
+        take(&self.tasks_preamble, &base.tasks_preamble, &mut merged.tasks_preamble);
+        take(&self.testing_strategy, &base.testing_strategy, &mut merged.testing_strategy);
+        take(
+            &self.completed_this_iteration,
+            &base.completed_this_iteration,
+            &mut merged.completed_this_iteration,
+        );
+        take(&self.completed_notes, &base.completed_notes, &mut merged.completed_notes);
+        take(&self.attempts_notes, &base.attempts_notes, &mut merged.attempts_notes);
+        take(&self.log_notes, &base.log_notes, &mut merged.log_notes);
+        take(&self.extra_sections, &base.extra_sections, &mut merged.extra_sections);
+
+        // Changed and removed tasks, wherever they are now
+        for before in base.tasks.iter().flat_map(|p| &p.tasks) {
+            let after = self.find_task(before);
+            if after == Some(before) {
+                continue;
+            }
+            let Some((p, t)) = merged.task_position(before) else {
+                continue;
+            };
+            match after {
+                Some(after) => merged.tasks[p].tasks[t] = after.clone(),
+                None => {
+                    merged.tasks[p].tasks.remove(t);
+                }
+            }
+        }
+
+        // Phase prose and new tasks, by phase name
+        for phase in &self.tasks {
+            let base_phase = base.tasks.iter().find(|p| p.name == phase.name);
+            if let Some(base_phase) = base_phase {
+                if let Some(target) = merged.tasks.iter_mut().find(|p| p.name == phase.name) {
+                    take(&phase.description, &base_phase.description, &mut target.description);
+                }
+            }
+            for task in &phase.tasks {
+                if base.task_position(task).is_some() || merged.task_position(task).is_some() {
+                    continue;
+                }
+                match merged.tasks.iter_mut().find(|p| p.name == phase.name) {
+                    Some(target) => target.tasks.push(task.clone()),
+                    None => merged.tasks.push(TaskPhase {
+                        name: phase.name.clone(),
+                        description: phase.description.clone(),
+                        tasks: vec![task.clone()],
+                    }),
+                }
+            }
+        }
+
+        for attempt in &self.recent_attempts {
+            if !base.recent_attempts.contains(attempt) && !merged.recent_attempts.contains(attempt) {
+                merged.recent_attempts.push(attempt.clone());
+            }
+        }
+        for entry in &self.iteration_log {
+            if !base.iteration_log.contains(entry) && !merged.iteration_log.contains(entry) {
+                merged.iteration_log.push(entry.clone());
+            }
+        }
+        merged
+    }
+
+    /// Find the task matching `task`, by ID when both have one, else by description
+    fn find_task(&self, task: &Task) -> Option<&Task> {
+        self.task_position(task).map(|(p, t)| &self.tasks[p].tasks[t])
+    }
+
+    /// Phase and task index of the task matching `task`
+    fn task_position(&self, task: &Task) -> Option<(usize, usize)> {
+        self.tasks.iter().enumerate().find_map(|(p, phase)| {
+            phase
+                .tasks
+                .iter()
+                .position(|other| match (&other.id, &task.id) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => other.description == task.description,
+                })
+                .map(|t| (p, t))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -566,19 +1508,24 @@ Some analysis notes here.
                         Task {
                             description: "Task 1".to_string(),
                             completed: true,
+                            ..Default::default()
                         },
                         Task {
                             description: "Task 2".to_string(),
                             completed: false,
+                            ..Default::default()
                         },
                     ],
+                    ..Default::default()
                 },
                 TaskPhase {
                     name: "Phase 2".to_string(),
                     tasks: vec![Task {
                         description: "Task 3".to_string(),
                         completed: false,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -597,12 +1544,15 @@ Some analysis notes here.
                     Task {
                         description: "Task 1".to_string(),
                         completed: true,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task 2".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -612,6 +1562,105 @@ Some analysis notes here.
         assert_eq!(task.description, "Task 2");
     }
 
+    const DAG_PROGRESS: &str = r#"# Progress: DAG
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [ ] T3: Wire up the API (after T1, T2)
+- [x] T1: Create schema
+- [ ] T2: Add models (after T1)
+- [ ] Write docs
+
+### Phase 2
+
+- [ ] Fix: handle empty input (after T3)
+"#;
+
+    #[test]
+    fn test_parse_task_ids_and_dependencies_round_trip() {
+        let pf = ProgressFile::parse(DAG_PROGRESS).expect("Should parse");
+        let tasks = &pf.tasks[0].tasks;
+        assert_eq!(tasks[0].id.as_deref(), Some("T3"));
+        assert_eq!(tasks[0].description, "Wire up the API");
+        assert_eq!(tasks[0].depends_on, ["T1", "T2"]);
+        assert!(tasks[3].id.is_none());
+        assert!(tasks[3].depends_on.is_empty());
+
+        // "Fix:" is not an ID, so it stays in the description
+        let fix = &pf.tasks[1].tasks[0];
+        assert!(fix.id.is_none());
+        assert_eq!(fix.description, "Fix: handle empty input");
+        assert_eq!(fix.depends_on, ["T3"]);
+
+        let md = pf.to_markdown();
+        assert!(md.contains("- [ ] T3: Wire up the API (after T1, T2)\n"));
+        assert!(md.contains("- [ ] Fix: handle empty input (after T3)\n"));
+        let reparsed = ProgressFile::parse(&md).expect("Should reparse");
+        assert_eq!(reparsed.tasks[0].tasks[0].depends_on, ["T1", "T2"]);
+    }
+
+    #[test]
+    fn test_next_task_waits_for_dependencies() {
+        let mut pf = ProgressFile::parse(DAG_PROGRESS).expect("Should parse");
+        let (_, task) = pf.next_task().expect("T2 is ready");
+        assert_eq!(task.id.as_deref(), Some("T2"));
+
+        assert!(pf.complete_task("Phase 1", "Add models"));
+        let (_, task) = pf.next_task().expect("T3 is ready");
+        assert_eq!(task.id.as_deref(), Some("T3"));
+
+        // A blocked dependency holds back everything after it
+        assert!(pf.block_task("Phase 1", "Wire up the API", "API is down"));
+        let (_, task) = pf.next_task().expect("docs are ready");
+        assert_eq!(task.description, "Write docs");
+        assert!(pf.complete_task("Phase 1", "Write docs"));
+        assert!(pf.next_task().is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_dependencies() {
+        let cyclic = "# Progress: C\n\n## Tasks\n\n### P\n\n- [ ] A1: One (after C1)\n- [ ] B1: Two (after A1)\n- [ ] C1: Three (after B1)\n";
+        let err = ProgressFile::parse(cyclic).unwrap_err().to_string();
+        assert!(err.contains("cycle: A1 -> C1 -> B1 -> A1"), "{}", err);
+
+        let self_loop = "# Progress: C\n\n## Tasks\n\n### P\n\n- [ ] A1: One (after A1)\n";
+        assert!(ProgressFile::parse(self_loop).is_err());
+
+    }
+
+    #[test]
+    fn test_parse_keeps_lookalike_ids_as_text() {
+        // Not IDs: a dependency on no task, and a prefix two tasks share
+        let content = "# Progress: C\n\n## Tasks\n\n### P\n\n- [ ] Upgrade the API (after v2)\n\n### Q\n\n\
+                       - [ ] Step1: Install (priority: high)\n- [ ] T1: Deploy (after T2)\n- [ ] T2: Test\n\n\
+                       ### R\n\n- [ ] Step1: Configure\n";
+        let pf = ProgressFile::parse(content).expect("parses");
+        let tasks: Vec<&Task> = pf.tasks.iter().flat_map(|p| &p.tasks).collect();
+
+        assert_eq!(tasks[0].description, "Upgrade the API (after v2)");
+        assert!(tasks[0].depends_on.is_empty());
+        assert_eq!(tasks[1].id, None);
+        assert_eq!(tasks[1].description, "Step1: Install");
+        assert_eq!(tasks[1].priority, Some(Priority::High));
+        assert_eq!(tasks[4].description, "Step1: Configure");
+        // Real IDs in the same file still work
+        assert_eq!(tasks[2].id.as_deref(), Some("T1"));
+        assert_eq!(tasks[2].depends_on, vec!["T2"]);
+
+        assert_eq!(
+            pf.next_task().map(|(_, t)| t.description.as_str()),
+            Some("Upgrade the API (after v2)")
+        );
+        assert!(pf.to_markdown().contains("- [ ] Upgrade the API (after v2)\n"));
+        assert!(pf.to_markdown().contains("- [ ] Step1: Configure\n"));
+    }
+
     #[test]
     fn test_parse_basic_sections() {
         let pf = ProgressFile::parse(SAMPLE_PROGRESS).expect("Should parse");
@@ -648,9 +1697,205 @@ Some analysis notes here.
         let markdown = original.to_markdown();
         let reparsed = ProgressFile::parse(&markdown).expect("Should reparse");
 
-        assert_eq!(original.name, reparsed.name);
-        assert_eq!(original.status, reparsed.status);
-        assert_eq!(original.tasks.len(), reparsed.tasks.len());
+        assert_eq!(original, reparsed);
+    }
+
+    const ANNOTATED_PROGRESS: &str = r#"# Progress: Notes
+
+## Status
+
+In Progress
+
+## Links
+
+- Design doc: [spec](https://example.com/spec)
+
+## Analysis
+
+Uses **bold** text and `code`.
+
+### Risks
+
+Parsing edge cases.
+
+## Tasks
+
+### Phase 1: Setup
+
+Set up the project first.
+See [the guide](https://example.com/guide).
+
+- [x] Create repo
+  - Use the template
+  - [ ] Enable CI
+- [ ] Add config
+
+  Read it from `.env` first.
+- [-] Deploy (blocked: no credentials)
+  - Ask ops for a key
+
+## Testing Strategy
+
+- Unit tests
+* Integration tests
+
+## Decisions
+
+Keep markdown as the primary format.
+
+## Completed This Iteration
+
+## Recent Attempts
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+"#;
+
+    #[test]
+    fn test_parse_keeps_unknown_sections_and_task_details() {
+        let pf = ProgressFile::parse(ANNOTATED_PROGRESS).expect("Should parse");
+        assert_eq!(
+            pf.extra_sections,
+            vec![
+                ExtraSection {
+                    title: "Links".to_string(),
+                    body: "- Design doc: [spec](https://example.com/spec)".to_string(),
+                    after: Some("Status".to_string()),
+                },
+                ExtraSection {
+                    title: "Decisions".to_string(),
+                    body: "Keep markdown as the primary format.".to_string(),
+                    after: Some("Testing Strategy".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            pf.analysis,
+            "Uses **bold** text and `code`.\n\n### Risks\n\nParsing edge cases."
+        );
+        assert_eq!(pf.testing_strategy, "- Unit tests\n* Integration tests");
+
+        let phase = &pf.tasks[0];
+        assert_eq!(
+            phase.description,
+            "Set up the project first.\nSee [the guide](https://example.com/guide)."
+        );
+        // Nested checkboxes are details, not tasks of their own
+        assert_eq!(pf.total_tasks(), 3);
+        assert_eq!(
+            phase.tasks[0].details,
+            "- Use the template\n- [ ] Enable CI"
+        );
+        assert_eq!(phase.tasks[1].description, "Add config");
+        assert_eq!(phase.tasks[1].details, "Read it from `.env` first.");
+        assert!(phase.tasks[2].is_blocked());
+        assert_eq!(phase.tasks[2].details, "- Ask ops for a key");
+
+        // Everything survives a rewrite, in its original place
+        let markdown = pf.to_markdown();
+        assert!(markdown.contains("## Status\n\nIn Progress\n\n## Links\n\n"));
+        assert!(markdown.contains("- [x] Create repo\n  - Use the template\n  - [ ] Enable CI\n"));
+        assert!(markdown.contains("- [ ] Add config\n\n  Read it from `.env` first.\n"));
+        assert!(markdown.contains("\n\n## Decisions\n\nKeep markdown"));
+        assert_eq!(ProgressFile::parse(&markdown).expect("Should reparse"), pf);
+    }
+
+    #[test]
+    fn test_parse_keeps_prose_around_entries() {
+        let content = r#"# Progress: Notes
+
+## Tasks
+
+Work top to bottom.
+
+### Phase 1
+
+- [ ] First
+
+#### Later
+
+- [ ] Second
+
+## Completed This Iteration
+
+- [x] Something
+- Reviewed by ops
+
+## Recent Attempts
+
+Keep only the last few.
+
+### Iteration 1
+
+- Tried: A
+- Result: B
+- Flaky on CI
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+| 1 | now | 1m | 1 | ok |
+
+Older runs were archived.
+"#;
+        let pf = ProgressFile::parse(content).expect("Should parse");
+        assert_eq!(pf.tasks_preamble, "Work top to bottom.");
+        // A sub-heading is phase prose, not a second phase
+        assert_eq!(pf.tasks.len(), 1);
+        assert_eq!(pf.tasks[0].description, "#### Later");
+        assert_eq!(pf.tasks[0].tasks.len(), 2);
+        assert_eq!(pf.completed_this_iteration, vec!["Something"]);
+        assert_eq!(pf.completed_notes, "- Reviewed by ops");
+        assert_eq!(pf.recent_attempts.len(), 1);
+        assert_eq!(pf.attempts_notes, "Keep only the last few.\n\n- Flaky on CI");
+        assert_eq!(pf.iteration_log.len(), 1);
+        assert_eq!(pf.log_notes, "Older runs were archived.");
+
+        let markdown = pf.to_markdown();
+        assert!(markdown.contains("## Tasks\n\nWork top to bottom.\n\n### Phase 1\n"));
+        assert!(markdown.contains("| ok |\n\nOlder runs were archived.\n"));
+        assert_eq!(ProgressFile::parse(&markdown).expect("Should reparse"), pf);
+    }
+
+    #[test]
+    fn test_escapes_survive_rewrites() {
+        let content = r#"# Progress: Escapes
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase *one*
+
+- [ ] Escape \*stars\* and [link](https://example.com) (priority: high)
+
+## Iteration Log
+
+| Iteration | Started | Duration | Tasks Completed | Notes |
+|-----------|---------|----------|-----------------|-------|
+| 1 | 2024-01-01 10:00 | 5m | 0 | used a \| pipe |
+"#;
+
+        let pf = ProgressFile::parse(content).expect("parse");
+        assert_eq!(pf.tasks[0].name, "Phase *one*");
+        let task = &pf.tasks[0].tasks[0];
+        assert_eq!(
+            task.description,
+            "Escape \\*stars\\* and [link](https://example.com)"
+        );
+        assert_eq!(task.priority, Some(Priority::High));
+        assert_eq!(pf.iteration_log[0].notes, "used a | pipe");
+
+        let once = ProgressFile::parse(&pf.to_markdown()).expect("first rewrite");
+        let twice = ProgressFile::parse(&once.to_markdown()).expect("second rewrite");
+        assert_eq!(once, pf);
+        assert_eq!(twice, pf);
+        assert!(pf.to_markdown().contains("| used a \\| pipe |"));
     }
 
     #[test]
@@ -666,7 +1911,9 @@ Some analysis notes here.
                 tasks: vec![Task {
                     description: "Task 1".to_string(),
                     completed: false,
+                    ..Default::default()
                 }],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -696,12 +1943,15 @@ Some analysis notes here.
                     Task {
                         description: "Task A".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                     Task {
                         description: "Task B".to_string(),
                         completed: false,
+                        ..Default::default()
                     },
                 ],
+                ..Default::default()
             }],
             ..Default::default()
         };
@@ -712,6 +1962,167 @@ Some analysis notes here.
         assert_eq!(pf.completed_this_iteration.len(), 1);
     }
 
+    #[test]
+    fn test_block_task_is_skipped_and_round_trips() {
+        let mut pf = ProgressFile {
+            name: "Blocked".to_string(),
+            tasks: vec![TaskPhase {
+                name: "Phase 1".to_string(),
+                tasks: vec![
+                    Task {
+                        description: "Task A".to_string(),
+                        completed: false,
+                        ..Default::default()
+                    },
+                    Task {
+                        description: "Task B".to_string(),
+                        completed: false,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert!(pf.block_task("Phase 1", "Task A", "needs credentials; ask ops"));
+        assert!(!pf.block_task("Phase 1", "Missing", "n/a"));
+        assert_eq!(pf.blocked_tasks(), 1);
+        assert_eq!(pf.remaining_tasks(), 1);
+        assert!(!pf.is_finished_with_blocked());
+        assert_eq!(pf.next_task().expect("next").1.description, "Task B");
+
+        let markdown = pf.to_markdown();
+        assert!(markdown.contains("- [-] Task A (blocked: needs credentials, ask ops)"));
+        let parsed = ProgressFile::parse(&markdown).expect("Should parse");
+        let task = &parsed.tasks[0].tasks[0];
+        assert!(task.is_blocked());
+        assert_eq!(task.description, "Task A");
+        assert_eq!(task.blocked.as_deref(), Some("needs credentials, ask ops"));
+        assert_eq!(parsed.blocked_tasks(), 1);
+
+        assert!(pf.complete_task("Phase 1", "Task B"));
+        assert!(pf.is_finished_with_blocked());
+        assert!(!pf.is_done());
+    }
+
+    #[test]
+    fn test_parse_task_metadata_round_trip() {
+        let content = r#"# Progress: Metadata
+
+## Tasks
+
+### Phase 1
+
+- [ ] T1: Add models (priority: high; tags: db, backend; estimate: 2)
+- [-] T2: Deploy (after T1; blocked: no credentials)
+- [~] Legacy skipped task
+- [ ] BLOCKED: Old-style blocked task
+- [ ] Call helper (the fast one)
+- [ ] Migrate (estimate: 2 days)
+"#;
+        let pf = ProgressFile::parse(content).expect("Should parse");
+        let tasks = &pf.tasks[0].tasks;
+        assert_eq!(tasks[0].priority, Some(Priority::High));
+        assert_eq!(tasks[0].tags, vec!["db", "backend"]);
+        assert_eq!(tasks[0].estimate, Some(2));
+        assert_eq!(tasks[0].description, "Add models");
+        assert_eq!(tasks[1].depends_on, vec!["T1"]);
+        assert_eq!(tasks[1].blocked.as_deref(), Some("no credentials"));
+        assert!(tasks[2].is_blocked());
+        assert_eq!(tasks[2].description, "Legacy skipped task");
+        assert!(tasks[3].is_blocked());
+        assert_eq!(tasks[3].description, "Old-style blocked task");
+        // A group that isn't all annotations stays in the description
+        assert!(!tasks[4].is_blocked());
+        assert_eq!(tasks[4].description, "Call helper (the fast one)");
+        // An estimate is a number of iterations; anything else is kept as written
+        assert_eq!(tasks[5].estimate, None);
+        assert_eq!(tasks[5].description, "Migrate (estimate: 2 days)");
+        assert_eq!(pf.blocked_tasks(), 3);
+        assert_eq!(pf.remaining_tasks(), 3);
+
+        let markdown = pf.to_markdown();
+        assert!(markdown
+            .contains("- [ ] T1: Add models (priority: high; tags: db, backend; estimate: 2)"));
+        assert!(markdown.contains("- [-] T2: Deploy (after T1; blocked: no credentials)"));
+        assert!(markdown.contains("- [-] Old-style blocked task\n"));
+        assert!(markdown.contains("- [ ] Migrate (estimate: 2 days)\n"));
+        let reparsed = ProgressFile::parse(&markdown).expect("Should reparse");
+        assert_eq!(reparsed.to_markdown(), markdown);
+    }
+
+    #[test]
+    fn test_progress_format_from_path() {
+        let cases = [
+            ("progress.md", ProgressFormat::Markdown),
+            ("PROGRESS", ProgressFormat::Markdown),
+            ("plan/progress.json", ProgressFormat::Json),
+            ("progress.YAML", ProgressFormat::Yaml),
+            ("progress.yml", ProgressFormat::Yaml),
+            ("progress.toml", ProgressFormat::Toml),
+        ];
+        for (path, format) in cases {
+            assert_eq!(
+                ProgressFormat::from_path(Path::new(path)),
+                format,
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_structured_formats_round_trip() {
+        let mut pf = ProgressFile::parse(DAG_PROGRESS).expect("Should parse");
+        pf.tasks[0].tasks[0].priority = Some(Priority::High);
+        pf.tasks[0].tasks[0].tags = vec!["api".to_string()];
+        pf.add_attempt(1, "Ran tests", "Two failures", None);
+        pf.log_iteration(1, "2026-01-01 10:00", "2m", 1, "Models done");
+        let markdown = pf.to_markdown();
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        for name in ["progress.json", "progress.yaml", "progress.toml"] {
+            let path = dir.path().join(name);
+            pf.write(&path).expect("write");
+            let loaded = ProgressFile::load(&path).expect("load");
+            assert_eq!(loaded.to_markdown(), markdown, "{}", name);
+        }
+
+        let json = pf.render(ProgressFormat::Json).expect("render");
+        assert!(json.contains("\"depends_on\""));
+        assert!(json.contains("\"priority\": \"high\""));
+        assert!(!json.contains("\"estimate\""), "unset metadata is omitted");
+    }
+
+    #[test]
+    fn test_parse_structured_response_and_errors() {
+        let reply =
+            "Here you go:\n```json\n{\"name\": \"Api\", \"tasks\": [{\"name\": \"Phase 1\", \
+                     \"tasks\": [{\"description\": \"Add models\", \"completed\": true}]}]}\n```\n";
+        let pf = ProgressFile::parse_response(reply, ProgressFormat::Json).expect("Should parse");
+        assert_eq!(pf.name, "Api");
+        assert_eq!(pf.completed_tasks(), 1);
+
+        let err = ProgressFile::parse_as("name = ", ProgressFormat::Toml).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid toml progress file"),
+            "{}",
+            err
+        );
+
+        let cyclic = r#"
+name: Cycle
+tasks:
+  - name: Phase 1
+    tasks:
+      - { description: A, id: A1, depends_on: [B1] }
+      - { description: B, id: B1, depends_on: [A1] }
+"#;
+        let err = ProgressFile::parse_as(cyclic, ProgressFormat::Yaml).unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{}", err);
+    }
+
     #[test]
     fn test_mark_done() {
         let mut pf = ProgressFile::default();
@@ -740,6 +2151,39 @@ Some analysis notes here.
         assert_eq!(progress.recent_attempts[2].iteration, 5);
     }
 
+    #[test]
+    fn test_rebase_keeps_edits_made_on_disk() {
+        let base = ProgressFile::parse(SAMPLE_PROGRESS).expect("Should parse");
+
+        // The iteration checks off a task and records an attempt...
+        let mut ours = base.clone();
+        ours.tasks[0].tasks[1].completed = true;
+        ours.add_attempt(3, "Wrote tests", "Passed", None);
+        ours.status = "Almost done".to_string();
+
+        // ...while someone adds a task and rewrites the analysis on disk
+        let mut onto = base.clone();
+        onto.tasks[0].tasks.insert(
+            0,
+            Task {
+                description: "Late task".to_string(),
+                ..Default::default()
+            },
+        );
+        onto.analysis = "Edited by hand.".to_string();
+
+        let merged = ours.rebase(&base, &onto);
+        assert_eq!(merged.tasks[0].tasks[0].description, "Late task");
+        assert!(merged.tasks[0].tasks[2].completed);
+        assert_eq!(merged.analysis, "Edited by hand.");
+        assert_eq!(merged.status, "Almost done");
+        assert_eq!(merged.recent_attempts, ours.recent_attempts);
+        assert_eq!(merged.total_tasks(), ours.total_tasks() + 1);
+
+        // Without edits on disk the iteration's file is kept as is
+        assert_eq!(ours.rebase(&base, &base), ours);
+    }
+
     #[test]
     fn test_parse_task_with_inline_code() {
         let content = r#"# Progress: Test
@@ -882,4 +2326,224 @@ Some analysis content here.
         let pf = result.unwrap();
         assert!(!pf.tasks.is_empty());
     }
+
+    mod round_trip {
+        use super::*;
+        use proptest::collection::vec;
+        use proptest::prelude::*;
+        use proptest::sample::select;
+
+        fn plain_words() -> impl Strategy<Value = String> {
+            vec("[a-z]{1,8}", 1..5).prop_map(|w| w.join(" "))
+        }
+
+        /// Words mixed with markdown punctuation, escapes, code and links.
+        /// A plain word comes first so a line never opens a list or heading.
+        fn words() -> impl Strategy<Value = String> {
+            let markup = select(vec![
+                "|",
+                "*",
+                "(",
+                ";",
+                ":",
+                "a|b",
+                "x*y",
+                "\\*stars\\*",
+                "\\|",
+                "`code`",
+                "&amp;",
+                "[link](https://example.com)",
+            ])
+            .prop_map(str::to_string);
+            let word = prop_oneof![3 => "[a-z]{1,8}", 1 => markup];
+            ("[a-z]{1,8}", vec(word, 0..5)).prop_map(|(first, rest)| {
+                std::iter::once(first)
+                    .chain(rest)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+        }
+
+        fn lines() -> impl Strategy<Value = String> {
+            vec(words(), 1..4).prop_map(|l| l.join("\n"))
+        }
+
+        fn optional(text: impl Strategy<Value = String>) -> impl Strategy<Value = String> {
+            prop_oneof![Just(String::new()), text]
+        }
+
+        /// Free text that isn't an entry: paragraphs, sub-headings or a plain item
+        fn prose() -> impl Strategy<Value = String> {
+            let block = prop_oneof![
+                3 => lines(),
+                1 => words().prop_map(|w| format!("#### {}", w)),
+                1 => words().prop_map(|w| format!("- {}", w)),
+            ];
+            vec(block, 1..3).prop_map(|blocks| blocks.join("\n\n"))
+        }
+
+        fn details() -> impl Strategy<Value = String> {
+            let item = (any::<bool>(), words()).prop_map(|(checkbox, text)| {
+                if checkbox {
+                    format!("- [ ] {}", text)
+                } else {
+                    format!("- {}", text)
+                }
+            });
+            optional(prop_oneof![
+                vec(item, 1..3).prop_map(|items| items.join("\n")),
+                lines(),
+            ])
+        }
+
+        fn task() -> impl Strategy<Value = Task> {
+            (
+                words(),
+                any::<bool>(),
+                // Blocked reasons sit inside the annotation group, so no `(` or `;`
+                prop::option::of(optional(plain_words())),
+                prop::option::of(select(vec![
+                    Priority::High,
+                    Priority::Medium,
+                    Priority::Low,
+                ])),
+                vec("[a-z]{1,6}", 0..3),
+                prop::option::of(0u32..10),
+                details(),
+            )
+                .prop_map(
+                    |(description, completed, blocked, priority, tags, estimate, details)| Task {
+                        description,
+                        completed,
+                        blocked: blocked.filter(|_| !completed),
+                        priority,
+                        tags,
+                        estimate,
+                        details,
+                        ..Default::default()
+                    },
+                )
+        }
+
+        fn phase() -> impl Strategy<Value = TaskPhase> {
+            (
+                words(),
+                optional(prose()),
+                vec((task(), any::<bool>()), 1..4),
+            )
+                .prop_map(|(name, description, tasks)| TaskPhase {
+                    name,
+                    description,
+                    // IDs are numbered once all phases exist
+                    tasks: tasks
+                        .into_iter()
+                        .map(|(mut task, has_id)| {
+                            if has_id {
+                                task.id = Some(String::new());
+                            }
+                            task
+                        })
+                        .collect(),
+                })
+        }
+
+        fn extra_section() -> impl Strategy<Value = ExtraSection> {
+            let anchors: Vec<Option<String>> = std::iter::once(None)
+                .chain(KNOWN_SECTIONS.iter().map(|s| Some(s.to_string())))
+                .collect();
+            (words(), optional(lines()), select(anchors))
+                .prop_map(|(title, body, after)| ExtraSection { title, body, after })
+        }
+
+        fn progress_file() -> impl Strategy<Value = ProgressFile> {
+            (
+                (words(), optional(words()), optional(lines()), optional(prose())),
+                vec(phase(), 0..4),
+                optional(lines()),
+                vec(words(), 0..3),
+                vec((words(), words(), prop::option::of(words())), 0..3),
+                vec((words(), words(), 0u32..5, words()), 0..3),
+                vec(extra_section(), 0..4),
+                (optional(prose()), optional(prose()), optional(prose())),
+            )
+                .prop_map(
+                    |(
+                        (name, status, analysis, tasks_preamble),
+                        mut tasks,
+                        testing_strategy,
+                        completed_this_iteration,
+                        attempts,
+                        log,
+                        mut extra_sections,
+                        (completed_notes, attempts_notes, log_notes),
+                    )| {
+                        // Number the tasks that got an ID, each depending on the previous one
+                        let mut previous: Option<String> = None;
+                        for (n, task) in tasks
+                            .iter_mut()
+                            .flat_map(|p| &mut p.tasks)
+                            .filter(|t| t.id.is_some())
+                            .enumerate()
+                        {
+                            let id = format!("T{}", n + 1);
+                            task.depends_on = previous.iter().cloned().collect();
+                            task.id = Some(id.clone());
+                            previous = Some(id);
+                        }
+                        // Extra sections come back grouped by the section they follow
+                        extra_sections.sort_by_key(|s| {
+                            s.after
+                                .as_deref()
+                                .and_then(|after| KNOWN_SECTIONS.iter().position(|k| *k == after))
+                                .map_or(0, |i| i + 1)
+                        });
+                        ProgressFile {
+                            name,
+                            status,
+                            analysis,
+                            tasks_preamble,
+                            tasks,
+                            testing_strategy,
+                            completed_this_iteration,
+                            completed_notes,
+                            recent_attempts: attempts
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, (tried, result, next))| Attempt {
+                                    iteration: i as u32 + 1,
+                                    tried,
+                                    result,
+                                    next,
+                                })
+                                .collect(),
+                            iteration_log: log
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, (started, duration, tasks_completed, notes))| {
+                                    IterationEntry {
+                                        iteration: i as u32 + 1,
+                                        started,
+                                        duration,
+                                        tasks_completed,
+                                        notes,
+                                    }
+                                })
+                                .collect(),
+                            attempts_notes,
+                            log_notes,
+                            extra_sections,
+                        }
+                    },
+                )
+        }
+
+        proptest! {
+            #[test]
+            fn parse_inverts_to_markdown(pf in progress_file()) {
+                let markdown = pf.to_markdown();
+                let parsed = ProgressFile::parse(&markdown).expect("Should parse");
+                prop_assert_eq!(parsed, pf, "markdown:\n{}", markdown);
+            }
+        }
+    }
 }
@@ -13,6 +13,10 @@ const GSD_BUILD: &str = include_str!("../../prompts/gsd/PROMPT_build.md");
 /// Test discovery prompt (mode-independent)
 pub const TEST_DISCOVERY_PROMPT: &str = include_str!("../../prompts/PROMPT_test_discovery.md");
 
+/// User message sent with each build iteration (see `template` for variables)
+pub const BUILD_MESSAGE_TEMPLATE: &str =
+    "## Current Progress\n\n{{progress}}\n\n## Instructions\n\n{{instructions}} Output the complete updated progress file.";
+
 impl PromptMode {
     /// Get the plan prompt for this mode.
     pub fn plan_prompt(&self) -> &'static str {
@@ -1,32 +1,74 @@
 //! Prompt loading with config override support.
 
+use std::path::Path;
+
+use super::defaults::{BUILD_MESSAGE_TEMPLATE, TEST_DISCOVERY_PROMPT};
 use super::PromptMode;
 use crate::config::Config;
+use crate::error::RslphError;
+
+/// Read a prompt override file, or fall back to the embedded prompt.
+fn read_override(path: Option<&Path>, kind: &str, default: &str) -> Result<String, RslphError> {
+    match path {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| {
+            RslphError::Prompt(format!(
+                "Failed to read {} prompt from '{}': {}",
+                kind,
+                path.display(),
+                e
+            ))
+        }),
+        None => Ok(default.to_string()),
+    }
+}
+
+/// Load the planning prompt template for `mode`.
+///
+/// Precedence: `plan_prompt` file override > mode selection
+pub fn load_plan_prompt(config: &Config, mode: PromptMode) -> Result<String, RslphError> {
+    read_override(config.plan_prompt.as_deref(), "plan", mode.plan_prompt())
+}
+
+/// Load the build system prompt template for `mode`.
+///
+/// Precedence: `build_prompt` file override > mode selection
+pub fn load_build_prompt(config: &Config, mode: PromptMode) -> Result<String, RslphError> {
+    read_override(config.build_prompt.as_deref(), "build", mode.build_prompt())
+}
+
+/// Load the template for the user message sent each build iteration.
+///
+/// Precedence: `build_message_prompt` file override > embedded default
+pub fn load_build_message(config: &Config) -> Result<String, RslphError> {
+    read_override(
+        config.build_message_prompt.as_deref(),
+        "build message",
+        BUILD_MESSAGE_TEMPLATE,
+    )
+}
+
+/// Load the test discovery prompt template.
+///
+/// Precedence: `test_discovery_prompt` file override > embedded default
+pub fn load_test_discovery_prompt(config: &Config) -> Result<String, RslphError> {
+    read_override(
+        config.test_discovery_prompt.as_deref(),
+        "test discovery",
+        TEST_DISCOVERY_PROMPT,
+    )
+}
 
 /// Get the planning prompt, using config override if specified.
 ///
 /// Precedence: file override > mode selection
 pub fn get_plan_prompt(config: &Config) -> color_eyre::Result<String> {
-    // File override takes precedence (power users)
-    if let Some(path) = &config.plan_prompt {
-        return std::fs::read_to_string(path).map_err(|e| {
-            color_eyre::eyre::eyre!(
-                "Failed to read plan prompt from '{}': {}",
-                path.display(),
-                e
-            )
-        });
-    }
-
-    // Mode-based selection
-    Ok(config.prompt_mode.plan_prompt().to_string())
+    Ok(load_plan_prompt(config, config.prompt_mode)?)
 }
 
 /// Get the planning prompt for a specific mode.
 ///
 /// This function bypasses config file overrides and returns the prompt
-/// for the specified mode directly. Used by eval to ensure different
-/// modes use their correct prompts.
+/// for the specified mode directly. Use `load_*_prompt` to honor overrides.
 pub fn get_plan_prompt_for_mode(mode: PromptMode) -> String {
     mode.plan_prompt().to_string()
 }
@@ -35,26 +77,13 @@ pub fn get_plan_prompt_for_mode(mode: PromptMode) -> String {
 ///
 /// Precedence: file override > mode selection
 pub fn get_build_prompt(config: &Config) -> color_eyre::Result<String> {
-    // File override takes precedence (power users)
-    if let Some(path) = &config.build_prompt {
-        return std::fs::read_to_string(path).map_err(|e| {
-            color_eyre::eyre::eyre!(
-                "Failed to read build prompt from '{}': {}",
-                path.display(),
-                e
-            )
-        });
-    }
-
-    // Mode-based selection
-    Ok(config.prompt_mode.build_prompt().to_string())
+    Ok(load_build_prompt(config, config.prompt_mode)?)
 }
 
 /// Get the build prompt for a specific mode.
 ///
 /// This function bypasses config file overrides and returns the prompt
-/// for the specified mode directly. Used by eval to ensure different
-/// modes use their correct prompts.
+/// for the specified mode directly. Use `load_*_prompt` to honor overrides.
 pub fn get_build_prompt_for_mode(mode: PromptMode) -> String {
     mode.build_prompt().to_string()
 }
@@ -142,4 +171,49 @@ mod tests {
         let result = get_plan_prompt(&config);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_load_build_prompt_override_ignores_mode() {
+        let mut temp = NamedTempFile::new().expect("Should create temp file");
+        writeln!(temp, "Custom build prompt").expect("Should write");
+
+        let config = Config {
+            build_prompt: Some(temp.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        for mode in [PromptMode::Basic, PromptMode::Gsd] {
+            let prompt = load_build_prompt(&config, mode).expect("Should read override");
+            assert!(prompt.contains("Custom build prompt"));
+        }
+        assert_eq!(
+            load_build_prompt(&Config::default(), PromptMode::Gsd).expect("default"),
+            PromptMode::Gsd.build_prompt()
+        );
+    }
+
+    #[test]
+    fn test_load_build_message_and_test_discovery_overrides() {
+        let config = Config::default();
+        assert!(load_build_message(&config)
+            .expect("default")
+            .contains("{{progress}}"));
+        assert_eq!(
+            load_test_discovery_prompt(&config).expect("default"),
+            TEST_DISCOVERY_PROMPT
+        );
+
+        let mut temp = NamedTempFile::new().expect("Should create temp file");
+        writeln!(temp, "Custom message for {{{{next_task}}}}").expect("Should write");
+        let config = Config {
+            build_message_prompt: Some(temp.path().to_path_buf()),
+            test_discovery_prompt: Some("/nonexistent/discovery.md".into()),
+            ..Default::default()
+        };
+        assert!(load_build_message(&config)
+            .expect("Should read override")
+            .contains("Custom message for {{next_task}}"));
+        let err = load_test_discovery_prompt(&config).expect_err("missing file");
+        assert!(err.to_string().contains("test discovery prompt"));
+    }
 }
@@ -1,14 +1,20 @@
 //! Prompt system for loading and managing Claude system prompts.
 //!
-//! Provides baked-in default prompts with optional file override via config.
+//! Provides baked-in default prompts with optional file override via config,
+//! rendered through a small `{{variable}}` templating layer.
 
 mod defaults;
 mod loader;
 mod modes;
+mod template;
 
 pub use defaults::test_discovery_prompt;
 pub use loader::get_build_prompt;
 pub use loader::get_build_prompt_for_mode;
 pub use loader::get_plan_prompt;
 pub use loader::get_plan_prompt_for_mode;
+pub use loader::{
+    load_build_message, load_build_prompt, load_plan_prompt, load_test_discovery_prompt,
+};
 pub use modes::PromptMode;
+pub use template::{render, PromptVars};
@@ -0,0 +1,90 @@
+//! Minimal `{{variable}}` templating for prompts.
+//!
+//! Prompts (embedded or overridden from a file) may reference variables such
+//! as `{{progress}}` or `{{next_task}}`. Unknown variables are left untouched
+//! so prompts that happen to contain braces still render as written.
+
+use std::collections::BTreeMap;
+
+/// Variables available to a prompt template.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptVars {
+    values: BTreeMap<&'static str, String>,
+}
+
+impl PromptVars {
+    /// Create an empty variable set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a variable, returning the updated set.
+    pub fn with(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.values.insert(name, value.into());
+        self
+    }
+
+    /// Look up a variable by name.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+}
+
+/// Substitute `{{name}}` (or `{{ name }}`) placeholders in `template`.
+pub fn render(template: &str, vars: &PromptVars) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let placeholder = &rest[start..start + 2 + end + 2];
+        match vars.get(after[..end].trim()) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(placeholder),
+        }
+        rest = &after[end + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_variables() {
+        let vars = PromptVars::new()
+            .with("iteration", "3")
+            .with("max_iterations", "20");
+        assert_eq!(
+            render("Iteration {{iteration}} of {{ max_iterations }}.", &vars),
+            "Iteration 3 of 20."
+        );
+    }
+
+    #[test]
+    fn test_render_leaves_unknown_and_unclosed_placeholders() {
+        let vars = PromptVars::new().with("stack", "Rust");
+        assert_eq!(
+            render("{{stack}} {{unknown}} {{stack", &vars),
+            "Rust {{unknown}} {{stack"
+        );
+    }
+
+    #[test]
+    fn test_render_does_not_expand_substituted_values() {
+        let vars = PromptVars::new()
+            .with("progress", "- [ ] write {{stack}} docs")
+            .with("stack", "Rust");
+        assert_eq!(render("{{progress}}", &vars), "- [ ] write {{stack}} docs");
+    }
+}
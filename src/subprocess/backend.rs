@@ -0,0 +1,538 @@
+//! Agent backends.
+//!
+//! An `AgentBackend` knows how to run a coding agent headlessly: the command
+//! line for a prompt, and how each line the agent prints maps to conversation
+//! items, tool uses, token usage and response text. Build iterations only talk
+//! to the agent through this trait, so the Claude CLI's stream-json protocol is
+//! one backend among others.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use super::http::HttpApi;
+use super::runner::{build_claude_args, model_args};
+use super::stream_json::{format_tool_summary, StreamEvent, Usage};
+use crate::config::{ClaudeCommand, Config};
+use crate::error::RslphError;
+use crate::tui::ConversationItem;
+
+/// Which agent runs build iterations.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    ValueEnum,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[clap(rename_all = "snake_case")]
+pub enum AgentKind {
+    /// Claude CLI in print mode with stream-json output
+    #[default]
+    Claude,
+    /// `agent_cmd` run through `sh -c`, printing its response as plain text
+    Command,
+    /// A Messages-API or OpenAI-compatible endpoint driven by `rslph http-agent`
+    Http,
+}
+
+impl AgentKind {
+    /// Name used in log messages and Recent Attempts.
+    pub fn label(self) -> &'static str {
+        match self {
+            AgentKind::Claude => "Claude",
+            AgentKind::Command => "agent",
+            AgentKind::Http => "HTTP agent",
+        }
+    }
+
+    /// A backend of this kind for parsing recorded output (not set up to run).
+    pub fn parser(self) -> Box<dyn AgentBackend> {
+        match self {
+            AgentKind::Claude => Box::new(ClaudeBackend::default()),
+            AgentKind::Command => Box::new(CommandBackend::new("")),
+            AgentKind::Http => Box::new(HttpBackend::parser()),
+        }
+    }
+}
+
+/// What to send to the agent.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentRequest<'a> {
+    pub system_prompt: &'a str,
+    pub user_input: &'a str,
+    /// Model to ask for (None leaves the choice to the agent).
+    pub model: Option<&'a str>,
+}
+
+/// A program and its arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentInvocation {
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+/// One line of agent output, normalized.
+#[derive(Debug, Clone, Default)]
+pub struct AgentOutput {
+    /// Items for the TUI conversation view.
+    pub items: Vec<ConversationItem>,
+    /// Text that belongs to the agent's response.
+    pub text: Option<String>,
+    /// Tools the agent used, as (name, summary).
+    pub tool_uses: Vec<(String, String)>,
+    /// Token usage reported with this line.
+    pub usage: Option<Usage>,
+    /// Model that produced this line.
+    pub model: Option<String>,
+    /// The agent is waiting for user input.
+    pub input_required: Option<String>,
+}
+
+/// A coding agent that can run a build iteration.
+pub trait AgentBackend: Send + Sync {
+    /// Which backend this is (named in logs and transcripts).
+    fn kind(&self) -> AgentKind;
+
+    /// Command line that runs the agent on `request`.
+    fn invocation(&self, request: &AgentRequest) -> AgentInvocation;
+
+    /// Normalize one stdout line (None for lines that carry nothing).
+    fn parse_line(&self, line: &str) -> Option<AgentOutput>;
+
+    /// What to check when the agent can't be spawned.
+    fn spawn_hint(&self) -> &'static str;
+}
+
+/// The Claude CLI (`-p --output-format stream-json`).
+#[derive(Debug, Clone, Default)]
+pub struct ClaudeBackend {
+    cmd: ClaudeCommand,
+    /// Append --dangerously-skip-permissions.
+    no_dsp: bool,
+}
+
+impl ClaudeBackend {
+    pub fn new(cmd: ClaudeCommand, no_dsp: bool) -> Self {
+        Self { cmd, no_dsp }
+    }
+}
+
+impl AgentBackend for ClaudeBackend {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Claude
+    }
+
+    fn invocation(&self, request: &AgentRequest) -> AgentInvocation {
+        let mut args = vec![
+            "-p".to_string(),        // Print mode (headless)
+            "--verbose".to_string(), // Required for stream-json with -p
+            "--output-format".to_string(),
+            "stream-json".to_string(), // JSONL for structured parsing
+        ];
+        args.extend(model_args(request.model));
+        args.extend([
+            "--system-prompt".to_string(),
+            request.system_prompt.to_string(),
+            request.user_input.to_string(),
+        ]);
+
+        AgentInvocation {
+            command: self.cmd.command.clone(),
+            args: build_claude_args(&self.cmd.base_args, &args, self.no_dsp),
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> Option<AgentOutput> {
+        parse_stream_json(line)
+    }
+
+    fn spawn_hint(&self) -> &'static str {
+        "Ensure claude is in PATH or set RSLPH_CLAUDE_CMD environment variable"
+    }
+}
+
+/// Normalize one line of Claude stream-json output.
+fn parse_stream_json(line: &str) -> Option<AgentOutput> {
+    let event = StreamEvent::parse(line).ok()?;
+    let mut output = AgentOutput {
+        items: event.extract_conversation_items(),
+        input_required: event.is_input_required(),
+        ..Default::default()
+    };
+
+    if event.is_assistant() {
+        output.text = event.extract_text();
+        output.tool_uses = event
+            .extract_tool_uses()
+            .into_iter()
+            .map(|(name, input)| {
+                let summary = format_tool_summary(&name, &input);
+                (name, summary)
+            })
+            .collect();
+        output.usage = event.usage().cloned();
+        output.model = event.message.as_ref().and_then(|m| m.model.clone());
+    }
+    Some(output)
+}
+
+/// Any agent CLI that prints its response as plain text.
+///
+/// The command line runs through `sh -c` with the prompt (system prompt, a
+/// blank line, then the user input) appended as its last argument. Every
+/// stdout line is part of the response; no token usage is reported.
+#[derive(Debug, Clone)]
+pub struct CommandBackend {
+    command_line: String,
+}
+
+impl CommandBackend {
+    pub fn new(command_line: impl Into<String>) -> Self {
+        Self {
+            command_line: command_line.into(),
+        }
+    }
+}
+
+impl AgentBackend for CommandBackend {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Command
+    }
+
+    fn invocation(&self, request: &AgentRequest) -> AgentInvocation {
+        let prompt = format!("{}\n\n{}", request.system_prompt, request.user_input);
+        AgentInvocation {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!("{} \"$1\"", self.command_line),
+                "rslph".to_string(),
+                prompt,
+            ],
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> Option<AgentOutput> {
+        let items = if line.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![ConversationItem::Text(line.to_string())]
+        };
+        Some(AgentOutput {
+            items,
+            text: Some(format!("{}\n", line)),
+            ..Default::default()
+        })
+    }
+
+    fn spawn_hint(&self) -> &'static str {
+        "Check the agent_cmd setting"
+    }
+}
+
+/// A Messages-API or OpenAI-compatible endpoint.
+///
+/// Runs `rslph http-agent` (this executable) as the subprocess, so timeouts,
+/// pausing and cancellation work as for any other agent. The child drives the
+/// model's tool loop in the working directory and prints each turn as Claude
+/// stream-json, which is parsed here the same way as the Claude CLI's output.
+/// The API key is read by the child from the environment, never passed as an
+/// argument.
+#[derive(Debug, Clone)]
+pub struct HttpBackend {
+    exe: String,
+    api: HttpApi,
+    base_url: String,
+    api_key_env: String,
+    /// Model when the iteration doesn't pick one.
+    default_model: String,
+    max_tokens: u32,
+    max_turns: u32,
+}
+
+impl HttpBackend {
+    /// Backend for `config`, which must name a `build_model`.
+    pub fn new(config: &Config, exe: impl Into<String>) -> Result<Self, RslphError> {
+        let default_model = config.build_model.clone().ok_or_else(|| {
+            RslphError::Subprocess(
+                "agent_backend = \"http\" requires build_model to be set".to_string(),
+            )
+        })?;
+        Ok(Self {
+            exe: exe.into(),
+            api: config.http_api,
+            base_url: config
+                .http_base_url
+                .clone()
+                .unwrap_or_else(|| config.http_api.default_base_url().to_string()),
+            api_key_env: config
+                .http_api_key_env
+                .clone()
+                .unwrap_or_else(|| config.http_api.default_api_key_env().to_string()),
+            default_model,
+            max_tokens: config.http_max_tokens,
+            max_turns: config.http_max_turns,
+        })
+    }
+
+    /// A backend that is only used to parse recorded output.
+    fn parser() -> Self {
+        Self {
+            exe: String::new(),
+            api: HttpApi::default(),
+            base_url: String::new(),
+            api_key_env: String::new(),
+            default_model: String::new(),
+            max_tokens: 0,
+            max_turns: 0,
+        }
+    }
+}
+
+impl AgentBackend for HttpBackend {
+    fn kind(&self) -> AgentKind {
+        AgentKind::Http
+    }
+
+    fn invocation(&self, request: &AgentRequest) -> AgentInvocation {
+        AgentInvocation {
+            command: self.exe.clone(),
+            args: vec![
+                "http-agent".to_string(),
+                "--api".to_string(),
+                self.api.to_string(),
+                "--base-url".to_string(),
+                self.base_url.clone(),
+                "--api-key-env".to_string(),
+                self.api_key_env.clone(),
+                "--model".to_string(),
+                request.model.unwrap_or(&self.default_model).to_string(),
+                "--max-tokens".to_string(),
+                self.max_tokens.to_string(),
+                "--max-turns".to_string(),
+                self.max_turns.to_string(),
+                "--system-prompt".to_string(),
+                request.system_prompt.to_string(),
+                request.user_input.to_string(),
+            ],
+        }
+    }
+
+    fn parse_line(&self, line: &str) -> Option<AgentOutput> {
+        parse_stream_json(line)
+    }
+
+    fn spawn_hint(&self) -> &'static str {
+        "The http backend re-runs the rslph executable; check that it is still installed"
+    }
+}
+
+/// The backend selected by `agent_backend`.
+///
+/// # Errors
+///
+/// `agent_backend = "command"` without a non-empty `agent_cmd`, or
+/// `agent_backend = "http"` without a `build_model`.
+pub fn agent_backend(config: &Config, no_dsp: bool) -> Result<Box<dyn AgentBackend>, RslphError> {
+    match config.agent_backend {
+        AgentKind::Claude => Ok(Box::new(ClaudeBackend::new(
+            config.claude_cmd.clone(),
+            no_dsp,
+        ))),
+        AgentKind::Command => match config.agent_cmd.as_deref().map(str::trim) {
+            Some(cmd) if !cmd.is_empty() => Ok(Box::new(CommandBackend::new(cmd))),
+            _ => Err(RslphError::Subprocess(
+                "agent_backend = \"command\" requires agent_cmd to be set".to_string(),
+            )),
+        },
+        AgentKind::Http => {
+            let exe = std::env::current_exe()?;
+            Ok(Box::new(HttpBackend::new(config, exe.to_string_lossy())?))
+        }
+    }
+}
+
+/// Response accumulated from an agent's normalized output.
+#[derive(Debug, Clone, Default)]
+pub struct AgentResponse {
+    /// Concatenated response text.
+    pub text: String,
+    /// First model reported by the agent.
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl AgentResponse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add one line of output.
+    pub fn process(&mut self, output: &AgentOutput) {
+        if let Some(ref text) = output.text {
+            self.text.push_str(text);
+        }
+        if self.model.is_none() {
+            self.model = output.model.clone();
+        }
+        if let Some(ref usage) = output.usage {
+            self.input_tokens += usage.input_tokens;
+            self.output_tokens += usage.output_tokens;
+            self.cache_creation_input_tokens += usage.cache_creation_input_tokens.unwrap_or(0);
+            self.cache_read_input_tokens += usage.cache_read_input_tokens.unwrap_or(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> AgentRequest<'static> {
+        AgentRequest {
+            system_prompt: "You are rslph",
+            user_input: "Go",
+            model: Some("claude-opus-4-5"),
+        }
+    }
+
+    #[test]
+    fn test_claude_backend_invocation_and_output() {
+        let backend = ClaudeBackend::new(
+            ClaudeCommand {
+                command: "claude".to_string(),
+                base_args: vec!["--internet".to_string()],
+            },
+            true,
+        );
+        let invocation = backend.invocation(&request());
+        assert_eq!(invocation.command, "claude");
+        assert_eq!(
+            invocation.args,
+            [
+                "--internet",
+                "--dangerously-skip-permissions",
+                "-p",
+                "--verbose",
+                "--output-format",
+                "stream-json",
+                "--model",
+                "claude-opus-4-5",
+                "--system-prompt",
+                "You are rslph",
+                "Go",
+            ]
+        );
+
+        let line = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Done"},{"type":"tool_use","name":"Bash","input":{"command":"ls"}}],"model":"claude-opus-4-5","usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":2}}}"#;
+        let output = backend.parse_line(line).expect("stream-json line");
+        assert_eq!(output.items.len(), 2);
+        assert_eq!(output.tool_uses.len(), 1);
+        assert_eq!(output.tool_uses[0].0, "Bash");
+        assert!(backend.parse_line("not json").is_none());
+
+        let mut response = AgentResponse::new();
+        response.process(&output);
+        response.process(&output);
+        assert_eq!(response.text, "DoneDone");
+        assert_eq!(response.model.as_deref(), Some("claude-opus-4-5"));
+        assert_eq!(response.input_tokens, 20);
+        assert_eq!(response.cache_read_input_tokens, 4);
+    }
+
+    #[test]
+    fn test_command_backend_passes_prompt_and_collects_text() {
+        let backend = CommandBackend::new("my-agent --yes");
+        let invocation = backend.invocation(&request());
+        assert_eq!(invocation.command, "sh");
+        assert_eq!(invocation.args[1], "my-agent --yes \"$1\"");
+        assert_eq!(invocation.args[3], "You are rslph\n\nGo");
+
+        let mut response = AgentResponse::new();
+        for line in ["# Progress: Demo", "", "## Status"] {
+            response.process(&backend.parse_line(line).expect("every line counts"));
+        }
+        assert_eq!(response.text, "# Progress: Demo\n\n## Status\n");
+        assert!(response.model.is_none());
+        assert_eq!(response.input_tokens, 0);
+        assert!(backend.parse_line("").expect("line").items.is_empty());
+    }
+
+    #[test]
+    fn test_agent_backend_selection() {
+        let mut config = Config::default();
+        assert_eq!(
+            agent_backend(&config, false).expect("claude").kind(),
+            AgentKind::Claude
+        );
+
+        config.agent_backend = AgentKind::Command;
+        assert!(agent_backend(&config, false).is_err());
+        config.agent_cmd = Some("my-agent".to_string());
+        assert_eq!(
+            agent_backend(&config, false).expect("command").kind(),
+            AgentKind::Command
+        );
+
+        config.agent_backend = AgentKind::Http;
+        assert!(agent_backend(&config, false).is_err());
+        config.build_model = Some("qwen2.5-coder".to_string());
+        assert_eq!(
+            agent_backend(&config, false).expect("http").kind(),
+            AgentKind::Http
+        );
+    }
+
+    #[test]
+    fn test_http_backend_invocation() {
+        let mut config = Config {
+            http_api: HttpApi::OpenAi,
+            http_base_url: Some("http://localhost:8080/v1".to_string()),
+            build_model: Some("local-model".to_string()),
+            ..Default::default()
+        };
+        config.http_max_turns = 7;
+        let backend = HttpBackend::new(&config, "/usr/bin/rslph").expect("backend");
+
+        let invocation = backend.invocation(&AgentRequest {
+            model: None,
+            ..request()
+        });
+        assert_eq!(invocation.command, "/usr/bin/rslph");
+        assert_eq!(
+            invocation.args,
+            [
+                "http-agent",
+                "--api",
+                "openai",
+                "--base-url",
+                "http://localhost:8080/v1",
+                "--api-key-env",
+                "OPENAI_API_KEY",
+                "--model",
+                "local-model",
+                "--max-tokens",
+                "8192",
+                "--max-turns",
+                "7",
+                "--system-prompt",
+                "You are rslph",
+                "Go",
+            ]
+        );
+        let escalated = backend.invocation(&request());
+        assert_eq!(escalated.args[8], "claude-opus-4-5");
+    }
+}
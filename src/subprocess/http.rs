@@ -0,0 +1,501 @@
+//! Agent loop over an HTTP model API.
+//!
+//! `rslph http-agent` sends the prompt to an Anthropic Messages or
+//! OpenAI-compatible chat completions endpoint, runs the tools the model asks
+//! for (see `tools`), and repeats until the model answers without tool calls.
+//! Every turn is printed as a Claude stream-json line, so the `http` backend
+//! streams, records transcripts and reports token usage exactly like the
+//! Claude CLI.
+
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use strum_macros::{Display, EnumString};
+
+use super::tools::{tool_specs, Workspace};
+use crate::error::RslphError;
+
+/// Wire protocol spoken by the `http` backend.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    ValueEnum,
+)]
+pub enum HttpApi {
+    /// Anthropic Messages API (`POST {base}/messages`)
+    #[default]
+    #[strum(serialize = "anthropic")]
+    #[serde(rename = "anthropic")]
+    #[value(name = "anthropic")]
+    Anthropic,
+    /// OpenAI chat completions (`POST {base}/chat/completions`)
+    #[strum(serialize = "openai")]
+    #[serde(rename = "openai")]
+    #[value(name = "openai")]
+    OpenAi,
+}
+
+/// A tool call requested by the model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: Value,
+}
+
+/// One model response, normalized across APIs.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    /// The assistant message to append to the conversation, in the API's format.
+    pub message: Value,
+    pub text: Vec<String>,
+    pub tool_calls: Vec<ToolCall>,
+    /// Token usage in the Claude stream-json `usage` shape.
+    pub usage: Value,
+    pub model: Option<String>,
+}
+
+impl HttpApi {
+    /// Base URL used when `http_base_url` is not set.
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            HttpApi::Anthropic => "https://api.anthropic.com/v1",
+            HttpApi::OpenAi => "https://api.openai.com/v1",
+        }
+    }
+
+    /// Environment variable read for the API key when `http_api_key_env` is not set.
+    pub fn default_api_key_env(self) -> &'static str {
+        match self {
+            HttpApi::Anthropic => "ANTHROPIC_API_KEY",
+            HttpApi::OpenAi => "OPENAI_API_KEY",
+        }
+    }
+
+    fn endpoint(self, base_url: &str) -> String {
+        let base = base_url.trim_end_matches('/');
+        match self {
+            HttpApi::Anthropic => format!("{}/messages", base),
+            HttpApi::OpenAi => format!("{}/chat/completions", base),
+        }
+    }
+
+    /// Conversation before the first turn.
+    fn initial_messages(self, system_prompt: &str, user_input: &str) -> Vec<Value> {
+        match self {
+            HttpApi::Anthropic => vec![json!({"role": "user", "content": user_input})],
+            HttpApi::OpenAi => vec![
+                json!({"role": "system", "content": system_prompt}),
+                json!({"role": "user", "content": user_input}),
+            ],
+        }
+    }
+
+    fn request_body(
+        self,
+        model: &str,
+        max_tokens: u32,
+        system_prompt: &str,
+        messages: &[Value],
+    ) -> Value {
+        let specs = tool_specs();
+        match self {
+            HttpApi::Anthropic => {
+                let tools: Vec<Value> = specs
+                    .iter()
+                    .map(|t| {
+                        json!({
+                            "name": t.name,
+                            "description": t.description,
+                            "input_schema": t.input_schema,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "model": model,
+                    "max_tokens": max_tokens,
+                    "system": system_prompt,
+                    "messages": messages,
+                    "tools": tools,
+                })
+            }
+            HttpApi::OpenAi => {
+                let tools: Vec<Value> = specs
+                    .iter()
+                    .map(|t| {
+                        json!({
+                            "type": "function",
+                            "function": {
+                                "name": t.name,
+                                "description": t.description,
+                                "parameters": t.input_schema,
+                            }
+                        })
+                    })
+                    .collect();
+                json!({
+                    "model": model,
+                    "max_tokens": max_tokens,
+                    "messages": messages,
+                    "tools": tools,
+                })
+            }
+        }
+    }
+
+    fn parse_reply(self, body: &Value) -> Result<Reply, RslphError> {
+        let malformed =
+            |what: &str| RslphError::Subprocess(format!("Malformed response: {}", what));
+        let model = body["model"].as_str().map(str::to_string);
+
+        match self {
+            HttpApi::Anthropic => {
+                let content = body["content"]
+                    .as_array()
+                    .ok_or_else(|| malformed("missing content"))?;
+                let mut text = Vec::new();
+                let mut tool_calls = Vec::new();
+                for block in content {
+                    match block["type"].as_str() {
+                        Some("text") => text.extend(block["text"].as_str().map(str::to_string)),
+                        Some("tool_use") => tool_calls.push(ToolCall {
+                            id: block["id"].as_str().unwrap_or_default().to_string(),
+                            name: block["name"].as_str().unwrap_or_default().to_string(),
+                            input: block["input"].clone(),
+                        }),
+                        _ => {}
+                    }
+                }
+                let usage = &body["usage"];
+                Ok(Reply {
+                    message: json!({"role": "assistant", "content": content}),
+                    text,
+                    tool_calls,
+                    usage: json!({
+                        "input_tokens": usage["input_tokens"].as_u64().unwrap_or(0),
+                        "output_tokens": usage["output_tokens"].as_u64().unwrap_or(0),
+                        "cache_creation_input_tokens": usage["cache_creation_input_tokens"].as_u64(),
+                        "cache_read_input_tokens": usage["cache_read_input_tokens"].as_u64(),
+                    }),
+                    model,
+                })
+            }
+            HttpApi::OpenAi => {
+                let message = &body["choices"][0]["message"];
+                if !message.is_object() {
+                    return Err(malformed("missing choices[0].message"));
+                }
+                let text = message["content"]
+                    .as_str()
+                    .filter(|s| !s.is_empty())
+                    .map(|s| vec![s.to_string()])
+                    .unwrap_or_default();
+                let tool_calls = message["tool_calls"]
+                    .as_array()
+                    .map(|calls| {
+                        calls
+                            .iter()
+                            .map(|call| {
+                                let arguments =
+                                    call["function"]["arguments"].as_str().unwrap_or("{}");
+                                ToolCall {
+                                    id: call["id"].as_str().unwrap_or_default().to_string(),
+                                    name: call["function"]["name"]
+                                        .as_str()
+                                        .unwrap_or_default()
+                                        .to_string(),
+                                    input: serde_json::from_str(arguments)
+                                        .unwrap_or_else(|_| Value::String(arguments.to_string())),
+                                }
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let usage = &body["usage"];
+                Ok(Reply {
+                    message: message.clone(),
+                    text,
+                    tool_calls,
+                    usage: json!({
+                        "input_tokens": usage["prompt_tokens"].as_u64().unwrap_or(0),
+                        "output_tokens": usage["completion_tokens"].as_u64().unwrap_or(0),
+                        "cache_read_input_tokens": usage["prompt_tokens_details"]["cached_tokens"].as_u64(),
+                    }),
+                    model,
+                })
+            }
+        }
+    }
+
+    /// Messages carrying tool results back to the model.
+    fn tool_result_messages(self, results: &[(ToolCall, Result<String, String>)]) -> Vec<Value> {
+        match self {
+            HttpApi::Anthropic => vec![json!({
+                "role": "user",
+                "content": results.iter().map(|(call, result)| tool_result_block(call, result)).collect::<Vec<_>>(),
+            })],
+            HttpApi::OpenAi => results
+                .iter()
+                .map(|(call, result)| {
+                    let content = match result {
+                        Ok(s) => s.clone(),
+                        Err(e) => format!("Error: {}", e),
+                    };
+                    json!({"role": "tool", "tool_call_id": call.id, "content": content})
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A `tool_result` content block in the Anthropic / stream-json shape.
+fn tool_result_block(call: &ToolCall, result: &Result<String, String>) -> Value {
+    let (content, is_error) = match result {
+        Ok(s) => (s.as_str(), false),
+        Err(e) => (e.as_str(), true),
+    };
+    json!({
+        "type": "tool_result",
+        "tool_use_id": call.id,
+        "content": content,
+        "is_error": is_error,
+    })
+}
+
+/// Settings for one `rslph http-agent` run.
+#[derive(Debug, Clone)]
+pub struct HttpAgent {
+    pub api: HttpApi,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub max_tokens: u32,
+    pub max_turns: u32,
+}
+
+impl HttpAgent {
+    /// Run the tool loop until the model stops calling tools.
+    ///
+    /// Each turn is written to `out` as stream-json lines: an `assistant`
+    /// event with the model's text, tool calls and usage, then a `user` event
+    /// with the tool results.
+    ///
+    /// # Errors
+    ///
+    /// Request failures, non-2xx responses, unparseable replies, or running
+    /// out of `max_turns`.
+    pub async fn run(
+        &self,
+        system_prompt: &str,
+        user_input: &str,
+        workspace: &Workspace,
+        out: &mut impl Write,
+    ) -> Result<(), RslphError> {
+        let client = reqwest::Client::new();
+        let url = self.api.endpoint(&self.base_url);
+        let mut messages = self.api.initial_messages(system_prompt, user_input);
+
+        for _ in 0..self.max_turns {
+            let body =
+                self.api
+                    .request_body(&self.model, self.max_tokens, system_prompt, &messages);
+            let reply = self
+                .api
+                .parse_reply(&self.post(&client, &url, &body).await?)?;
+
+            let mut content: Vec<Value> = reply
+                .text
+                .iter()
+                .map(|text| json!({"type": "text", "text": text}))
+                .collect();
+            content.extend(reply.tool_calls.iter().map(|call| {
+                json!({"type": "tool_use", "id": call.id, "name": call.name, "input": call.input})
+            }));
+            emit(
+                out,
+                &json!({
+                    "type": "assistant",
+                    "message": {
+                        "role": "assistant",
+                        "model": reply.model.as_deref().unwrap_or(&self.model),
+                        "content": content,
+                        "usage": reply.usage,
+                    }
+                }),
+            )?;
+            messages.push(reply.message);
+
+            if reply.tool_calls.is_empty() {
+                return Ok(());
+            }
+
+            let mut results = Vec::new();
+            for call in reply.tool_calls {
+                let result = workspace.run(&call.name, &call.input).await;
+                results.push((call, result));
+            }
+            emit(
+                out,
+                &json!({
+                    "type": "user",
+                    "message": {
+                        "role": "user",
+                        "content": results.iter().map(|(call, result)| tool_result_block(call, result)).collect::<Vec<_>>(),
+                    }
+                }),
+            )?;
+            messages.extend(self.api.tool_result_messages(&results));
+        }
+
+        Err(RslphError::Subprocess(format!(
+            "Model still calling tools after {} turns (http_max_turns)",
+            self.max_turns
+        )))
+    }
+
+    async fn post(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        body: &Value,
+    ) -> Result<Value, RslphError> {
+        let mut request = client.post(url).json(body);
+        request = match (self.api, &self.api_key) {
+            (HttpApi::Anthropic, key) => {
+                let request = request.header("anthropic-version", "2023-06-01");
+                match key {
+                    Some(key) => request.header("x-api-key", key),
+                    None => request,
+                }
+            }
+            (HttpApi::OpenAi, Some(key)) => request.bearer_auth(key),
+            (HttpApi::OpenAi, None) => request,
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| RslphError::Subprocess(format!("Request to {} failed: {}", url, e)))?;
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
+            RslphError::Subprocess(format!("Reading response from {} failed: {}", url, e))
+        })?;
+        if !status.is_success() {
+            return Err(RslphError::Subprocess(format!(
+                "{} returned {}: {}",
+                url,
+                status,
+                text.trim()
+            )));
+        }
+        serde_json::from_str(&text).map_err(|e| {
+            RslphError::Subprocess(format!("Response from {} is not JSON: {}", url, e))
+        })
+    }
+}
+
+/// Write one stream-json line and flush it so the parent sees it right away.
+fn emit(out: &mut impl Write, event: &Value) -> Result<(), RslphError> {
+    writeln!(out, "{}", event)?;
+    out.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subprocess::StreamEvent;
+
+    #[test]
+    fn test_anthropic_reply_and_tool_results() {
+        let body = json!({
+            "model": "claude-sonnet-4-5",
+            "content": [
+                {"type": "text", "text": "Writing"},
+                {"type": "tool_use", "id": "t1", "name": "Write", "input": {"file_path": "a", "content": "b"}}
+            ],
+            "usage": {"input_tokens": 12, "output_tokens": 3, "cache_read_input_tokens": 4}
+        });
+        let reply = HttpApi::Anthropic.parse_reply(&body).expect("reply");
+        assert_eq!(reply.text, ["Writing"]);
+        assert_eq!(reply.tool_calls[0].name, "Write");
+        assert_eq!(reply.usage["cache_read_input_tokens"], 4);
+        assert_eq!(reply.message["content"][1]["id"], "t1");
+
+        let results = vec![(reply.tool_calls[0].clone(), Err("denied".to_string()))];
+        let messages = HttpApi::Anthropic.tool_result_messages(&results);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["content"][0]["tool_use_id"], "t1");
+        assert_eq!(messages[0]["content"][0]["is_error"], true);
+
+        let request = HttpApi::Anthropic.request_body("m", 100, "sys", &messages);
+        assert_eq!(request["system"], "sys");
+        assert_eq!(request["tools"][0]["name"], "Read");
+        assert!(HttpApi::Anthropic.parse_reply(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_openai_reply_and_tool_results() {
+        let body = json!({
+            "model": "qwen2.5-coder",
+            "choices": [{"message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{"id": "c1", "type": "function", "function": {"name": "Bash", "arguments": "{\"command\":\"ls\"}"}}]
+            }}],
+            "usage": {"prompt_tokens": 20, "completion_tokens": 5}
+        });
+        let reply = HttpApi::OpenAi.parse_reply(&body).expect("reply");
+        assert!(reply.text.is_empty());
+        assert_eq!(
+            reply.tool_calls,
+            [ToolCall {
+                id: "c1".to_string(),
+                name: "Bash".to_string(),
+                input: json!({"command": "ls"}),
+            }]
+        );
+        assert_eq!(reply.usage["input_tokens"], 20);
+
+        let results = vec![(
+            reply.tool_calls[0].clone(),
+            Ok("exit code: 0\n".to_string()),
+        )];
+        let messages = HttpApi::OpenAi.tool_result_messages(&results);
+        assert_eq!(messages[0]["role"], "tool");
+        assert_eq!(messages[0]["tool_call_id"], "c1");
+
+        let initial = HttpApi::OpenAi.initial_messages("sys", "go");
+        assert_eq!(initial[0]["role"], "system");
+        let request = HttpApi::OpenAi.request_body("m", 100, "sys", &initial);
+        assert_eq!(request["tools"][3]["function"]["name"], "Bash");
+        assert_eq!(
+            HttpApi::OpenAi.endpoint("http://localhost:8080/v1/"),
+            "http://localhost:8080/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_emitted_lines_parse_as_stream_json() {
+        let mut out = Vec::new();
+        emit(
+            &mut out,
+            &json!({"type": "assistant", "message": {"content": [{"type": "text", "text": "hi"}], "usage": {"input_tokens": 1, "output_tokens": 2, "cache_read_input_tokens": null}}}),
+        )
+        .expect("emit");
+        let line = String::from_utf8(out).expect("utf8");
+        let event = StreamEvent::parse(line.trim_end()).expect("stream-json");
+        assert_eq!(event.extract_text().as_deref(), Some("hi"));
+        assert_eq!(event.usage().expect("usage").output_tokens, 2);
+    }
+}
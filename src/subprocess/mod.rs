@@ -1,9 +1,18 @@
+mod backend;
+mod http;
 mod output;
 mod runner;
 mod signals;
 mod stream_json;
+mod tools;
 
+pub use backend::{
+    agent_backend, AgentBackend, AgentInvocation, AgentKind, AgentOutput, AgentRequest,
+    AgentResponse, ClaudeBackend, CommandBackend, HttpBackend,
+};
+pub use http::{HttpAgent, HttpApi};
 pub use output::OutputLine;
-pub use runner::{build_claude_args, ClaudeRunner};
-pub use signals::{is_cancelled, setup_ctrl_c_handler};
+pub use runner::{build_claude_args, model_args, ClaudeRunner};
+pub use signals::{is_cancelled, resume_process, setup_ctrl_c_handler, suspend_process};
 pub use stream_json::{format_tool_summary, AskUserQuestion, StreamEvent, StreamResponse, Usage};
+pub use tools::Workspace;
@@ -37,6 +37,16 @@ pub fn build_claude_args(
     args
 }
 
+/// Build the `--model` argument pair for a Claude invocation.
+///
+/// Returns no args when `model` is None, leaving the choice to the Claude CLI.
+pub fn model_args(model: Option<&str>) -> Vec<String> {
+    match model {
+        Some(model) => vec!["--model".to_string(), model.to_string()],
+        None => Vec::new(),
+    }
+}
+
 pub struct ClaudeRunner {
     child: Child,
     stdout: Lines<BufReader<ChildStdout>>,
@@ -52,19 +62,25 @@ impl ClaudeRunner {
     /// Use this for non-interactive mode where no user input is expected.
     /// Stdin is set to null to signal EOF immediately, which prevents some
     /// launchers from blocking while waiting for input.
+    ///
+    /// On unix the process leads its own process group, so pausing and
+    /// terminating it reach the processes it spawns as well.
     pub async fn spawn(
         command_path: &str,
         args: &[String],
         working_dir: &Path,
     ) -> std::io::Result<Self> {
-        let mut child = Command::new(command_path)
+        let mut command = Command::new(command_path);
+        command
             .args(args)
             .current_dir(working_dir)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .stdin(Stdio::null()) // Non-interactive: no stdin expected
-            .kill_on_drop(true)
-            .spawn()?;
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        command.process_group(0);
+        let mut child = command.spawn()?;
 
         let stdout = child.stdout.take().expect("stdout configured as piped");
         let stderr = child.stderr.take().expect("stderr configured as piped");
@@ -198,11 +214,14 @@ impl ClaudeRunner {
     pub async fn terminate_gracefully(&mut self, grace_period: Duration) -> std::io::Result<()> {
         #[cfg(unix)]
         if let Some(id) = self.child.id() {
-            use nix::sys::signal::{kill, Signal};
+            use nix::sys::signal::{kill, killpg, Signal};
             use nix::unistd::Pid;
 
-            // Send SIGTERM to process (positive PID since we're not using process_group)
-            let _ = kill(Pid::from_raw(id as i32), Signal::SIGTERM);
+            // Signal the whole group when the process leads one (`spawn`)
+            let pid = Pid::from_raw(id as i32);
+            if killpg(pid, Signal::SIGTERM).is_err() {
+                let _ = kill(pid, Signal::SIGTERM);
+            }
 
             // Wait for graceful exit or timeout
             tokio::select! {
@@ -215,7 +234,14 @@ impl ClaudeRunner {
             }
         }
 
-        // Force kill if still running
+        // Force kill if still running, including anything left in its group
+        #[cfg(unix)]
+        if let Some(id) = self.child.id() {
+            let _ = nix::sys::signal::killpg(
+                nix::unistd::Pid::from_raw(id as i32),
+                nix::sys::signal::Signal::SIGKILL,
+            );
+        }
         self.child.kill().await?;
         // Reap to prevent zombie
         let _ = self.child.wait().await;
@@ -28,6 +28,45 @@ pub fn is_cancelled(token: &CancellationToken) -> bool {
     token.is_cancelled()
 }
 
+/// Suspend a running process and its process group with SIGSTOP.
+///
+/// Agent subprocesses lead their own process group (see `ClaudeRunner::spawn`),
+/// so the tools and shells they started are stopped along with them.
+///
+/// Returns true if the signal was delivered. Always false on non-unix platforms.
+pub fn suspend_process(pid: u32) -> bool {
+    send_signal(pid, true)
+}
+
+/// Resume a process and its process group, previously suspended with
+/// `suspend_process`.
+///
+/// Returns true if the signal was delivered. Always false on non-unix platforms.
+pub fn resume_process(pid: u32) -> bool {
+    send_signal(pid, false)
+}
+
+#[cfg(unix)]
+fn send_signal(pid: u32, stop: bool) -> bool {
+    use nix::sys::signal::{kill, killpg, Signal};
+    use nix::unistd::Pid;
+
+    let signal = if stop {
+        Signal::SIGSTOP
+    } else {
+        Signal::SIGCONT
+    };
+    // A process that doesn't lead a group is signalled on its own
+    killpg(Pid::from_raw(pid as i32), signal)
+        .or_else(|_| kill(Pid::from_raw(pid as i32), signal))
+        .is_ok()
+}
+
+#[cfg(not(unix))]
+fn send_signal(_pid: u32, _stop: bool) -> bool {
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +84,55 @@ mod tests {
         token.cancel();
         assert!(is_cancelled(&token));
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_suspend_and_resume_process() {
+        let mut child = tokio::process::Command::new("/bin/sleep")
+            .arg("5")
+            .spawn()
+            .expect("spawn should succeed");
+        let pid = child.id().expect("child should have a pid");
+
+        assert!(suspend_process(pid));
+        assert!(resume_process(pid));
+
+        child.kill().await.expect("kill should succeed");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_suspend_stops_grandchildren() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let ticks = dir.path().join("ticks");
+        let script = format!(
+            "sh -c 'while :; do echo x >> {}; sleep 0.02; done' & wait",
+            ticks.display()
+        );
+        let mut child = tokio::process::Command::new("sh")
+            .args(["-c", &script])
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .expect("spawn should succeed");
+        let pid = child.id().expect("child should have a pid");
+        let count = || std::fs::read_to_string(&ticks).unwrap_or_default().len();
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(suspend_process(pid));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        let stopped_at = count();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert_eq!(count(), stopped_at, "grandchild kept running while suspended");
+
+        assert!(resume_process(pid));
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        assert!(count() > stopped_at, "grandchild should run again after resume");
+
+        let _ = nix::sys::signal::killpg(
+            nix::unistd::Pid::from_raw(pid as i32),
+            nix::sys::signal::Signal::SIGKILL,
+        );
+        let _ = child.wait().await;
+    }
 }
@@ -0,0 +1,329 @@
+//! Built-in tools for the `http` agent backend.
+//!
+//! The model gets four tools named after their Claude CLI counterparts (Read,
+//! Write, Edit, Bash) so tool summaries render the same in the TUI. File tools
+//! only touch paths inside the working directory; Bash runs `sh -c` with the
+//! working directory as its current directory but is not otherwise sandboxed.
+
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+/// Longest tool output returned to the model, in bytes.
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// A tool the model can call.
+#[derive(Debug, Clone)]
+pub struct ToolSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON Schema of the tool's input object.
+    pub input_schema: Value,
+}
+
+/// The tools offered to the model.
+pub fn tool_specs() -> Vec<ToolSpec> {
+    vec![
+        ToolSpec {
+            name: "Read",
+            description: "Read a UTF-8 text file in the working directory.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "file_path": {"type": "string", "description": "Path relative to the working directory"}
+                },
+                "required": ["file_path"]
+            }),
+        },
+        ToolSpec {
+            name: "Write",
+            description:
+                "Create or overwrite a file in the working directory, creating parent directories.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "file_path": {"type": "string", "description": "Path relative to the working directory"},
+                    "content": {"type": "string"}
+                },
+                "required": ["file_path", "content"]
+            }),
+        },
+        ToolSpec {
+            name: "Edit",
+            description:
+                "Replace old_string with new_string in a file. old_string must occur exactly once.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "file_path": {"type": "string", "description": "Path relative to the working directory"},
+                    "old_string": {"type": "string"},
+                    "new_string": {"type": "string"}
+                },
+                "required": ["file_path", "old_string", "new_string"]
+            }),
+        },
+        ToolSpec {
+            name: "Bash",
+            description:
+                "Run a shell command in the working directory and return its exit code and output.",
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "command": {"type": "string"}
+                },
+                "required": ["command"]
+            }),
+        },
+    ]
+}
+
+/// The directory the tools operate in.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+}
+
+impl Workspace {
+    /// Tools confined to `root`.
+    pub fn new(root: &Path) -> std::io::Result<Self> {
+        Ok(Self {
+            root: root.canonicalize()?,
+        })
+    }
+
+    /// Run tool `name` on `input`.
+    ///
+    /// Returns the text to send back to the model; `Err` carries the message
+    /// for a failed call (reported to the model as an error result).
+    pub async fn run(&self, name: &str, input: &Value) -> Result<String, String> {
+        match name {
+            "Read" => {
+                let path = self.resolve(str_arg(input, "file_path")?)?;
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+                Ok(truncate_output(content))
+            }
+            "Write" => {
+                let path = self.resolve(str_arg(input, "file_path")?)?;
+                let content = str_arg(input, "content")?;
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("Cannot create {}: {}", parent.display(), e))?;
+                }
+                std::fs::write(&path, content)
+                    .map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+                Ok(format!(
+                    "Wrote {} bytes to {}",
+                    content.len(),
+                    self.display(&path)
+                ))
+            }
+            "Edit" => {
+                let path = self.resolve(str_arg(input, "file_path")?)?;
+                let old = str_arg(input, "old_string")?;
+                let new = str_arg(input, "new_string")?;
+                let content = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+                match content.matches(old).count() {
+                    0 => Err(format!("old_string not found in {}", self.display(&path))),
+                    1 => {
+                        std::fs::write(&path, content.replacen(old, new, 1))
+                            .map_err(|e| format!("Cannot write {}: {}", path.display(), e))?;
+                        Ok(format!("Edited {}", self.display(&path)))
+                    }
+                    n => Err(format!(
+                        "old_string occurs {} times in {}; include more context",
+                        n,
+                        self.display(&path)
+                    )),
+                }
+            }
+            "Bash" => {
+                let command = str_arg(input, "command")?;
+                let output = Command::new("sh")
+                    .arg("-c")
+                    .arg(command)
+                    .current_dir(&self.root)
+                    .stdin(Stdio::null())
+                    .kill_on_drop(true)
+                    .output()
+                    .await
+                    .map_err(|e| format!("Cannot run sh: {}", e))?;
+                let mut text = format!("exit code: {}\n", output.status.code().unwrap_or(-1));
+                text.push_str(&String::from_utf8_lossy(&output.stdout));
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                Ok(truncate_output(text))
+            }
+            other => Err(format!("Unknown tool: {}", other)),
+        }
+    }
+
+    /// Resolve `path` against the root, refusing anything that leaves it.
+    ///
+    /// `..` components are resolved lexically, then the nearest existing
+    /// ancestor is canonicalized so symlinks can't point outside either.
+    /// Dangling symlinks count as existing, so writing through one is refused.
+    fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let outside = || format!("{} is outside the working directory", path);
+        let resolved = normalize(&self.root.join(path));
+
+        // symlink_metadata doesn't follow links, so a dangling one stops the walk
+        let mut existing = resolved.as_path();
+        while existing.symlink_metadata().is_err() {
+            existing = existing.parent().unwrap_or(&self.root);
+        }
+        let real = match existing.canonicalize() {
+            Ok(real) => real,
+            Err(e) => {
+                if let Ok(target) = std::fs::read_link(existing) {
+                    let parent = existing.parent().unwrap_or(&self.root);
+                    if !normalize(&parent.join(target)).starts_with(&self.root) {
+                        return Err(outside());
+                    }
+                }
+                return Err(format!("Cannot resolve {}: {}", path, e));
+            }
+        };
+        if !resolved.starts_with(&self.root) || !real.starts_with(&self.root) {
+            return Err(outside());
+        }
+        Ok(resolved)
+    }
+
+    /// `path` relative to the root, for messages.
+    fn display(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    }
+}
+
+/// Resolve `.` and `..` components of an absolute path without touching the
+/// file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// A required string field of a tool input.
+fn str_arg<'a>(input: &'a Value, key: &str) -> Result<&'a str, String> {
+    input
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing string argument '{}'", key))
+}
+
+/// Cut `text` to MAX_OUTPUT_BYTES on a char boundary.
+fn truncate_output(mut text: String) -> String {
+    if text.len() > MAX_OUTPUT_BYTES {
+        let mut end = MAX_OUTPUT_BYTES;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("\n[output truncated]");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_tools_round_trip() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let workspace = Workspace::new(dir.path()).expect("workspace");
+
+        let wrote = workspace
+            .run(
+                "Write",
+                &json!({"file_path": "src/a.txt", "content": "one two"}),
+            )
+            .await
+            .expect("write");
+        assert_eq!(wrote, "Wrote 7 bytes to src/a.txt");
+
+        workspace
+            .run(
+                "Edit",
+                &json!({"file_path": "src/a.txt", "old_string": "two", "new_string": "three"}),
+            )
+            .await
+            .expect("edit");
+        let read = workspace
+            .run("Read", &json!({"file_path": "./src/../src/a.txt"}))
+            .await
+            .expect("read");
+        assert_eq!(read, "one three");
+
+        let err = workspace
+            .run(
+                "Edit",
+                &json!({"file_path": "src/a.txt", "old_string": "missing", "new_string": "x"}),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.contains("not found"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_paths_outside_workspace_are_refused() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let workspace = Workspace::new(dir.path()).expect("workspace");
+
+        for path in ["../escape.txt", "/etc/passwd", "a/../../escape.txt"] {
+            let err = workspace
+                .run("Write", &json!({"file_path": path, "content": "x"}))
+                .await
+                .unwrap_err();
+            assert!(err.contains("outside the working directory"), "{}", err);
+        }
+
+        let outside = tempfile::tempdir().expect("temp dir");
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("link")).expect("symlink");
+        let err = workspace
+            .run("Write", &json!({"file_path": "link/x.txt", "content": "x"}))
+            .await
+            .unwrap_err();
+        assert!(err.contains("outside the working directory"), "{}", err);
+        assert!(!outside.path().join("x.txt").exists());
+
+        // A dangling link is not "missing": writing through it would escape
+        std::os::unix::fs::symlink(outside.path().join("new.txt"), dir.path().join("dangling"))
+            .expect("symlink");
+        let err = workspace
+            .run("Write", &json!({"file_path": "dangling", "content": "x"}))
+            .await
+            .unwrap_err();
+        assert!(err.contains("outside the working directory"), "{}", err);
+        assert!(!outside.path().join("new.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_bash_runs_in_workspace() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        std::fs::write(dir.path().join("marker"), "").expect("marker");
+        let workspace = Workspace::new(dir.path()).expect("workspace");
+
+        let output = workspace
+            .run("Bash", &json!({"command": "ls; exit 3"}))
+            .await
+            .expect("bash");
+        assert!(output.starts_with("exit code: 3\n"), "{}", output);
+        assert!(output.contains("marker"), "{}", output);
+        assert!(workspace.run("Grep", &json!({})).await.is_err());
+    }
+}
@@ -6,13 +6,27 @@
 use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use throbber_widgets_tui::ThrobberState;
 
 use crate::build::tokens::TokenUsage;
-use crate::subprocess::StreamEvent;
-use crate::tui::conversation::ConversationBuffer;
+use crate::tui::conversation::{ConversationBuffer, ConversationItem};
+
+/// Slowest and fastest replay speeds.
+pub const MIN_PLAYBACK_SPEED: f64 = 0.25;
+pub const MAX_PLAYBACK_SPEED: f64 = 64.0;
+
+/// Playback controls for `rslph replay`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Playback {
+    /// Speed multiplier (1.0 is normal speed).
+    pub speed: f64,
+    /// Iterations before this one are played without delay.
+    pub skip_to: u32,
+    /// Bumped to play the recording again from the start.
+    pub restarts: u32,
+}
 
 /// A group of consecutive tool uses under a common header.
 ///
@@ -288,6 +302,8 @@ pub struct App {
     pub current_task: u32,
     /// Total number of tasks in current iteration.
     pub total_tasks: u32,
+    /// Number of tasks marked blocked in the progress file.
+    pub blocked_tasks: u32,
     /// Context usage as a ratio (0.0 to 1.0).
     pub context_usage: f64,
     /// Name of the model being used (e.g., "claude-sonnet-4-20250514").
@@ -316,6 +332,10 @@ pub struct App {
     pub viewing_iteration: u32,
     /// Whether the build is paused.
     pub is_paused: bool,
+    /// When the current pause started (None while running).
+    pub paused_since: Option<Instant>,
+    /// Total time spent in completed pauses this session.
+    pub paused_total: Duration,
     /// Whether the application should quit.
     pub should_quit: bool,
     /// Maximum system messages to keep expanded (rolling limit).
@@ -324,6 +344,8 @@ pub struct App {
     // Token tracking state
     /// Cumulative token usage across all iterations.
     pub total_tokens: TokenUsage,
+    /// Cost of the current iteration in USD.
+    pub iteration_cost_usd: f64,
 
     // Conversation view state (enhanced TUI)
     /// Full conversation history for enhanced display.
@@ -358,6 +380,10 @@ pub struct App {
     pub input_buffer: String,
     /// The question being answered.
     pub current_question: Option<String>,
+
+    // Replay state
+    /// Playback controls when replaying a recorded run (None for live builds).
+    pub playback: Option<Playback>,
 }
 
 impl Default for App {
@@ -367,6 +393,7 @@ impl Default for App {
             max_iterations: 1,
             current_task: 0,
             total_tasks: 0,
+            blocked_tasks: 0,
             context_usage: 0.0,
             model_name: String::new(),
             project_name: String::new(),
@@ -379,9 +406,12 @@ impl Default for App {
             selected_group: None,
             viewing_iteration: 0,
             is_paused: false,
+            paused_since: None,
+            paused_total: Duration::ZERO,
             should_quit: false,
             max_system_expanded: 5,
             total_tokens: TokenUsage::default(),
+            iteration_cost_usd: 0.0,
             conversation: ConversationBuffer::new(1000),
             conversation_scroll: 0,
             show_conversation: false,
@@ -393,6 +423,7 @@ impl Default for App {
             input_mode: false,
             input_buffer: String::new(),
             current_question: None,
+            playback: None,
         }
     }
 }
@@ -412,6 +443,25 @@ impl App {
         }
     }
 
+    /// Drop everything received from the agent, keeping the session settings.
+    fn clear_output(&mut self) {
+        *self = Self {
+            max_iterations: self.max_iterations,
+            total_tasks: self.total_tasks,
+            model_name: std::mem::take(&mut self.model_name),
+            project_name: std::mem::take(&mut self.project_name),
+            log_path: self.log_path.take(),
+            is_paused: self.is_paused,
+            paused_since: self.paused_since,
+            paused_total: self.paused_total,
+            max_system_expanded: self.max_system_expanded,
+            show_conversation: self.show_conversation,
+            session_start: self.session_start,
+            playback: self.playback,
+            ..Default::default()
+        };
+    }
+
     /// Update the app state based on an event.
     pub fn update(&mut self, event: AppEvent) {
         match event {
@@ -439,10 +489,39 @@ impl App {
             }
             AppEvent::TogglePause => {
                 self.is_paused = !self.is_paused;
+                if self.is_paused {
+                    self.paused_since = Some(Instant::now());
+                } else if let Some(since) = self.paused_since.take() {
+                    self.paused_total += since.elapsed();
+                }
             }
             AppEvent::Quit => {
                 self.should_quit = true;
             }
+            AppEvent::PlaybackFaster => {
+                if let Some(ref mut playback) = self.playback {
+                    playback.speed = (playback.speed * 2.0).min(MAX_PLAYBACK_SPEED);
+                }
+            }
+            AppEvent::PlaybackSlower => {
+                if let Some(ref mut playback) = self.playback {
+                    playback.speed = (playback.speed / 2.0).max(MIN_PLAYBACK_SPEED);
+                }
+            }
+            AppEvent::PlaybackSkip => {
+                if let Some(ref mut playback) = self.playback {
+                    playback.skip_to = playback.skip_to.max(self.current_iteration + 1);
+                }
+            }
+            AppEvent::PlaybackBack => {
+                if let Some(ref mut playback) = self.playback {
+                    playback.skip_to = self.current_iteration.saturating_sub(1).max(1);
+                    playback.restarts += 1;
+                }
+            }
+            AppEvent::PlaybackRestart => {
+                self.clear_output();
+            }
             AppEvent::SelectPrevMessage => {
                 self.select_prev_group();
             }
@@ -465,10 +544,7 @@ impl App {
                 self.conversation_scroll = (self.conversation_scroll + lines)
                     .min(self.conversation.len().saturating_sub(1));
             }
-            AppEvent::StreamEvent(stream_event) => {
-                // Extract conversation items from the stream event
-                let items = stream_event.extract_conversation_items();
-
+            AppEvent::Conversation(items) => {
                 // Start streaming if we receive any content
                 if !items.is_empty() && !self.is_streaming {
                     self.start_streaming();
@@ -510,12 +586,15 @@ impl App {
                 output_tokens,
                 cache_creation_input_tokens,
                 cache_read_input_tokens,
+                cost_usd,
             } => {
                 // Token events contain per-message values; we accumulate across all messages and iterations
                 self.total_tokens.input_tokens += input_tokens;
                 self.total_tokens.output_tokens += output_tokens;
                 self.total_tokens.cache_creation_input_tokens += cache_creation_input_tokens;
                 self.total_tokens.cache_read_input_tokens += cache_read_input_tokens;
+                self.total_tokens.cost_usd += cost_usd;
+                self.iteration_cost_usd += cost_usd;
             }
             AppEvent::IterationStart { iteration } => {
                 // Finalize current groups before starting new iteration
@@ -523,17 +602,22 @@ impl App {
                 self.finalize_system_group();
                 self.current_iteration = iteration;
                 self.viewing_iteration = iteration;
+                self.iteration_cost_usd = 0.0;
                 self.scroll_offset = 0;
                 self.selected_message = None;
                 self.selected_group = None;
                 // Start a new group for this iteration
                 self.current_group = Some(MessageGroup::new(iteration));
             }
-            AppEvent::IterationComplete { tasks_done } => {
+            AppEvent::IterationComplete {
+                tasks_done,
+                tasks_blocked,
+            } => {
                 // Finalize current groups
                 self.finalize_current_group();
                 self.finalize_system_group();
                 self.current_task = tasks_done;
+                self.blocked_tasks = tasks_blocked;
                 self.viewing_iteration = self.current_iteration;
                 self.selected_message = None;
                 self.selected_group = None;
@@ -596,6 +680,11 @@ impl App {
         }
     }
 
+    /// Total time spent paused, including the pause in progress.
+    pub fn paused_duration(&self) -> Duration {
+        self.paused_total + self.paused_since.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
     /// Get display items for the currently viewed iteration.
     pub fn display_items_for_viewing(&self) -> Vec<&DisplayItem> {
         let items: Vec<&DisplayItem> = self
@@ -966,6 +1055,7 @@ impl App {
 ///
 /// These are converted from raw crossterm events or sent from subprocess handlers.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum AppEvent {
     // Keyboard/mouse navigation
     /// Scroll up one line.
@@ -981,6 +1071,18 @@ pub enum AppEvent {
     /// Request application quit.
     Quit,
 
+    // Replay controls (ignored for live builds)
+    /// Double the replay speed.
+    PlaybackFaster,
+    /// Halve the replay speed.
+    PlaybackSlower,
+    /// Fast-forward to the next iteration.
+    PlaybackSkip,
+    /// Play the recording again up to the previous iteration.
+    PlaybackBack,
+    /// The recording is being played again from the start.
+    PlaybackRestart,
+
     // Message selection (for collapse/expand)
     /// Select previous message.
     SelectPrevMessage,
@@ -998,8 +1100,8 @@ pub enum AppEvent {
     ConversationScrollUp(usize),
     /// Scroll conversation down by N lines.
     ConversationScrollDown(usize),
-    /// Raw stream event for conversation extraction.
-    StreamEvent(StreamEvent),
+    /// Conversation items from the agent's output.
+    Conversation(Vec<ConversationItem>),
 
     // Subprocess events
     /// New output from Claude (text).
@@ -1014,6 +1116,8 @@ pub enum AppEvent {
         output_tokens: u64,
         cache_creation_input_tokens: u64,
         cache_read_input_tokens: u64,
+        /// Cost of this usage in USD.
+        cost_usd: f64,
     },
     /// New iteration is starting.
     IterationStart {
@@ -1024,6 +1128,8 @@ pub enum AppEvent {
     IterationComplete {
         /// Number of tasks completed in this iteration.
         tasks_done: u32,
+        /// Number of tasks currently marked blocked.
+        tasks_blocked: u32,
     },
     /// Log message from build loop (displayed as system message).
     LogMessage(String),
@@ -1075,6 +1181,43 @@ mod tests {
         assert_eq!(app.scroll_offset, 0);
     }
 
+    #[test]
+    fn test_playback_controls() {
+        let mut app = App {
+            current_iteration: 2,
+            playback: Some(Playback {
+                speed: 1.0,
+                skip_to: 0,
+                restarts: 0,
+            }),
+            ..Default::default()
+        };
+
+        app.update(AppEvent::PlaybackFaster);
+        app.update(AppEvent::PlaybackFaster);
+        assert_eq!(app.playback.map(|p| p.speed), Some(4.0));
+        for _ in 0..10 {
+            app.update(AppEvent::PlaybackSlower);
+        }
+        assert_eq!(app.playback.map(|p| p.speed), Some(MIN_PLAYBACK_SPEED));
+
+        app.update(AppEvent::PlaybackSkip);
+        assert_eq!(app.playback.map(|p| p.skip_to), Some(3));
+
+        // Going back restarts playback and fast-forwards to the previous iteration
+        app.update(AppEvent::PlaybackBack);
+        assert_eq!(app.playback.map(|p| (p.skip_to, p.restarts)), Some((1, 1)));
+        app.update(AppEvent::PlaybackRestart);
+        assert_eq!(app.current_iteration, 0);
+        assert_eq!(app.playback.map(|p| p.speed), Some(MIN_PLAYBACK_SPEED));
+
+        // Live builds have no playback to control
+        let mut live = App::default();
+        live.update(AppEvent::PlaybackFaster);
+        live.update(AppEvent::PlaybackSkip);
+        assert!(live.playback.is_none());
+    }
+
     #[test]
     fn test_app_update_quit() {
         let mut app = App::default();
@@ -1096,6 +1239,26 @@ mod tests {
         assert!(!app.is_paused);
     }
 
+    #[test]
+    fn test_app_tracks_paused_duration() {
+        let mut app = App::default();
+        assert_eq!(app.paused_duration(), Duration::ZERO);
+
+        app.update(AppEvent::TogglePause);
+        assert!(app.paused_since.is_some());
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(app.paused_duration() >= Duration::from_millis(10));
+
+        app.update(AppEvent::TogglePause);
+        assert!(app.paused_since.is_none());
+        let total = app.paused_total;
+        assert!(total >= Duration::from_millis(10));
+
+        // Duration is frozen while running
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(app.paused_duration(), total);
+    }
+
     #[test]
     fn test_app_update_context_usage() {
         let mut app = App::default();
@@ -1121,6 +1284,7 @@ mod tests {
             output_tokens: 500,
             cache_creation_input_tokens: 200,
             cache_read_input_tokens: 100,
+            cost_usd: 0.25,
         });
 
         assert_eq!(app.total_tokens.input_tokens, 1000);
@@ -1134,6 +1298,7 @@ mod tests {
             output_tokens: 1300,
             cache_creation_input_tokens: 300,
             cache_read_input_tokens: 200,
+            cost_usd: 0.5,
         });
 
         // Verify accumulated values (1000+2500, 500+1300, 200+300, 100+200)
@@ -1141,6 +1306,13 @@ mod tests {
         assert_eq!(app.total_tokens.output_tokens, 1800);
         assert_eq!(app.total_tokens.cache_creation_input_tokens, 500);
         assert_eq!(app.total_tokens.cache_read_input_tokens, 300);
+        assert!((app.total_tokens.cost_usd - 0.75).abs() < f64::EPSILON);
+        assert!((app.iteration_cost_usd - 0.75).abs() < f64::EPSILON);
+
+        // A new iteration resets the per-iteration cost but not the total
+        app.update(AppEvent::IterationStart { iteration: 2 });
+        assert_eq!(app.iteration_cost_usd, 0.0);
+        assert!((app.total_tokens.cost_usd - 0.75).abs() < f64::EPSILON);
     }
 
     #[test]
@@ -1221,8 +1393,12 @@ mod tests {
             output_tokens: 50,
             cache_creation_input_tokens: 20,
             cache_read_input_tokens: 10,
+            cost_usd: 0.01,
+        };
+        let _ = AppEvent::IterationComplete {
+            tasks_done: 3,
+            tasks_blocked: 0,
         };
-        let _ = AppEvent::IterationComplete { tasks_done: 3 };
         let _ = AppEvent::LogMessage("log".to_string());
         let _ = AppEvent::Render;
     }
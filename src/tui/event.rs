@@ -14,12 +14,13 @@ use futures::StreamExt;
 use tokio::sync::mpsc;
 
 use super::AppEvent;
-use crate::subprocess::StreamEvent;
+use crate::tui::ConversationItem;
 
 /// Subprocess event that can be sent to the TUI.
 ///
-/// These events come from the ClaudeRunner or other subprocess components.
+/// These events come from the agent subprocess or other build components.
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum SubprocessEvent {
     /// New line of output from Claude (assistant text).
     Output(String),
@@ -33,19 +34,23 @@ pub enum SubprocessEvent {
         output_tokens: u64,
         cache_creation_input_tokens: u64,
         cache_read_input_tokens: u64,
+        /// Cost of this usage in USD.
+        cost_usd: f64,
     },
     /// New iteration is starting (sets current_iteration).
     IterationStart { iteration: u32 },
     /// Iteration completed.
-    IterationDone { tasks_done: u32 },
+    IterationDone { tasks_done: u32, tasks_blocked: u32 },
     /// Log message (displayed in output area but not treated as Claude message).
     Log(String),
-    /// Raw stream event for conversation view extraction.
-    StreamEvent(StreamEvent),
+    /// Conversation view items normalized by the agent backend.
+    Conversation(Vec<ConversationItem>),
     /// Claude CLI is waiting for user input.
     InputRequired { question: String },
     /// Stderr output from Claude CLI subprocess.
     Stderr(String),
+    /// A replay starts over; clears everything shown so far.
+    ReplayRestart,
 }
 
 impl From<SubprocessEvent> for AppEvent {
@@ -61,24 +66,31 @@ impl From<SubprocessEvent> for AppEvent {
                 output_tokens,
                 cache_creation_input_tokens,
                 cache_read_input_tokens,
+                cost_usd,
             } => AppEvent::TokenUsage {
                 input_tokens,
                 output_tokens,
                 cache_creation_input_tokens,
                 cache_read_input_tokens,
+                cost_usd,
             },
             SubprocessEvent::IterationStart { iteration } => AppEvent::IterationStart { iteration },
-            SubprocessEvent::IterationDone { tasks_done } => {
-                AppEvent::IterationComplete { tasks_done }
-            }
+            SubprocessEvent::IterationDone {
+                tasks_done,
+                tasks_blocked,
+            } => AppEvent::IterationComplete {
+                tasks_done,
+                tasks_blocked,
+            },
             // Log messages are displayed in the output but treated as system messages
             SubprocessEvent::Log(s) => AppEvent::LogMessage(s),
-            // Stream events are forwarded for conversation view extraction
-            SubprocessEvent::StreamEvent(e) => AppEvent::StreamEvent(e),
+            // Conversation items are forwarded to the conversation view
+            SubprocessEvent::Conversation(items) => AppEvent::Conversation(items),
             // Input required events are forwarded for TUI input handling
             SubprocessEvent::InputRequired { question } => AppEvent::InputRequired { question },
             // Stderr output is logged with [stderr] prefix for visibility
             SubprocessEvent::Stderr(s) => AppEvent::LogMessage(format!("[stderr] {}", s)),
+            SubprocessEvent::ReplayRestart => AppEvent::PlaybackRestart,
         }
     }
 }
@@ -160,15 +172,11 @@ impl EventHandler {
 
                 // Subprocess events from channel
                 maybe_subprocess = subprocess_rx.recv() => {
-                    match maybe_subprocess {
-                        Some(event) => {
-                            if tx.send(AppEvent::from(event)).is_err() {
-                                // Receiver dropped, exit loop
-                                break;
-                            }
-                        }
-                        None => {
-                            // Channel closed, but keep running for keyboard events
+                    // On None the channel is closed, but keep running for keyboard events
+                    if let Some(event) = maybe_subprocess {
+                        if tx.send(AppEvent::from(event)).is_err() {
+                            // Receiver dropped, exit loop
+                            break;
                         }
                     }
                 }
@@ -211,6 +219,10 @@ impl EventHandler {
                     KeyCode::Char('q') => Some(AppEvent::Quit),
                     KeyCode::Char('c') => Some(AppEvent::ToggleConversation),
                     KeyCode::Char('t') => Some(AppEvent::ToggleThinkingCollapse),
+                    KeyCode::Char('+') | KeyCode::Char('=') => Some(AppEvent::PlaybackFaster),
+                    KeyCode::Char('-') => Some(AppEvent::PlaybackSlower),
+                    KeyCode::Char('n') => Some(AppEvent::PlaybackSkip),
+                    KeyCode::Char('b') => Some(AppEvent::PlaybackBack),
                     KeyCode::PageUp => Some(AppEvent::ConversationScrollUp(10)),
                     KeyCode::PageDown => Some(AppEvent::ConversationScrollDown(10)),
                     KeyCode::Esc => Some(AppEvent::Quit),
@@ -270,6 +282,7 @@ mod tests {
             output_tokens: 1500,
             cache_creation_input_tokens: 2000,
             cache_read_input_tokens: 1000,
+            cost_usd: 0.05,
         };
         let app_event: AppEvent = token_usage.into();
         assert!(matches!(
@@ -279,14 +292,21 @@ mod tests {
                 output_tokens: 1500,
                 cache_creation_input_tokens: 2000,
                 cache_read_input_tokens: 1000,
+                ..
             }
         ));
 
-        let done = SubprocessEvent::IterationDone { tasks_done: 5 };
+        let done = SubprocessEvent::IterationDone {
+            tasks_done: 5,
+            tasks_blocked: 1,
+        };
         let app_event: AppEvent = done.into();
         assert!(matches!(
             app_event,
-            AppEvent::IterationComplete { tasks_done: 5 }
+            AppEvent::IterationComplete {
+                tasks_done: 5,
+                tasks_blocked: 1
+            }
         ));
 
         let start = SubprocessEvent::IterationStart { iteration: 3 };
@@ -306,16 +326,12 @@ mod tests {
     }
 
     #[test]
-    fn test_subprocess_stream_event_conversion() {
-        use crate::subprocess::StreamEvent;
-
-        // Create a minimal stream event for testing
-        let json = r#"{"type":"assistant","message":{"content":[{"type":"text","text":"hello"}]}}"#;
-        let stream_event = StreamEvent::parse(json).unwrap();
+    fn test_subprocess_conversation_conversion() {
+        let items = vec![ConversationItem::Text("hello".to_string())];
 
-        let subprocess_event = SubprocessEvent::StreamEvent(stream_event);
+        let subprocess_event = SubprocessEvent::Conversation(items);
         let app_event: AppEvent = subprocess_event.into();
 
-        assert!(matches!(app_event, AppEvent::StreamEvent(_)));
+        assert!(matches!(app_event, AppEvent::Conversation(items) if items.len() == 1));
     }
 }
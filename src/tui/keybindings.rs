@@ -114,7 +114,14 @@ mod tests {
         app.current_iteration = 1;
         app.current_task = 0;
 
-        handle_event(&mut app, AppEvent::IterationComplete { tasks_done: 3 }, 20);
+        handle_event(
+            &mut app,
+            AppEvent::IterationComplete {
+                tasks_done: 3,
+                tasks_blocked: 0,
+            },
+            20,
+        );
         assert_eq!(app.current_task, 3);
         assert_eq!(app.viewing_iteration, 1);
     }
@@ -15,7 +15,9 @@ pub mod theme;
 mod ui;
 mod widgets;
 
-pub use app::{App, AppEvent, Message, MessageRole};
+pub use app::{
+    App, AppEvent, Message, MessageRole, Playback, MAX_PLAYBACK_SPEED, MIN_PLAYBACK_SPEED,
+};
 pub use conversation::{ConversationBuffer, ConversationItem};
 pub use dashboard::{run_dashboard_tui, DashboardState, TrialProgress, TrialStatus};
 pub use event::{EventHandler, SubprocessEvent};
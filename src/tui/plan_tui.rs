@@ -360,7 +360,7 @@ fn render_question_input(frame: &mut Frame, area: Rect, state: &PlanTuiState) {
         if lines.is_empty() {
             lines.push(Line::from("_"));
         } else if let Some(last) = lines.last_mut() {
-            *last = Line::from(format!("{}_", last.to_string()));
+            *last = Line::from(format!("{}_", last));
         }
         lines
     };
@@ -3,13 +3,13 @@
 //! Provides the async run loop that ties together terminal, events, and rendering.
 
 use crate::error::RslphError;
-use crate::tui::app::App;
+use crate::tui::app::{App, Playback};
 use crate::tui::event::{EventHandler, SubprocessEvent};
 use crate::tui::keybindings::handle_event;
 use crate::tui::terminal::{init_terminal, restore_terminal};
 use crate::tui::ui::render;
 
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 
 /// Run the TUI event loop.
@@ -19,6 +19,8 @@ use tokio_util::sync::CancellationToken;
 /// * `app` - Initial app state
 /// * `recent_count` - Number of recent messages to display (from config)
 /// * `cancel_token` - Token to cancel the build loop when user quits
+/// * `pause_tx` - Publishes the pause flag so the build loop can hold between iterations
+/// * `playback_tx` - Publishes replay speed and skip requests (`rslph replay` only)
 ///
 /// # Returns
 ///
@@ -31,6 +33,8 @@ pub async fn run_tui(
     mut app: App,
     recent_count: usize,
     cancel_token: CancellationToken,
+    pause_tx: watch::Sender<bool>,
+    playback_tx: Option<watch::Sender<Playback>>,
 ) -> Result<mpsc::UnboundedSender<SubprocessEvent>, RslphError> {
     let mut terminal = init_terminal()
         .map_err(|e| RslphError::Subprocess(format!("Terminal init failed: {}", e)))?;
@@ -62,6 +66,20 @@ pub async fn run_tui(
                     cancel_token.cancel();
                     break;
                 }
+
+                // Publish pause changes to the build loop
+                pause_tx.send_if_modified(|paused| {
+                    let changed = *paused != app.is_paused;
+                    *paused = app.is_paused;
+                    changed
+                });
+                if let (Some(tx), Some(playback)) = (&playback_tx, app.playback) {
+                    tx.send_if_modified(|current| {
+                        let changed = *current != playback;
+                        *current = playback;
+                        changed
+                    });
+                }
             } else {
                 // Event stream ended
                 break;
@@ -12,7 +12,7 @@ use ratatui::{
 use super::app::App;
 use super::conversation::render_conversation;
 use super::widgets::spinner::render_spinner;
-use super::widgets::status_bar::render_header;
+use super::widgets::status_bar::{format_elapsed, render_header};
 use super::widgets::thread_view::render_thread;
 
 /// Render the entire TUI interface.
@@ -73,7 +73,7 @@ pub fn render(frame: &mut Frame, app: &mut App, recent_count: usize) {
 
     // Show pause overlay if paused
     if app.is_paused {
-        render_pause_overlay(frame, body);
+        render_pause_overlay(frame, body, app);
     }
 }
 
@@ -93,7 +93,11 @@ fn render_body(frame: &mut Frame, area: Rect, app: &App, recent_count: usize) {
 
 /// Render the footer with key binding hints and log path.
 fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
-    let key_hints = "j/k:scroll  Tab:select  Enter:toggle  {/}:iteration  c:conversation  t:thinking  p:pause  Ctrl+C:quit";
+    let key_hints = if app.playback.is_some() {
+        "j/k:scroll  Tab:select  Enter:toggle  {/}:iteration  c:conversation  t:thinking  +/-:speed  n/b:next/back  p:pause  q:quit"
+    } else {
+        "j/k:scroll  Tab:select  Enter:toggle  {/}:iteration  c:conversation  t:thinking  p:pause  Ctrl+C:quit"
+    };
 
     // If log_path exists, show it on the right
     let log_display = app
@@ -125,8 +129,9 @@ fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Render a centered pause overlay.
-fn render_pause_overlay(frame: &mut Frame, area: Rect) {
-    let message = "PAUSED - press p to resume";
+fn render_pause_overlay(frame: &mut Frame, area: Rect, app: &App) {
+    let paused_for = format_elapsed(app.paused_since.map(|s| s.elapsed()).unwrap_or_default());
+    let message = format!("PAUSED {} - press p to resume", paused_for);
     let width = message.len() as u16 + 4;
     let height = 3;
 
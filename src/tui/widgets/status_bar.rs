@@ -2,9 +2,12 @@
 //!
 //! Renders the 2-line header showing:
 //! - Line 1: "rslph" branding on left, "◆ model | HH:MM:SS" on right
+//!   (plus the replay speed during `rslph replay`, "paused MM:SS" once the
+//!   build has been paused, and iteration and total cost once there is spend
+//!   to show)
 //! - Line 2: Iteration/task counts, token usage, and context usage bar
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use ratatui::{
     layout::{Alignment, Constraint, Layout, Rect},
@@ -14,13 +17,18 @@ use ratatui::{
 };
 
 use crate::build::tokens::format_tokens;
+use crate::pricing::format_cost;
 use crate::tui::app::App;
 use crate::tui::theme::symbols::model_tier_indicator;
 use crate::tui::widgets::progress_bar::render_context_bar;
 
 /// Format session duration as HH:MM:SS or MM:SS.
 fn format_session_time(start: Instant) -> String {
-    let elapsed = start.elapsed();
+    format_elapsed(start.elapsed())
+}
+
+/// Format a duration as HH:MM:SS or MM:SS.
+pub fn format_elapsed(elapsed: Duration) -> String {
     let secs = elapsed.as_secs();
     let mins = secs / 60;
     let hours = mins / 60;
@@ -42,7 +50,8 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
 
 /// Render the first line: "rslph" left, "◆ model | HH:MM:SS" right.
 fn render_branding_line(frame: &mut Frame, area: Rect, app: &App) {
-    let [left, right] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+    let [left, right] =
+        Layout::horizontal([Constraint::Length(5), Constraint::Fill(1)]).areas(area);
 
     frame.render_widget(
         Paragraph::new("rslph").style(Style::default().add_modifier(Modifier::BOLD)),
@@ -52,7 +61,26 @@ fn render_branding_line(frame: &mut Frame, area: Rect, app: &App) {
     // Format: "◆ claude-opus-4 | 05:23"
     let tier_symbol = model_tier_indicator(&app.model_name);
     let session_time = format_session_time(app.session_start);
-    let right_text = format!("{} {} | {}", tier_symbol, app.model_name, session_time);
+    let mut right_text = format!("{} {} | {}", tier_symbol, app.model_name, session_time);
+
+    if let Some(playback) = app.playback {
+        right_text.push_str(&format!(" | replay {}x", playback.speed));
+    }
+
+    // Show accumulated pause time once there is something to show
+    let paused = app.paused_duration();
+    if paused.as_secs() > 0 {
+        right_text.push_str(&format!(" | paused {}", format_elapsed(paused)));
+    }
+
+    // Show cost once the pricing table has priced some usage
+    if app.total_tokens.cost_usd > 0.0 {
+        right_text.push_str(&format!(
+            " | {} iter / {} total",
+            format_cost(app.iteration_cost_usd),
+            format_cost(app.total_tokens.cost_usd)
+        ));
+    }
 
     frame.render_widget(
         Paragraph::new(right_text).alignment(Alignment::Right),
@@ -63,12 +91,18 @@ fn render_branding_line(frame: &mut Frame, area: Rect, app: &App) {
 /// Render the second line: iteration/task counts, token usage, and context bar.
 fn render_status_line(frame: &mut Frame, area: Rect, app: &App) {
     // Format the status text with token counts (per CONTEXT.md decision - abbreviated format)
+    let blocked = if app.blocked_tasks > 0 {
+        format!(" ({} blocked)", app.blocked_tasks)
+    } else {
+        String::new()
+    };
     let status_text = format!(
-        "Iter {}/{} | Task {}/{} | In: {} | Out: {} | CacheW: {} | CacheR: {} | ",
+        "Iter {}/{} | Task {}/{}{} | In: {} | Out: {} | CacheW: {} | CacheR: {} | ",
         app.current_iteration,
         app.max_iterations,
         app.current_task,
         app.total_tasks,
+        blocked,
         format_tokens(app.total_tokens.input_tokens),
         format_tokens(app.total_tokens.output_tokens),
         format_tokens(app.total_tokens.cache_creation_input_tokens),
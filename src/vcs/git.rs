@@ -1,10 +1,10 @@
 //! Git VCS implementation.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 use crate::error::VcsError;
-use crate::vcs::{Vcs, VcsType};
+use crate::vcs::{parse_path_list, remove_new_untracked, Snapshot, Vcs, VcsType};
 
 /// Git VCS implementation.
 pub struct GitVcs {
@@ -28,6 +28,94 @@ impl GitVcs {
                 error: e.to_string(),
             })
     }
+
+    /// Run a git command, treating a non-zero exit as an error.
+    fn run_git_checked(&self, args: &[&str]) -> Result<Output, VcsError> {
+        let output = self.run_git(args)?;
+        if !output.status.success() {
+            return Err(VcsError::CommandFailed {
+                command: format!("git {}", args.join(" ")),
+                error: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(output)
+    }
+
+    /// List untracked, non-ignored files relative to the repository root.
+    fn untracked_files(&self) -> Result<Vec<PathBuf>, VcsError> {
+        let output = self.run_git_checked(&["ls-files", "--others", "--exclude-standard", "-z"])?;
+        Ok(parse_path_list(&output.stdout))
+    }
+
+    /// Full hash of the checked-out commit.
+    pub fn head(&self) -> Result<String, VcsError> {
+        let output = self.run_git_checked(&["rev-parse", "--verify", "HEAD"])?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Check out HEAD into a new detached worktree at `path`.
+    pub fn add_worktree(&self, path: &Path) -> Result<(), VcsError> {
+        let path = path.to_string_lossy();
+        self.run_git_checked(&["worktree", "add", "--quiet", "--detach", &path, "HEAD"])?;
+        Ok(())
+    }
+
+    /// Remove a worktree created by [`GitVcs::add_worktree`], discarding its changes.
+    pub fn remove_worktree(&self, path: &Path) -> Result<(), VcsError> {
+        let path = path.to_string_lossy();
+        self.run_git_checked(&["worktree", "remove", "--force", &path])?;
+        Ok(())
+    }
+
+    /// Commit all changes except `exclude` (relative to the root).
+    ///
+    /// Returns the commit hash, or None if nothing else changed.
+    pub fn commit_all_except(
+        &self,
+        message: &str,
+        exclude: &Path,
+    ) -> Result<Option<String>, VcsError> {
+        let exclude = format!(":(exclude){}", exclude.to_string_lossy());
+        self.run_git_checked(&["add", "-A", "--", ".", &exclude])?;
+
+        // `diff --cached --quiet` exits non-zero when something is staged
+        if self
+            .run_git(&["diff", "--cached", "--quiet"])?
+            .status
+            .success()
+        {
+            return Ok(None);
+        }
+        self.commit(message)?;
+        self.head().map(Some)
+    }
+
+    /// Merge `commit` into the checked-out branch.
+    ///
+    /// A conflicting merge is aborted, leaving the working copy as it was, and
+    /// reported as [`VcsError::MergeConflict`] with the conflicting paths.
+    pub fn merge(&self, commit: &str, message: &str) -> Result<(), VcsError> {
+        let output = self.run_git(&["merge", "--no-edit", "-m", message, commit])?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let conflicts = self.run_git_checked(&["diff", "--name-only", "--diff-filter=U", "-z"])?;
+        let paths: Vec<String> = parse_path_list(&conflicts.stdout)
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        if paths.is_empty() {
+            // Refused before merging (e.g. local changes would be overwritten)
+            return Err(VcsError::CommandFailed {
+                command: format!("git merge {}", commit),
+                error: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        self.run_git_checked(&["merge", "--abort"])?;
+        Err(VcsError::MergeConflict(paths))
+    }
 }
 
 impl Vcs for GitVcs {
@@ -91,6 +179,29 @@ impl Vcs for GitVcs {
 
         Ok(hash)
     }
+
+    fn snapshot(&self) -> Result<Snapshot, VcsError> {
+        let commit = self.head()?;
+
+        // `stash create` records tracked changes as a commit without touching the tree
+        let stash = self.run_git_checked(&["stash", "create"])?;
+        let pending = Some(String::from_utf8_lossy(&stash.stdout).trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        Ok(Snapshot {
+            commit,
+            pending,
+            untracked: self.untracked_files()?,
+        })
+    }
+
+    fn restore(&self, snapshot: &Snapshot) -> Result<(), VcsError> {
+        self.run_git_checked(&["reset", "--hard", "--quiet", &snapshot.commit])?;
+        if let Some(ref stash) = snapshot.pending {
+            self.run_git_checked(&["stash", "apply", "--quiet", stash])?;
+        }
+        remove_new_untracked(&self.root, &snapshot.untracked, self.untracked_files()?)
+    }
 }
 
 #[cfg(test)]
@@ -162,4 +273,126 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_none()); // Nothing to commit
     }
+
+    #[test]
+    fn test_git_restore_discards_changes_since_snapshot() {
+        let (dir, vcs) = setup_git_repo();
+        fs::write(dir.path().join("tracked.txt"), "original").expect("write");
+        vcs.commit_all("Initial").expect("initial commit");
+
+        // Pre-existing uncommitted work must survive the restore
+        fs::write(dir.path().join("tracked.txt"), "user edit").expect("write");
+        fs::write(dir.path().join("notes.txt"), "user notes").expect("write");
+
+        let snapshot = vcs.snapshot().expect("snapshot");
+        assert!(snapshot.pending.is_some());
+        assert_eq!(snapshot.untracked, vec![PathBuf::from("notes.txt")]);
+
+        // Simulate a failed iteration: commit an edit, then leave new files behind
+        fs::write(dir.path().join("tracked.txt"), "half done").expect("write");
+        vcs.run_git_checked(&["commit", "-am", "Iteration"])
+            .expect("iteration commit");
+        fs::create_dir_all(dir.path().join("src/new")).expect("mkdir");
+        fs::write(dir.path().join("src/new/mod.rs"), "broken").expect("write");
+        fs::write(dir.path().join("scratch.txt"), "tmp").expect("write");
+
+        vcs.restore(&snapshot).expect("restore");
+
+        let read = |name: &str| fs::read_to_string(dir.path().join(name)).expect("read");
+        assert_eq!(read("tracked.txt"), "user edit");
+        assert_eq!(read("notes.txt"), "user notes");
+        assert!(!dir.path().join("scratch.txt").exists());
+        assert!(!dir.path().join("src").exists());
+    }
+
+    #[test]
+    fn test_git_restore_clean_snapshot_keeps_ignored_files() {
+        let (dir, vcs) = setup_git_repo();
+        fs::write(dir.path().join(".gitignore"), "runs/\n").expect("write");
+        fs::write(dir.path().join("tracked.txt"), "original").expect("write");
+        vcs.commit_all("Initial").expect("initial commit");
+
+        let snapshot = vcs.snapshot().expect("snapshot");
+        assert!(snapshot.pending.is_none());
+
+        fs::write(dir.path().join("tracked.txt"), "changed").expect("write");
+        fs::create_dir_all(dir.path().join("runs")).expect("mkdir");
+        fs::write(dir.path().join("runs/state.json"), "{}").expect("write");
+
+        vcs.restore(&snapshot).expect("restore");
+        assert!(!vcs.has_changes().expect("has_changes"));
+        assert!(dir.path().join("runs/state.json").exists());
+    }
+
+    #[test]
+    fn test_git_snapshot_without_commits_fails() {
+        let (_dir, vcs) = setup_git_repo();
+        assert!(vcs.snapshot().is_err());
+    }
+
+    #[test]
+    fn test_git_worktree_commit_and_merge() {
+        let (dir, vcs) = setup_git_repo();
+        fs::write(dir.path().join("README.md"), "readme").expect("write");
+        vcs.commit_all("Initial").expect("initial commit");
+
+        let tree_dir = TempDir::new().expect("temp dir");
+        let tree_path = tree_dir.path().join("tree");
+        vcs.add_worktree(&tree_path).expect("add worktree");
+        let tree = GitVcs::new(tree_path.clone());
+
+        // Only the progress file changed: nothing to commit
+        fs::write(tree_path.join("progress.md"), "- [x] Task").expect("write");
+        let excluded = Path::new("progress.md");
+        assert!(tree
+            .commit_all_except("Progress only", excluded)
+            .expect("commit")
+            .is_none());
+
+        fs::write(tree_path.join("feature.txt"), "feature").expect("write");
+        let commit = tree
+            .commit_all_except("Add feature", excluded)
+            .expect("commit")
+            .expect("commit hash");
+        vcs.remove_worktree(&tree_path).expect("remove worktree");
+        assert!(!tree_path.exists());
+
+        vcs.merge(&commit, "Merge feature").expect("merge");
+        assert!(dir.path().join("feature.txt").exists());
+        assert!(!dir.path().join("progress.md").exists());
+    }
+
+    #[test]
+    fn test_git_merge_conflict_is_aborted() {
+        let (dir, vcs) = setup_git_repo();
+        fs::write(dir.path().join("shared.txt"), "base").expect("write");
+        vcs.commit_all("Initial").expect("initial commit");
+
+        let tree_dir = TempDir::new().expect("temp dir");
+        let tree_path = tree_dir.path().join("tree");
+        vcs.add_worktree(&tree_path).expect("add worktree");
+        let tree = GitVcs::new(tree_path.clone());
+        fs::write(tree_path.join("shared.txt"), "theirs").expect("write");
+        let commit = tree
+            .commit_all_except("Theirs", Path::new("progress.md"))
+            .expect("commit")
+            .expect("commit hash");
+        vcs.remove_worktree(&tree_path).expect("remove worktree");
+
+        fs::write(dir.path().join("shared.txt"), "ours").expect("write");
+        vcs.commit_all("Ours").expect("commit");
+
+        match vcs.merge(&commit, "Merge theirs") {
+            Err(VcsError::MergeConflict(paths)) => assert_eq!(paths, vec!["shared.txt"]),
+            other => panic!("expected merge conflict, got {:?}", other),
+        }
+        assert!(
+            !vcs.has_changes().expect("has_changes"),
+            "merge was aborted"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("shared.txt")).expect("read"),
+            "ours"
+        );
+    }
 }
@@ -1,6 +1,8 @@
 //! VCS (Version Control System) integration for auto-commit after iterations.
 //!
 //! Provides trait abstraction for Git and Sapling, with auto-detection.
+//! Snapshots of the working copy let the build loop discard a failed
+//! iteration's changes.
 
 mod git;
 mod sapling;
@@ -8,6 +10,7 @@ mod sapling;
 pub use git::GitVcs;
 pub use sapling::SaplingVcs;
 
+use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -37,6 +40,22 @@ pub struct VcsDetection {
     pub root: PathBuf,
 }
 
+/// Working copy state captured before an iteration.
+///
+/// Restoring a snapshot discards everything done since it was taken: commits,
+/// edits to tracked files, and new untracked files. Ignored files (such as
+/// `.rslph/runs`) are left alone, and untracked files from before the snapshot
+/// are kept unless the iteration committed them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Commit checked out when the snapshot was taken.
+    pub commit: String,
+    /// Uncommitted changes to tracked files (a git stash commit or a Sapling patch).
+    pub pending: Option<String>,
+    /// Untracked files present when the snapshot was taken, relative to the root.
+    pub untracked: Vec<PathBuf>,
+}
+
 /// Trait for VCS operations.
 pub trait Vcs: Send + Sync {
     /// Get the VCS type.
@@ -61,6 +80,51 @@ pub trait Vcs: Send + Sync {
         let hash = self.commit(message)?;
         Ok(Some(hash))
     }
+
+    /// Capture the current working copy so it can be restored later.
+    fn snapshot(&self) -> Result<Snapshot, VcsError>;
+
+    /// Return the working copy to the state captured in `snapshot`.
+    fn restore(&self, snapshot: &Snapshot) -> Result<(), VcsError>;
+}
+
+/// Delete untracked files that were not present when the snapshot was taken.
+///
+/// Directories left empty by the removal are deleted as well, up to `root`.
+fn remove_new_untracked(
+    root: &Path,
+    before: &[PathBuf],
+    now: Vec<PathBuf>,
+) -> Result<(), VcsError> {
+    let before: HashSet<&PathBuf> = before.iter().collect();
+
+    for rel in now.iter().filter(|p| !before.contains(p)) {
+        let path = root.join(rel);
+        std::fs::remove_file(&path).map_err(|e| VcsError::CommandFailed {
+            command: format!("remove {}", path.display()),
+            error: e.to_string(),
+        })?;
+
+        // remove_dir fails on non-empty directories, which ends the walk
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| *d != root) {
+            if std::fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse NUL- or newline-separated path output into relative paths.
+fn parse_path_list(output: &[u8]) -> Vec<PathBuf> {
+    String::from_utf8_lossy(output)
+        .split(['\0', '\n'])
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .collect()
 }
 
 /// Detect VCS type for the given path.
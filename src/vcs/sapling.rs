@@ -1,10 +1,11 @@
 //! Sapling VCS implementation.
 
+use std::io::Write;
 use std::path::PathBuf;
-use std::process::{Command, Output};
+use std::process::{Command, Output, Stdio};
 
 use crate::error::VcsError;
-use crate::vcs::{Vcs, VcsType};
+use crate::vcs::{parse_path_list, remove_new_untracked, Snapshot, Vcs, VcsType};
 
 /// Sapling VCS implementation.
 pub struct SaplingVcs {
@@ -28,6 +29,58 @@ impl SaplingVcs {
                 error: e.to_string(),
             })
     }
+
+    /// Run a Sapling command, treating a non-zero exit as an error.
+    fn run_sl_checked(&self, args: &[&str]) -> Result<Output, VcsError> {
+        let output = self.run_sl(args)?;
+        if !output.status.success() {
+            return Err(VcsError::CommandFailed {
+                command: format!("sl {}", args.join(" ")),
+                error: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(output)
+    }
+
+    /// Apply a patch to the working copy without committing it.
+    fn import_patch(&self, patch: &str) -> Result<(), VcsError> {
+        let command = "sl import --no-commit -".to_string();
+        let failed = |error: String| VcsError::CommandFailed {
+            command: command.clone(),
+            error,
+        };
+
+        let mut child = Command::new("sl")
+            .args(["import", "--no-commit", "-"])
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(e.to_string()))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(patch.as_bytes())
+                .map_err(|e| failed(e.to_string()))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| failed(e.to_string()))?;
+        if !output.status.success() {
+            return Err(failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// List untracked, non-ignored files relative to the repository root.
+    fn untracked_files(&self) -> Result<Vec<PathBuf>, VcsError> {
+        let output = self.run_sl_checked(&["status", "--unknown", "--no-status", "--print0"])?;
+        Ok(parse_path_list(&output.stdout))
+    }
 }
 
 impl Vcs for SaplingVcs {
@@ -85,4 +138,29 @@ impl Vcs for SaplingVcs {
 
         Ok("unknown".to_string())
     }
+
+    fn snapshot(&self) -> Result<Snapshot, VcsError> {
+        let whereami = self.run_sl_checked(&["whereami"])?;
+        let commit = String::from_utf8_lossy(&whereami.stdout).trim().to_string();
+
+        // Sapling has no `stash create`; keep tracked changes as a git-style patch
+        let diff = self.run_sl_checked(&["diff", "--git"])?;
+        let pending = Some(String::from_utf8_lossy(&diff.stdout).into_owned())
+            .filter(|patch| !patch.trim().is_empty());
+
+        Ok(Snapshot {
+            commit,
+            pending,
+            untracked: self.untracked_files()?,
+        })
+    }
+
+    fn restore(&self, snapshot: &Snapshot) -> Result<(), VcsError> {
+        // `goto --clean` reverts tracked changes and moves back past any new commits
+        self.run_sl_checked(&["goto", "--clean", &snapshot.commit])?;
+        if let Some(ref patch) = snapshot.pending {
+            self.import_patch(patch)?;
+        }
+        remove_new_untracked(&self.root, &snapshot.untracked, self.untracked_files()?)
+    }
 }
@@ -742,6 +742,7 @@ fn test_compare_valid_files() {
     "elapsed_secs": {"mean": 10.0, "variance": 0.0, "std_dev": 0.0, "min": 10.0, "max": 10.0, "count": 1},
     "total_input_tokens": {"mean": 100.0, "variance": 0.0, "std_dev": 0.0, "min": 100.0, "max": 100.0, "count": 1},
     "total_output_tokens": {"mean": 50.0, "variance": 0.0, "std_dev": 0.0, "min": 50.0, "max": 50.0, "count": 1},
+    "total_cost_usd": {"mean": 0.5, "variance": 0.0, "std_dev": 0.0, "min": 0.5, "max": 0.5, "count": 1},
     "iterations": {"mean": 1.0, "variance": 0.0, "std_dev": 0.0, "min": 1.0, "max": 1.0, "count": 1}
   }
 }"#;
@@ -763,6 +764,7 @@ fn test_compare_valid_files() {
     "elapsed_secs": {"mean": 8.0, "variance": 0.0, "std_dev": 0.0, "min": 8.0, "max": 8.0, "count": 1},
     "total_input_tokens": {"mean": 80.0, "variance": 0.0, "std_dev": 0.0, "min": 80.0, "max": 80.0, "count": 1},
     "total_output_tokens": {"mean": 40.0, "variance": 0.0, "std_dev": 0.0, "min": 40.0, "max": 40.0, "count": 1},
+    "total_cost_usd": {"mean": 0.4, "variance": 0.0, "std_dev": 0.0, "min": 0.4, "max": 0.4, "count": 1},
     "iterations": {"mean": 1.0, "variance": 0.0, "std_dev": 0.0, "min": 1.0, "max": 1.0, "count": 1}
   }
 }"#;
@@ -798,4 +800,9 @@ fn test_compare_valid_files() {
         "Output should contain 'Pass Rate'. stdout:\n{}",
         stdout
     );
+    assert!(
+        stdout.contains("Cost (USD): 0.50 -> 0.40 (^0.10"),
+        "Output should contain the cost delta. stdout:\n{}",
+        stdout
+    );
 }
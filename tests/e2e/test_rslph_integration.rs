@@ -679,3 +679,1689 @@ tui_enabled = false
         progress_content
     );
 }
+
+#[test]
+fn test_rslph_build_resume_continues_iteration_numbering() {
+    let progress_iter1 = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+- [ ] Task 3
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    let progress_iter2 = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [x] Task 2
+- [ ] Task 3
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(progress_iter1)
+        .next_invocation()
+        .respond_with_text(progress_iter2)
+        .build();
+
+    let workspace =
+        workspace_with_tui_disabled(&scenario, "# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n- [ ] Task 3\n");
+
+    // First run stops after one iteration
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--max-iterations")
+        .arg("1")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    assert!(output.status.success(), "first run should succeed");
+    assert!(
+        workspace.file_exists(".rslph/runs/.gitignore"),
+        "runs directory should be created"
+    );
+
+    // Resume picks up at iteration 2
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--resume")
+        .arg("--max-iterations")
+        .arg("2")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph --resume");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "resume should succeed: {}", stderr);
+    assert!(stderr.contains("[JOURNAL] Resuming run"), "stderr: {}", stderr);
+
+    assert_eq!(scenario.invocation_count(), 2);
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("| 2 |"),
+        "iteration log should continue at 2: {}",
+        progress
+    );
+}
+
+#[test]
+fn test_rslph_build_resume_without_journal_fails() {
+    let scenario = ScenarioBuilder::new().respond_with_text("unused").build();
+    let workspace = workspace_with_tui_disabled(&scenario, "# Progress\n\n- [ ] Task 1\n");
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--resume")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph --resume");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("nothing to resume"), "stderr: {}", stderr);
+    assert_eq!(scenario.invocation_count(), 0);
+}
+
+#[test]
+fn test_rslph_build_failed_gate_reverts_checked_task() {
+    let progress_checked = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(progress_checked)
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+verify_gates = true
+verify_test_cmd = "echo 'assertion failed: calc(2+2)'; exit 1"
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [ ] Task 1"),
+        "Task 1 should be reverted: {}",
+        progress
+    );
+    assert!(
+        progress.contains("test gate failed") && progress.contains("assertion failed"),
+        "gate failure should be recorded as an attempt: {}",
+        progress
+    );
+}
+
+#[test]
+fn test_rslph_build_passing_gate_keeps_checked_task() {
+    let progress_checked = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(progress_checked)
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+verify_gates = true
+verify_test_cmd = "true"
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    cmd.output().expect("Failed to run rslph");
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [x] Task 1"),
+        "Task 1 should stay checked: {}",
+        progress
+    );
+}
+
+#[test]
+fn test_rslph_build_rolls_back_failed_iteration() {
+    let progress_checked = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo 'half done' >> src/lib.rs && echo scratch > scratch.txt")
+        .with_execute_tools()
+        .respond_with_text(progress_checked)
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+verify_gates = true
+verify_test_cmd = "exit 1"
+rollback_failed_iterations = true
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n")
+        .with_source_file("src/lib.rs", "pub fn original() {}\n")
+        .build();
+
+    for args in [&["add", "-A"][..], &["commit", "-m", "Initial"][..]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(workspace.path())
+            .output()
+            .expect("git should run");
+    }
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+
+    assert_eq!(workspace.read_file("src/lib.rs"), "pub fn original() {}\n");
+    assert!(
+        !workspace.path().join("scratch.txt").exists(),
+        "new files from the failed iteration should be removed"
+    );
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(progress.contains("- [ ] Task 1"), "progress: {}", progress);
+    assert!(
+        progress.contains("working tree changes rolled back"),
+        "rollback should be noted in Recent Attempts: {}",
+        progress
+    );
+}
+
+const PARALLEL_PROGRESS: &str = r#"# Progress: Parallel
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase A
+
+- [ ] Task A
+
+### Phase B
+
+- [ ] Task B
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+/// Workspace with two independent phases, committed so worktrees can branch from it.
+fn parallel_workspace(
+    scenario: &FakeClaudeHandle,
+    extra_config: &str,
+) -> crate::fixtures::Workspace {
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\n{}",
+        scenario.executable_path.display(),
+        extra_config
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(PARALLEL_PROGRESS)
+        .build();
+
+    for args in [&["add", "-A"][..], &["commit", "-m", "Initial"][..]] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(workspace.path())
+            .output()
+            .expect("git should run");
+    }
+    workspace
+}
+
+/// Test that --parallel runs tasks from different phases in worktrees and merges both.
+#[test]
+fn test_rslph_build_parallel_merges_worktrees() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo a > a.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task A", "- [x] Task A"))
+        .next_invocation()
+        .uses_bash("echo b > b.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task B", "- [x] Task B"))
+        .build();
+    let workspace = parallel_workspace(&scenario, "");
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--parallel")
+        .arg("2")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+
+    assert_eq!(scenario.invocation_count(), 2, "one Claude run per task");
+    assert!(
+        stdout.contains("Reason: All tasks complete"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        workspace.file_exists("a.txt"),
+        "worker A's changes are merged"
+    );
+    assert!(
+        workspace.file_exists("b.txt"),
+        "worker B's changes are merged"
+    );
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(progress.contains("- [x] Task A"), "progress: {}", progress);
+    assert!(progress.contains("- [x] Task B"), "progress: {}", progress);
+    assert!(
+        progress.contains("| 1 |"),
+        "both tasks ran in one iteration: {}",
+        progress
+    );
+}
+
+/// Test that a merge conflict between workers is recorded as an attempt, not lost.
+#[test]
+fn test_rslph_build_parallel_reports_merge_conflict() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo one > shared.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task A", "- [x] Task A"))
+        .next_invocation()
+        .uses_bash("echo two > shared.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task B", "- [x] Task B"))
+        .build();
+    let workspace = parallel_workspace(&scenario, "max_iterations = 1\n");
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--parallel")
+        .arg("2")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert_eq!(
+        progress.matches("- [ ] Task").count(),
+        1,
+        "the conflicting task stays open: {}",
+        progress
+    );
+    assert!(
+        progress.contains("Merge conflict in shared.txt"),
+        "conflict should be recorded in Recent Attempts: {}",
+        progress
+    );
+}
+
+/// Test that pre_commit runs in each worker's worktree and a veto keeps that
+/// task's changes out of the main checkout.
+#[test]
+fn test_rslph_build_parallel_pre_commit_vetoes_worker() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo a > a.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task A", "- [x] Task A"))
+        .next_invocation()
+        .uses_bash("echo b > b.txt")
+        .with_execute_tools()
+        .respond_with_text(&PARALLEL_PROGRESS.replace("- [ ] Task B", "- [x] Task B"))
+        .build();
+    let workspace = parallel_workspace(
+        &scenario,
+        "max_iterations = 1\n\n[hooks]\npre_commit = \"test ! -f b.txt\"\n",
+    );
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--parallel")
+        .arg("2")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+
+    assert!(workspace.file_exists("a.txt"), "worker A passed the hook");
+    assert!(
+        !workspace.file_exists("b.txt"),
+        "worker B's vetoed changes must not be merged"
+    );
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(progress.contains("- [x] Task A"), "progress: {}", progress);
+    assert!(progress.contains("- [ ] Task B"), "progress: {}", progress);
+    assert!(
+        progress.contains("Commit parallel task: Task B")
+            && progress.contains("Vetoed by pre_commit hook: exit code 1"),
+        "the veto should be recorded in Recent Attempts: {}",
+        progress
+    );
+}
+
+/// Test that --parallel refuses to run outside a Git repository.
+#[test]
+fn test_rslph_build_parallel_requires_git() {
+    let scenario = ScenarioBuilder::new().build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .without_git()
+        .with_config(&config_toml)
+        .with_progress_file(PARALLEL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--parallel")
+        .arg("2")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("require a Git repository"),
+        "stderr: {}",
+        stderr
+    );
+    assert_eq!(scenario.invocation_count(), 0);
+}
+
+const STALL_PROGRESS: &str = r#"# Progress: Stall
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [ ] Stuck task
+- [ ] Easy task
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+/// Test that repeated iterations without progress stop the build as stalled.
+#[test]
+fn test_rslph_build_stops_when_stalled() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(STALL_PROGRESS)
+        .next_invocation()
+        .respond_with_text(STALL_PROGRESS)
+        .next_invocation()
+        .respond_with_text(STALL_PROGRESS)
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nstall_threshold = 2\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert_eq!(
+        scenario.invocation_count(),
+        2,
+        "stops at the stall threshold"
+    );
+    assert!(
+        stdout.contains("Reason: Build stalled"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stderr.contains("2 consecutive iteration(s) completed no tasks"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+/// Test that the skip policy blocks the stuck task and moves on.
+#[test]
+fn test_rslph_build_stall_skip_blocks_task() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(STALL_PROGRESS)
+        .next_invocation()
+        .respond_with_text(
+            &STALL_PROGRESS
+                .replace(
+                    "- [ ] Stuck task",
+                    "- [-] Stuck task (blocked: no progress)",
+                )
+                .replace("- [ ] Easy task", "- [x] Easy task"),
+        )
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nstall_threshold = 1\nstall_policy = \"skip\"\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert_eq!(scenario.invocation_count(), 2);
+    assert!(
+        stdout.contains("Reason: All unblocked tasks complete"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("1 task(s) blocked"), "stdout: {}", stdout);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [-] Stuck task (blocked: no progress)"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        progress.contains("- [x] Easy task"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        stderr.contains("[STALL] Marked task blocked: Stuck task"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+const GUARD_PROGRESS: &str = r#"# Progress: Guard
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Done task
+- [ ] Task 2
+- [ ] Task 3
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+/// Claude checks off Task 2 but drops Task 3 and unchecks the done task.
+const GUARD_BAD_RESPONSE: &str = r#"# Progress: Guard
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [ ] Done task
+- [x] Task 2
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+/// Test that the default guardrails keep Claude's progress file and warn.
+#[test]
+fn test_rslph_build_guardrails_warn_by_default() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(GUARD_BAD_RESPONSE)
+        .build();
+    let workspace = workspace_with_tui_disabled(&scenario, GUARD_PROGRESS);
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(progress.contains("- [ ] Done task"), "progress: {}", progress);
+    assert!(!progress.contains("- [ ] Task 3"), "progress: {}", progress);
+    assert!(
+        progress.contains("Guardrail warning: unchecked completed task \"Done task\""),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        stderr.contains("[GUARD] Warning: progress file breaks guardrails"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+/// Test that guardrails = "repair" restores a progress file that lost tasks.
+#[test]
+fn test_rslph_build_guardrails_repair_progress() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(GUARD_BAD_RESPONSE)
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nguardrails = \"repair\"\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(GUARD_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [x] Done task"),
+        "progress: {}",
+        progress
+    );
+    assert!(progress.contains("- [x] Task 2"), "progress: {}", progress);
+    assert!(progress.contains("- [ ] Task 3"), "progress: {}", progress);
+    assert!(
+        progress.contains("Repaired by guardrails: unchecked completed task \"Done task\"; removed task \"Task 3\" from Phase 1"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        stderr.contains("[GUARD] Repaired progress file"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+/// Test that guardrails = "reject" keeps the previous progress file.
+#[test]
+fn test_rslph_build_guardrails_reject_progress() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(GUARD_BAD_RESPONSE)
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nguardrails = \"reject\"\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(GUARD_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [x] Done task"),
+        "progress: {}",
+        progress
+    );
+    assert!(progress.contains("- [ ] Task 2"), "progress: {}", progress);
+    assert!(progress.contains("- [ ] Task 3"), "progress: {}", progress);
+    assert!(
+        progress.contains("Rejected by guardrails:"),
+        "progress: {}",
+        progress
+    );
+}
+
+/// Test that build prompt overrides are honored and rendered as templates.
+#[test]
+fn test_rslph_build_renders_prompt_overrides() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text("# Progress: Test\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### Phase 1\n\n- [ ] Task 1\n")
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nbuild_prompt = \"prompts/build.md\"\nbuild_message_prompt = \"prompts/message.md\"\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress: Test\n\n## Status\n\nIn Progress\n\n## Tasks\n\n### Phase 1\n\n- [ ] Task 1\n")
+        .with_source_file(
+            "prompts/build.md",
+            "CUSTOM SYSTEM iteration {{iteration}} of {{max_iterations}}",
+        )
+        .with_source_file("prompts/message.md", "Work on {{ next_task }} now. {{unknown}}")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("CUSTOM SYSTEM iteration 1 of 20"),
+        "system prompt override should be rendered: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Work on Task 1 now. {{unknown}}"),
+        "message template should be rendered: {}",
+        stderr
+    );
+}
+
+/// Test that a task left unfinished moves up the build model ladder.
+#[test]
+fn test_rslph_build_escalates_model_for_unfinished_task() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(STALL_PROGRESS)
+        .next_invocation()
+        .respond_with_text(STALL_PROGRESS)
+        .next_invocation()
+        .respond_with_text(STALL_PROGRESS)
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nmax_iterations = 3\nstall_threshold = 0\nbuild_model = \"cheap-model\"\nbuild_model_escalation = [\"strong-model\"]\nescalate_after = 2\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert_eq!(scenario.invocation_count(), 3);
+
+    let models: Vec<_> = stderr
+        .lines()
+        .filter(|l| l.contains("Spawning Claude"))
+        .map(|l| {
+            if l.contains("--model strong-model") {
+                "strong"
+            } else if l.contains("--model cheap-model") {
+                "cheap"
+            } else {
+                "none"
+            }
+        })
+        .collect();
+    assert_eq!(
+        models,
+        vec!["cheap", "cheap", "strong"],
+        "stderr: {}",
+        stderr
+    );
+    assert!(
+        stderr
+            .contains("Escalating to strong-model after 2 unfinished iteration(s) on: Stuck task"),
+        "stderr: {}",
+        stderr
+    );
+
+    // The model reported by Claude is recorded in the iteration log
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("No tasks completed (model: claude-opus-4-5-20251101)"),
+        "progress: {}",
+        progress
+    );
+}
+
+/// Test that the notify script runs on the interval and when the build finishes.
+#[test]
+fn test_rslph_build_runs_notify_script() {
+    let one_done = STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task");
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(&one_done)
+        .next_invocation()
+        .respond_with_text(&one_done.replace("- [ ] Easy task", "- [x] Easy task"))
+        .build();
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\nnotify_interval = 1\nnotify_script = 'echo \"$RSLPH_EVENT $RSLPH_TASKS_COMPLETED/$RSLPH_TASKS_TOTAL $RSLPH_REASON\" >> notify.log'\n",
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "stderr: {}", stderr);
+    let log = workspace.read_file("notify.log");
+    let lines: Vec<_> = log.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        vec!["progress 1/2", "done 2/2 All tasks complete"],
+        "stderr: {}",
+        stderr
+    );
+}
+
+/// Build a workspace for hook tests with the given `[hooks]` table.
+fn hooks_workspace(scenario: &FakeClaudeHandle, hooks_toml: &str) -> crate::fixtures::Workspace {
+    let config_toml = format!(
+        "claude_path = \"{}\"\ntui_enabled = false\n\n[hooks]\n{}",
+        scenario.executable_path.display(),
+        hooks_toml
+    );
+    WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file(STALL_PROGRESS)
+        .build()
+}
+
+/// Run `rslph build --once` against a hook test workspace.
+fn run_hooks_build(
+    scenario: &FakeClaudeHandle,
+    workspace: &crate::fixtures::Workspace,
+) -> std::process::Output {
+    let mut cmd = rslph_with_fake_claude_and_config(scenario, workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    cmd.output().expect("Failed to run rslph")
+}
+
+/// Test that a failing pre_iteration hook skips Claude and on_done still runs.
+#[test]
+fn test_rslph_build_pre_iteration_hook_vetoes_iteration() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(STALL_PROGRESS)
+        .build();
+    let workspace = hooks_workspace(
+        &scenario,
+        "pre_iteration = \"echo 'CI is red'; exit 1\"\non_done = \"cat > done.json\"\n",
+    );
+
+    let output = run_hooks_build(&scenario, &workspace);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    assert_eq!(
+        scenario.invocation_count(),
+        0,
+        "Claude should not be spawned"
+    );
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("Vetoed by pre_iteration hook: exit code 1: CI is red"),
+        "progress: {}",
+        progress
+    );
+
+    let done: serde_json::Value =
+        serde_json::from_str(&workspace.read_file("done.json")).expect("valid json");
+    assert_eq!(done["hook"], "on_done");
+    assert_eq!(done["reason"], "single_iteration_complete");
+}
+
+/// Test that a failing post_iteration hook reverts the iteration's tasks.
+#[test]
+fn test_rslph_build_post_iteration_hook_fails_iteration() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task"))
+        .build();
+    let workspace = hooks_workspace(
+        &scenario,
+        "post_iteration = \"cat > payload.json; echo 'missing changelog' >&2; exit 2\"\n",
+    );
+
+    let output = run_hooks_build(&scenario, &workspace);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let payload: serde_json::Value =
+        serde_json::from_str(&workspace.read_file("payload.json")).expect("valid json");
+    assert_eq!(payload["completed"][0], "Stuck task");
+    assert_eq!(payload["task"], "Stuck task");
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [ ] Stuck task"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        progress.contains("post_iteration hook failed: exit code 2: missing changelog"),
+        "progress: {}",
+        progress
+    );
+}
+
+/// Test that a failing pre_commit hook leaves the iteration uncommitted.
+#[test]
+fn test_rslph_build_pre_commit_hook_vetoes_commit() {
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task"))
+        .build();
+    let workspace = hooks_workspace(&scenario, "pre_commit = \"exit 1\"\n");
+    let commits_before = crate::helpers::git_commit_count(&workspace);
+
+    let output = run_hooks_build(&scenario, &workspace);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    assert_eq!(crate::helpers::git_commit_count(&workspace), commits_before);
+    assert!(
+        stderr.contains("Commit vetoed by pre_commit hook"),
+        "stderr: {}",
+        stderr
+    );
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [x] Stuck task"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        progress.contains("Vetoed by pre_commit hook: exit code 1"),
+        "progress: {}",
+        progress
+    );
+}
+
+/// Test that `--events` writes a versioned JSONL stream of build events.
+#[test]
+fn test_rslph_build_writes_event_stream() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo working")
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task"))
+        .build();
+    let workspace = workspace_with_tui_disabled(&scenario, STALL_PROGRESS);
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .arg("--events")
+        .arg("events.jsonl")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let events: Vec<serde_json::Value> = workspace
+        .read_file("events.jsonl")
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each line is JSON"))
+        .collect();
+    assert!(events.iter().all(|e| e["schema_version"] == 1));
+
+    let types: Vec<&str> = events
+        .iter()
+        .map(|e| e["type"].as_str().expect("type"))
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            "build_started",
+            "iteration_started",
+            "tool_use",
+            "token_usage",
+            "commit",
+            "iteration_finished",
+            "done",
+        ],
+        "events: {:?}",
+        events
+    );
+
+    assert_eq!(events[1]["task"], "Stuck task");
+    assert_eq!(events[2]["tool"], "Bash");
+    assert_eq!(events[3]["model"], "claude-opus-4-5-20251101");
+    assert_eq!(events[5]["tasks_completed"], 1);
+    assert_eq!(events[6]["reason"], "single_iteration_complete");
+}
+
+/// Test that `--events -` keeps stdout for events and logs to stderr.
+#[test]
+fn test_rslph_build_events_on_stdout_move_log_to_stderr() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo working")
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task"))
+        .build();
+    let workspace = workspace_with_tui_disabled(&scenario, STALL_PROGRESS);
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .arg("--events")
+        .arg("-")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let types: Vec<String> = stdout
+        .lines()
+        .map(|l| {
+            let event: serde_json::Value = serde_json::from_str(l)
+                .unwrap_or_else(|_| panic!("stdout line is not JSON: {}", l));
+            event["type"].as_str().expect("type").to_string()
+        })
+        .collect();
+    assert_eq!(types.first().map(String::as_str), Some("build_started"));
+    assert_eq!(types.last().map(String::as_str), Some("done"));
+    assert!(stderr.contains("Build started"), "stderr: {}", stderr);
+}
+
+/// Test that each iteration's prompts and raw stream-json output are archived.
+#[test]
+fn test_rslph_build_archives_iteration_transcript() {
+    let scenario = ScenarioBuilder::new()
+        .uses_bash("echo working")
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task"))
+        .build();
+    let workspace = workspace_with_tui_disabled(&scenario, STALL_PROGRESS);
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let runs_dir = workspace.path().join(".rslph/runs");
+    let run_dir = std::fs::read_dir(&runs_dir)
+        .expect("runs dir")
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.is_dir())
+        .expect("run directory");
+    let transcript =
+        std::fs::read_to_string(run_dir.join("iter-001.jsonl")).expect("iteration transcript");
+
+    let lines: Vec<serde_json::Value> = transcript
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each line is JSON"))
+        .collect();
+    assert_eq!(lines[0]["type"], "rslph_input");
+    assert_eq!(lines[0]["iteration"], 1);
+    assert!(lines[0]["system_prompt"]
+        .as_str()
+        .expect("system prompt")
+        .contains("RALPH_DONE"));
+    assert!(lines[0]["user_input"]
+        .as_str()
+        .expect("user input")
+        .contains("Stuck task"));
+    assert!(
+        lines.iter().any(|l| l["type"] == "assistant"),
+        "raw stream lines should follow: {}",
+        transcript
+    );
+    assert!(stderr.contains("[TRANSCRIPT] Saved"), "stderr: {}", stderr);
+}
+
+/// Test that the build instruction names the task whose dependencies are done.
+#[test]
+fn test_rslph_build_instruction_names_ready_task() {
+    let progress = STALL_PROGRESS
+        .replace("- [ ] Stuck task", "- [ ] T1: Stuck task (after T2)")
+        .replace("- [ ] Easy task", "- [ ] T2: Easy task");
+    let scenario = ScenarioBuilder::new()
+        .respond_with_text(&progress.replace("- [ ] T2", "- [x] T2"))
+        .build();
+    let workspace = workspace_with_tui_disabled(&scenario, &progress);
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let run_dir = std::fs::read_dir(workspace.path().join(".rslph/runs"))
+        .expect("runs dir")
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.is_dir())
+        .expect("run directory");
+    let transcript =
+        std::fs::read_to_string(run_dir.join("iter-001.jsonl")).expect("iteration transcript");
+    let input: serde_json::Value =
+        serde_json::from_str(transcript.lines().next().expect("input record")).expect("JSON");
+    assert!(
+        input["user_input"]
+            .as_str()
+            .expect("user input")
+            .contains("the next one that is ready to start: T2: Easy task."),
+        "user input: {}",
+        input["user_input"]
+    );
+}
+
+/// Test that `rslph replay` reports a run directory without transcripts.
+#[test]
+fn test_rslph_replay_requires_transcripts() {
+    let workspace = WorkspaceBuilder::new().build();
+    let run_dir = workspace.path().join(".rslph/runs/20260101-000000");
+    std::fs::create_dir_all(&run_dir).expect("create run dir");
+
+    let output = Command::cargo_bin("rslph")
+        .expect("rslph binary should exist")
+        .arg("replay")
+        .arg(&run_dir)
+        .current_dir(workspace.path())
+        .output()
+        .expect("Failed to run rslph");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Replay failed") && stderr.contains("No transcripts found"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+/// Test that the `command` backend runs a plain-text agent instead of Claude.
+#[test]
+fn test_rslph_build_with_command_backend() {
+    let done = STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task");
+    let agent = format!(
+        "case \"$1\" in *\"Stuck task\"*) ;; *) echo 'prompt missing' >&2; exit 1 ;; esac\ncat <<'PROGRESS'\n{}PROGRESS\n",
+        done
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(
+            "tui_enabled = false\nagent_backend = \"command\"\nagent_cmd = \"sh agent.sh\"\n",
+        )
+        .with_progress_file(STALL_PROGRESS)
+        .with_source_file("agent.sh", &agent)
+        .build();
+
+    let output = Command::cargo_bin("rslph")
+        .expect("rslph binary should exist")
+        .arg("-c")
+        .arg(workspace.path().join(".rslph/config.toml"))
+        .arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path())
+        .output()
+        .expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("Spawning agent: sh -c sh agent.sh"),
+        "stderr: {}",
+        stderr
+    );
+
+    let progress = std::fs::read_to_string(workspace.path().join("PROGRESS.md")).expect("progress");
+    assert!(
+        progress.contains("- [x] Stuck task"),
+        "progress: {}",
+        progress
+    );
+    assert!(
+        progress.contains("- [ ] Easy task"),
+        "progress: {}",
+        progress
+    );
+
+    let run_dir = std::fs::read_dir(workspace.path().join(".rslph/runs"))
+        .expect("runs dir")
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| p.is_dir())
+        .expect("run directory");
+    let transcript =
+        std::fs::read_to_string(run_dir.join("iter-001.jsonl")).expect("iteration transcript");
+    let input: serde_json::Value =
+        serde_json::from_str(transcript.lines().next().expect("input record")).expect("json");
+    assert_eq!(input["backend"], "command");
+    assert!(
+        transcript.contains("# Progress: Stall"),
+        "transcript: {}",
+        transcript
+    );
+}
+
+/// Serve one canned JSON response per request on a local port.
+///
+/// Returns the base URL and a handle yielding each request (head and body).
+fn serve_http_stub(
+    responses: Vec<serde_json::Value>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind stub");
+    let url = format!("http://{}/v1", listener.local_addr().expect("addr"));
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (stream, _) = listener.accept().expect("accept");
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("read header");
+                if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().expect("content length");
+                }
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).expect("read body");
+            requests.push(format!("{}\n{}", head, String::from_utf8_lossy(&body)));
+
+            let body = response.to_string();
+            let mut stream = reader.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .expect("write response");
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// Test that the `http` backend runs the tool loop against a Messages-API endpoint.
+#[test]
+fn test_rslph_build_with_http_backend() {
+    let done = STALL_PROGRESS.replace("- [ ] Stuck task", "- [x] Stuck task");
+    let (url, stub) = serve_http_stub(vec![
+        serde_json::json!({
+            "model": "stub-model",
+            "content": [{"type": "tool_use", "id": "t1", "name": "Write", "input": {"file_path": "notes.txt", "content": "hello"}}],
+            "usage": {"input_tokens": 100, "output_tokens": 10}
+        }),
+        serde_json::json!({
+            "model": "stub-model",
+            "content": [{"type": "text", "text": done}],
+            "usage": {"input_tokens": 150, "output_tokens": 40}
+        }),
+    ]);
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&format!(
+            "tui_enabled = false\nagent_backend = \"http\"\nhttp_base_url = \"{}\"\nhttp_api_key_env = \"STUB_API_KEY\"\nbuild_model = \"stub-model\"\n",
+            url
+        ))
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let output = Command::cargo_bin("rslph")
+        .expect("rslph binary should exist")
+        .arg("-c")
+        .arg(workspace.path().join(".rslph/config.toml"))
+        .arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .arg("--events")
+        .arg("events.jsonl")
+        .env("STUB_API_KEY", "secret")
+        .current_dir(workspace.path())
+        .output()
+        .expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let requests = stub.join().expect("stub thread");
+    assert!(
+        requests[0].starts_with("POST /v1/messages"),
+        "{}",
+        requests[0]
+    );
+    assert!(requests[0].contains("x-api-key: secret"), "{}", requests[0]);
+    assert!(!stderr.contains("secret"), "stderr: {}", stderr);
+    assert!(requests[1].contains("\"tool_result\""), "{}", requests[1]);
+
+    assert_eq!(workspace.read_file("notes.txt"), "hello");
+    assert!(workspace
+        .read_file("PROGRESS.md")
+        .contains("- [x] Stuck task"));
+
+    let events: Vec<serde_json::Value> = workspace
+        .read_file("events.jsonl")
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("each line is JSON"))
+        .collect();
+    let tool = events
+        .iter()
+        .find(|e| e["type"] == "tool_use")
+        .expect("tool_use event");
+    assert_eq!(tool["tool"], "Write");
+    let usage = events
+        .iter()
+        .find(|e| e["type"] == "token_usage")
+        .expect("token_usage event");
+    assert_eq!(usage["input_tokens"], 250);
+    assert_eq!(usage["output_tokens"], 50);
+    assert_eq!(usage["model"], "stub-model");
+}
+
+/// Test converting a progress file to JSON and building from it.
+#[test]
+fn test_rslph_progress_convert_and_json_build() {
+    let workspace = WorkspaceBuilder::new()
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+    let convert = |force: bool| {
+        let mut cmd = Command::cargo_bin("rslph").expect("rslph binary should exist");
+        cmd.args(["progress", "convert", "PROGRESS.md", "progress.json"]);
+        if force {
+            cmd.arg("--force");
+        }
+        cmd.current_dir(workspace.path())
+            .output()
+            .expect("Failed to run rslph")
+    };
+
+    let output = convert(false);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut progress: serde_json::Value =
+        serde_json::from_str(&workspace.read_file("progress.json")).expect("valid JSON");
+    assert_eq!(progress["name"], "Stall");
+    assert_eq!(progress["tasks"][0]["tasks"][1]["description"], "Easy task");
+    assert!(!convert(false).status.success(), "refuses to overwrite");
+    assert!(convert(true).status.success());
+
+    for task in progress["tasks"][0]["tasks"].as_array_mut().expect("tasks") {
+        task["completed"] = serde_json::json!(true);
+    }
+    let reply = format!("```json\n{}\n```", progress);
+    let (url, stub) = serve_http_stub(vec![serde_json::json!({
+        "model": "stub-model",
+        "content": [{"type": "text", "text": reply}],
+        "usage": {"input_tokens": 100, "output_tokens": 40}
+    })]);
+    workspace.write_file(
+        ".rslph/config.toml",
+        &format!(
+            "tui_enabled = false\nagent_backend = \"http\"\nhttp_base_url = \"{}\"\nbuild_model = \"stub-model\"\n",
+            url
+        ),
+    );
+
+    let output = Command::cargo_bin("rslph")
+        .expect("rslph binary should exist")
+        .arg("-c")
+        .arg(workspace.path().join(".rslph/config.toml"))
+        .arg("build")
+        .arg("progress.json")
+        .current_dir(workspace.path())
+        .output()
+        .expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Reason: All tasks complete"),
+        "stdout: {}",
+        stdout
+    );
+
+    let requests = stub.join().expect("stub thread");
+    assert!(
+        requests[0].contains("The progress file is JSON"),
+        "{}",
+        requests[0]
+    );
+    let built: serde_json::Value =
+        serde_json::from_str(&workspace.read_file("progress.json")).expect("valid JSON");
+    assert_eq!(built["tasks"][0]["tasks"][0]["completed"], true);
+    assert_eq!(built["tasks"][0]["tasks"][1]["completed"], true);
+}
+
+/// Test that `rslph status` reports a progress file as text and JSON without
+/// running an agent.
+#[test]
+fn test_rslph_status_reports_progress() {
+    let workspace = WorkspaceBuilder::new()
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+    let status = |args: &[&str]| {
+        Command::cargo_bin("rslph")
+            .expect("rslph binary should exist")
+            .arg("status")
+            .args(args)
+            .current_dir(workspace.path())
+            .output()
+            .expect("Failed to run rslph")
+    };
+
+    let output = status(&["PROGRESS.md"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Plan: Stall\n"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Tasks: 0/2 complete\n"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Next task: Phase 1: Stuck task\n"),
+        "stdout: {}",
+        stdout
+    );
+
+    let output = status(&["PROGRESS.md", "--json"]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("status JSON");
+    assert_eq!(report["total_tasks"], 2);
+    assert_eq!(report["phases"][0]["name"], "Phase 1");
+    assert_eq!(report["next_task"]["description"], "Stuck task");
+
+    let output = status(&["missing.md"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Status failed"));
+}
+
+/// Test that `rslph task` edits the progress file in place and rejects bad
+/// references without touching it.
+#[test]
+fn test_rslph_task_edits_progress_file() {
+    let workspace = WorkspaceBuilder::new()
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+    let task = |args: &[&str]| {
+        Command::cargo_bin("rslph")
+            .expect("rslph binary should exist")
+            .args(["task", "--file", "PROGRESS.md"])
+            .args(args)
+            .current_dir(workspace.path())
+            .output()
+            .expect("Failed to run rslph")
+    };
+
+    let output = task(&["add", "T3: Ship it", "--phase", "1"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Added 1.3 T3: Ship it\n"
+    );
+    assert!(task(&["done", "1.2"]).status.success());
+    assert!(task(&["move", "T3", "1.1"]).status.success());
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(
+        progress.contains("- [ ] T3: Ship it\n- [ ] Stuck task\n- [x] Easy task\n"),
+        "progress: {}",
+        progress
+    );
+
+    let output = task(&["rm", "2.1"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No phase 2: the plan has 1 phase(s)"),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(workspace.read_file("PROGRESS.md"), progress);
+
+    let output = task(&["list"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("  1.3   [x] Easy task\n"));
+}
+
+/// Test that a `rslph task` edit made while an iteration runs is kept, with
+/// the iteration's changes applied on top of it.
+#[test]
+fn test_rslph_build_keeps_task_edit_made_during_iteration() {
+    let rslph = assert_cmd::cargo::cargo_bin("rslph");
+    let scenario = ScenarioBuilder::new()
+        .uses_bash(&format!(
+            "'{}' task --file PROGRESS.md add 'Late task' --phase 1",
+            rslph.display()
+        ))
+        .with_execute_tools()
+        .respond_with_text(&STALL_PROGRESS.replace("- [ ] Easy task", "- [x] Easy task"))
+        .build();
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&format!(
+            "claude_path = \"{}\"\ntui_enabled = false\n",
+            scenario.executable_path.display()
+        ))
+        .with_progress_file(STALL_PROGRESS)
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .arg("--once")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "build should succeed: {}", stderr);
+    assert!(
+        stderr.contains("changed while iteration 1 ran"),
+        "stderr: {}",
+        stderr
+    );
+
+    let progress = workspace.read_file("PROGRESS.md");
+    assert!(progress.contains("- [ ] Late task"), "progress: {}", progress);
+    assert!(progress.contains("- [x] Easy task"), "progress: {}", progress);
+    assert!(
+        progress.contains("| 1 |"),
+        "the iteration should still be logged: {}",
+        progress
+    );
+}
+
+/// Test that `rslph lint` reports problems with positions and `--fix`
+/// repairs the mechanical ones.
+#[test]
+fn test_rslph_lint_reports_and_fixes() {
+    let workspace = WorkspaceBuilder::new()
+        .with_progress_file("# Progress: Lint\n\n## Tasks\n\n- [ ] Orphan task\n")
+        .build();
+    let lint = |fix: bool| {
+        let mut cmd = Command::cargo_bin("rslph").expect("rslph binary should exist");
+        cmd.args(["lint", "PROGRESS.md"]);
+        if fix {
+            cmd.arg("--fix");
+        }
+        cmd.current_dir(workspace.path())
+            .output()
+            .expect("Failed to run rslph")
+    };
+
+    let output = lint(false);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("error[missing-status]"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains(" --> PROGRESS.md:5:1\n"),
+        "stdout: {}",
+        stdout
+    );
+
+    let output = lint(true);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("no problems found"));
+    let progress = rslph::progress::ProgressFile::parse(&workspace.read_file("PROGRESS.md"))
+        .expect("fixed file parses");
+    assert_eq!(progress.status, "In Progress");
+    assert_eq!(progress.tasks[0].tasks[0].description, "Orphan task");
+}
@@ -193,3 +193,173 @@ fn test_fake_claude_tool_use_with_tokens() {
         stdout
     );
 }
+
+/// Test that the build stops cleanly once the token budget is spent.
+#[test]
+fn test_rslph_build_stops_at_token_budget() {
+    let progress_after = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+- [ ] Task 3
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    // Each invocation uses 9,500 tokens; the budget allows only one
+    let scenario = ScenarioBuilder::new()
+        .with_token_usage(5000, 1500, 2000, 1000)
+        .respond_with_text(progress_after)
+        .next_invocation()
+        .with_token_usage(5000, 1500, 2000, 1000)
+        .respond_with_text(progress_after)
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+max_total_tokens = 9000
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n- [ ] Task 3\n")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "build should stop cleanly: {}", stderr);
+    assert!(
+        stdout.contains("Reason: Budget exhausted"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stderr.contains("max_total_tokens = 9000"), "stderr: {}", stderr);
+    assert_eq!(scenario.invocation_count(), 1, "no iteration after the cap");
+}
+
+/// Test that a timed-out iteration's usage counts toward the budget before a retry.
+#[test]
+fn test_rslph_build_counts_timed_out_usage_toward_budget() {
+    // Usage arrives after ~1.6s, the result only after the 2s timeout
+    let scenario = ScenarioBuilder::new()
+        .with_token_usage(5000, 1500, 2000, 1000)
+        .with_delay_ms(800)
+        .respond_with_text("# Progress: Test\n")
+        .next_invocation()
+        .respond_with_text("# Progress: Test\n")
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+iteration_timeout = 2
+max_total_tokens = 9000
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "build should stop cleanly: {}", stderr);
+    assert!(
+        stdout.contains("Reason: Budget exhausted"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stderr.contains("used 9500 tokens"),
+        "partial usage should be logged: {}",
+        stderr
+    );
+    assert_eq!(scenario.invocation_count(), 1, "no retry past the budget");
+}
+
+/// Test that build prices usage with the config's pricing table and reports cost.
+#[test]
+fn test_rslph_build_reports_cost_from_pricing_table() {
+    let progress_after = r#"# Progress: Test
+
+## Status
+
+In Progress
+
+## Tasks
+
+### Phase 1
+
+- [x] Task 1
+- [ ] Task 2
+
+## Testing Strategy
+
+Unit tests.
+"#;
+
+    // 9,500 tokens at $100/M for every kind = $0.95
+    let scenario = ScenarioBuilder::new()
+        .with_token_usage(5000, 1500, 2000, 1000)
+        .respond_with_text(progress_after)
+        .build();
+
+    let config_toml = format!(
+        r#"claude_path = "{}"
+tui_enabled = false
+
+[pricing.claude-opus-4-5]
+input = 100.0
+output = 100.0
+cache_write = 100.0
+cache_read = 100.0
+"#,
+        scenario.executable_path.display()
+    );
+    let workspace = WorkspaceBuilder::new()
+        .with_config(&config_toml)
+        .with_progress_file("# Progress\n\n- [ ] Task 1\n- [ ] Task 2\n")
+        .build();
+
+    let mut cmd = rslph_with_fake_claude_and_config(&scenario, &workspace);
+    cmd.arg("build")
+        .arg("--once")
+        .arg("PROGRESS.md")
+        .current_dir(workspace.path());
+    let output = cmd.output().expect("Failed to run rslph");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "build failed: {}", stderr);
+    assert!(stdout.contains("Cost: $0.95"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Iteration 1: $0.95 (9.5k tokens)"),
+        "stdout: {}",
+        stdout
+    );
+}
@@ -132,7 +132,10 @@ fn test_iteration_navigation() {
     app.update(AppEvent::ClaudeOutput(
         "Iteration 1 message - first iteration content.".to_string(),
     ));
-    app.update(AppEvent::IterationComplete { tasks_done: 1 });
+    app.update(AppEvent::IterationComplete {
+        tasks_done: 1,
+        tasks_blocked: 0,
+    });
 
     // Create iteration 2
     app.update(AppEvent::IterationStart { iteration: 2 });
@@ -242,6 +245,7 @@ fn test_status_bar_displays_tokens() {
         output_tokens: 10900,
         cache_creation_input_tokens: 2100,
         cache_read_input_tokens: 1500,
+        cost_usd: 0.19,
     });
 
     terminal.draw(|frame| render(frame, &mut app, 10)).unwrap();
@@ -283,6 +287,7 @@ fn test_status_bar_large_tokens() {
         output_tokens: 567_890,
         cache_creation_input_tokens: 123_456,
         cache_read_input_tokens: 789_012,
+        cost_usd: 0.0,
     });
 
     terminal.draw(|frame| render(frame, &mut app, 10)).unwrap();
@@ -309,8 +314,12 @@ fn test_token_accumulation_across_iterations() {
         output_tokens: 500,
         cache_creation_input_tokens: 0,
         cache_read_input_tokens: 0,
+        cost_usd: 0.0,
+    });
+    app.update(AppEvent::IterationComplete {
+        tasks_done: 1,
+        tasks_blocked: 0,
     });
-    app.update(AppEvent::IterationComplete { tasks_done: 1 });
 
     // Second iteration with MORE tokens (these ADD to iteration 1)
     app.update(AppEvent::IterationStart { iteration: 2 });
@@ -319,6 +328,7 @@ fn test_token_accumulation_across_iterations() {
         output_tokens: 1300, // Total now: 500 + 1300 = 1800
         cache_creation_input_tokens: 500,
         cache_read_input_tokens: 1200,
+        cost_usd: 0.0,
     });
 
     terminal.draw(|frame| render(frame, &mut app, 10)).unwrap();
@@ -366,6 +376,7 @@ fn test_eval_build_flow_display() {
         output_tokens: 850,
         cache_creation_input_tokens: 200,
         cache_read_input_tokens: 0,
+        cost_usd: 0.0,
     });
 
     terminal.draw(|frame| render(frame, &mut app, 10)).unwrap();
@@ -393,8 +404,12 @@ fn test_eval_multi_iteration_display() {
         output_tokens: 500,
         cache_creation_input_tokens: 100,
         cache_read_input_tokens: 0,
+        cost_usd: 0.0,
+    });
+    app.update(AppEvent::IterationComplete {
+        tasks_done: 0,
+        tasks_blocked: 0,
     });
-    app.update(AppEvent::IterationComplete { tasks_done: 0 });
 
     // Iteration 2: Fix and complete
     app.update(AppEvent::IterationStart { iteration: 2 });
@@ -414,6 +429,7 @@ fn test_eval_multi_iteration_display() {
         output_tokens: 600,
         cache_creation_input_tokens: 0,
         cache_read_input_tokens: 100,
+        cost_usd: 0.0,
     });
 
     // Set task completion state
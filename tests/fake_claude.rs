@@ -150,12 +150,28 @@ fn execute_tools_in_event(event: &StreamEventOutput) {
 }
 
 /// Increment the invocation counter and return the previous value.
+///
+/// A lock file serializes concurrent invocations (e.g. parallel builds).
 fn increment_counter(path: &Path) -> usize {
+    let lock_path = path.with_extension("lock");
+    let mut waited = 0;
+    while fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock_path)
+        .is_err()
+        && waited < 500
+    {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        waited += 1;
+    }
+
     let current = fs::read_to_string(path)
         .ok()
         .and_then(|s| s.trim().parse().ok())
         .unwrap_or(0);
 
     fs::write(path, format!("{}", current + 1)).ok();
+    fs::remove_file(&lock_path).ok();
     current
 }